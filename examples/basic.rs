@@ -3,25 +3,30 @@ use aei_framework::{
     AddRandomNeuronCommand, AddRandomNeuronHandler, AddRandomSynapseCommand,
     AddRandomSynapseHandler, Query, QueryHandler, QueryResult,
 };
-use rand::thread_rng;
+use rand_chacha::ChaCha8Rng;
 
 fn main() {
     env_logger::init();
     let path = std::env::temp_dir().join("aei_example.log");
+    let _ = std::fs::remove_file(&path);
     let store = FileEventStore::new(path);
+    // A fixed seed makes the run, including every identifier, reproducible.
+    let seed = 42;
 
     // Add two neurons to the network.
-    let mut add_neuron = AddRandomNeuronHandler::new(store, thread_rng()).expect("store");
+    let mut add_neuron =
+        AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(store, seed).expect("store");
     let n1 = add_neuron
-        .handle(AddRandomNeuronCommand)
+        .handle(AddRandomNeuronCommand::default())
         .expect("first neuron");
     let n2 = add_neuron
-        .handle(AddRandomNeuronCommand)
+        .handle(AddRandomNeuronCommand::default())
         .expect("second neuron");
 
     // Reuse the same event store to add a synapse between them.
     let store = add_neuron.base.store;
-    let mut add_synapse = AddRandomSynapseHandler::new(store, thread_rng()).expect("store");
+    let mut add_synapse =
+        AddRandomSynapseHandler::<_, ChaCha8Rng>::with_seed(store, seed).expect("store");
     let syn = add_synapse
         .handle(AddRandomSynapseCommand)
         .expect("synapse");
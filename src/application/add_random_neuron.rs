@@ -1,23 +1,36 @@
 //! Command and handler for adding a neuron with random properties.
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use uuid::Uuid;
 
-use super::NetworkHandlerBase;
-use crate::domain::{Activation, Event, RandomNeuronAdded};
-use crate::infrastructure::EventStore;
+use super::{conflict_error, NetworkHandlerBase};
+use crate::domain::{
+    ActivationDistribution, ActivationDistributionError, Event, NeuronRole, RandomNeuronAdded,
+};
+use crate::infrastructure::{AppendError, EventStore};
 
 /// Command requesting the addition of a randomly configured neuron.
-#[derive(Debug, Clone, Copy)]
-pub struct AddRandomNeuronCommand;
+#[derive(Debug, Clone, Default)]
+pub struct AddRandomNeuronCommand {
+    /// Activations the new neuron may receive; defaults to a uniform choice
+    /// over [`Activation::all`](crate::domain::Activation::all).
+    pub activations: ActivationDistribution,
+}
 
 /// Possible errors when adding a random neuron.
 #[derive(Debug, Clone, PartialEq)]
 pub enum AddRandomNeuronError {
+    /// The activation distribution of the command cannot be sampled.
+    InvalidActivationDistribution(ActivationDistributionError),
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(AddRandomNeuronError);
+
 /// Handles [`AddRandomNeuronCommand`], emitting events and updating state.
 pub struct AddRandomNeuronHandler<S: EventStore, R: Rng> {
     /// Shared handler state including store, network and RNG.
@@ -32,27 +45,60 @@ impl<S: EventStore, R: Rng> AddRandomNeuronHandler<S, R> {
         })
     }
 
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
     /// Handles the command and returns the identifier of the created neuron.
-    pub fn handle(&mut self, _cmd: AddRandomNeuronCommand) -> Result<Uuid, AddRandomNeuronError> {
-        let activations = [
-            Activation::Identity,
-            Activation::Sigmoid,
-            Activation::ReLU,
-            Activation::Tanh,
-        ];
-        let base = &mut self.base;
-        let activation = *activations
-            .choose(&mut base.rng)
-            .expect("activation list is non-empty");
-        let neuron_id = Uuid::new_v4();
-        let event = Event::RandomNeuronAdded(RandomNeuronAdded {
+    ///
+    /// The neuron is created with the [`NeuronRole::Hidden`] role and an
+    /// activation drawn from the command's distribution. Its synapses receive
+    /// innovation numbers from [`NetworkHandlerBase::innovations`].
+    ///
+    /// # Errors
+    /// Returns [`AddRandomNeuronError::InvalidActivationDistribution`] if the
+    /// distribution is invalid and [`AddRandomNeuronError::StorageError`] if
+    /// persisting an event fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`AddRandomNeuronError::Conflict`] is returned if that happens again.
+    pub fn handle(&mut self, cmd: AddRandomNeuronCommand) -> Result<Uuid, AddRandomNeuronError> {
+        self.base
+            .run_command(|base| Self::execute(base, cmd.clone()))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: AddRandomNeuronCommand,
+    ) -> Result<Result<Uuid, AddRandomNeuronError>, AppendError<S::Error>> {
+        if let Err(error) = cmd.activations.validate() {
+            return Ok(Err(AddRandomNeuronError::InvalidActivationDistribution(
+                error,
+            )));
+        }
+        let activation = cmd
+            .activations
+            .sample(&mut base.rng, None)
+            .expect("valid distribution has a positive weight");
+        let neuron_id = base.next_id();
+        let mut events = vec![Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id,
             activation,
-        });
-        base.store
-            .append(&event)
-            .map_err(|_| AddRandomNeuronError::StorageError)?;
-        base.network.apply(&event);
+            role: NeuronRole::Hidden,
+            bias: 0.0,
+        })];
 
         // Attach at least one random synapse if other neurons exist.
         let mut others: Vec<Uuid> = base
@@ -67,29 +113,28 @@ impl<S: EventStore, R: Rng> AddRandomNeuronHandler<S, R> {
             others.shuffle(&mut base.rng);
             for target in others.into_iter().take(count) {
                 let weight = base.rng.gen_range(-1.0..=1.0);
-                let syn_id = Uuid::new_v4();
-                let event = if base.rng.gen_bool(0.5) {
-                    Event::SynapseCreated {
-                        id: syn_id,
-                        from: target,
-                        to: neuron_id,
-                        weight,
-                    }
+                let syn_id = base.next_id();
+                let (from, to) = if base.rng.gen_bool(0.5) {
+                    (target, neuron_id)
                 } else {
-                    Event::SynapseCreated {
-                        id: syn_id,
-                        from: neuron_id,
-                        to: target,
-                        weight,
-                    }
+                    (neuron_id, target)
                 };
-                base.store
-                    .append(&event)
-                    .map_err(|_| AddRandomNeuronError::StorageError)?;
-                base.network.apply(&event);
+                events.push(Event::SynapseCreated {
+                    id: syn_id,
+                    from,
+                    to,
+                    weight,
+                    innovation: Some(base.innovations.innovation(from, to)),
+                });
             }
         }
 
-        Ok(neuron_id)
+        // The neuron and its synapses are persisted together, so a conflict
+        // never leaves a partial command in the log.
+        base.append_all(&events)?;
+        for event in &events {
+            base.network.apply(event);
+        }
+        Ok(Ok(neuron_id))
     }
 }
@@ -1,11 +1,11 @@
 //! Command and handler for randomly adding a synapse between two neurons.
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use uuid::Uuid;
 
-use super::NetworkHandlerBase;
+use super::{conflict_error, NetworkHandlerBase};
 use crate::domain::{Event, RandomSynapseAdded};
-use crate::infrastructure::EventStore;
+use crate::infrastructure::{AppendError, EventStore};
 
 /// Command requesting the creation of a random synapse.
 #[derive(Debug, Clone, Copy)]
@@ -20,8 +20,13 @@ pub enum AddRandomSynapseError {
     NoAvailableConnection,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(AddRandomSynapseError);
+
 /// Handles [`AddRandomSynapseCommand`], emitting a [`RandomSynapseAdded`] event.
 pub struct AddRandomSynapseHandler<S: EventStore, R: Rng> {
     /// Shared handler state including store, network and RNG.
@@ -36,12 +41,38 @@ impl<S: EventStore, R: Rng> AddRandomSynapseHandler<S, R> {
         })
     }
 
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
     /// Handles the command and returns the identifier of the created synapse.
-    pub fn handle(&mut self, _cmd: AddRandomSynapseCommand) -> Result<Uuid, AddRandomSynapseError> {
-        let base = &mut self.base;
+    ///
+    /// The synapse receives the innovation number of its connection from
+    /// [`NetworkHandlerBase::innovations`].
+    pub fn handle(&mut self, cmd: AddRandomSynapseCommand) -> Result<Uuid, AddRandomSynapseError> {
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        _cmd: AddRandomSynapseCommand,
+    ) -> Result<Result<Uuid, AddRandomSynapseError>, AppendError<S::Error>> {
         let neuron_ids: Vec<Uuid> = base.network.neurons.keys().copied().collect();
         if neuron_ids.len() < 2 {
-            return Err(AddRandomSynapseError::NotEnoughNeurons);
+            return Ok(Err(AddRandomSynapseError::NotEnoughNeurons));
         }
 
         let mut pairs = Vec::new();
@@ -62,21 +93,20 @@ impl<S: EventStore, R: Rng> AddRandomSynapseHandler<S, R> {
             }
         }
 
-        let (from, to) = *pairs
-            .choose(&mut base.rng)
-            .ok_or(AddRandomSynapseError::NoAvailableConnection)?;
+        let Some(&(from, to)) = pairs.choose(&mut base.rng) else {
+            return Ok(Err(AddRandomSynapseError::NoAvailableConnection));
+        };
         let weight = base.rng.gen_range(-1.0..=1.0);
-        let synapse_id = Uuid::new_v4();
+        let synapse_id = base.next_id();
         let event = Event::RandomSynapseAdded(RandomSynapseAdded {
             synapse_id,
             from,
             to,
             weight,
+            innovation: Some(base.innovations.innovation(from, to)),
         });
-        base.store
-            .append(&event)
-            .map_err(|_| AddRandomSynapseError::StorageError)?;
+        base.append(&event)?;
         base.network.apply(&event);
-        Ok(synapse_id)
+        Ok(Ok(synapse_id))
     }
 }
@@ -1,8 +1,22 @@
 //! Handles write-side commands and persists resulting events.
+//!
+//! Synapses created through [`Command::CreateSynapse`] receive an innovation
+//! number from the handler's [`InnovationTracker`].
+//!
+//! A handler created with [`CommandHandler::from_snapshot`] starts from a
+//! [`Snapshot`] and only replays the events appended after it.
 
+use uuid::Uuid;
+
+use super::Reload;
 use crate::application::Command;
-use crate::domain::{Event, Network, NeuronAdded, NeuronRemoved};
-use crate::infrastructure::EventStore;
+use crate::domain::{
+    Event, InnovationTracker, Network, NeuronAdded, NeuronRemoved, SynapseDisabled, SynapseEnabled,
+};
+use crate::infrastructure::{
+    AppendError, Correlation, EventStore, NetworkSnapshot, Snapshot, SnapshotPolicy, SnapshotStore,
+    Snapshotting, Versions,
+};
 
 /// Processes commands, emitting events and updating the in-memory state.
 pub struct CommandHandler<S: EventStore> {
@@ -10,6 +24,13 @@ pub struct CommandHandler<S: EventStore> {
     pub store: S,
     /// Current network state derived from applied events.
     pub network: Network,
+    /// Tracker numbering the synapses created by the handler.
+    pub innovations: InnovationTracker,
+    /// Number of events in the store that the network reflects, and
+    /// version of the latest snapshot taken or loaded.
+    versions: Versions,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
 }
 
 impl<S: EventStore> CommandHandler<S> {
@@ -17,15 +38,120 @@ impl<S: EventStore> CommandHandler<S> {
     pub fn new(mut store: S) -> Result<Self, S::Error> {
         let events = store.load()?;
         let network = Network::hydrate(&events);
-        Ok(Self { store, network })
+        let innovations = InnovationTracker::from_events(&events);
+        Ok(Self {
+            store,
+            network,
+            innovations,
+            versions: Versions::new(events.len() as u64),
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Starts from `snapshot` and replays the events appended to the store
+    /// after it, see
+    /// [`NetworkHandlerBase::from_snapshot`](super::NetworkHandlerBase::from_snapshot).
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn from_snapshot(
+        mut store: S,
+        snapshot: Option<Snapshot<NetworkSnapshot>>,
+    ) -> Result<Self, S::Error> {
+        let Some(snapshot) = snapshot else {
+            return Self::new(store);
+        };
+        let (state, versions) = Versions::restore(snapshot, |version| store.load_from(version))?;
+        Ok(Self {
+            store,
+            innovations: state.innovations(),
+            network: state.network,
+            versions,
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Captures the network and innovation counter at the current version.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot<NetworkSnapshot> {
+        self.capture()
+    }
+
+    /// Saves a snapshot of the current state to `snapshots`.
+    ///
+    /// # Errors
+    /// Propagates snapshot store errors.
+    pub fn save_snapshot<P: SnapshotStore<NetworkSnapshot>>(
+        &mut self,
+        snapshots: &mut P,
+    ) -> Result<(), P::Error> {
+        self.save(snapshots)
+    }
+
+    /// Saves a snapshot to `snapshots` if `policy` says one is due, and
+    /// returns whether it did.
+    ///
+    /// # Errors
+    /// Propagates snapshot store errors.
+    pub fn snapshot_if_due<P: SnapshotStore<NetworkSnapshot>>(
+        &mut self,
+        snapshots: &mut P,
+        policy: SnapshotPolicy,
+    ) -> Result<bool, P::Error> {
+        self.save_if_due(snapshots, policy)
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`](super::NetworkHandlerBase::correlation_id).
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
+    }
+
+    /// Numbers new synapses with `tracker` instead of the handler's own
+    /// tracker, see
+    /// [`NetworkHandlerBase::share_innovations`](super::NetworkHandlerBase::share_innovations).
+    pub fn share_innovations(&mut self, tracker: &InnovationTracker) {
+        if let Some(last) = self.innovations.peek().checked_sub(1) {
+            tracker.observe(last);
+        }
+        self.innovations = tracker.clone();
     }
 
     /// Handles a command by converting it to an event and applying it.
-    pub fn handle(&mut self, command: Command) -> Result<(), S::Error> {
-        let event = match command {
-            Command::CreateNeuron { id, activation } => Event::NeuronAdded(NeuronAdded {
+    ///
+    /// # Errors
+    /// Returns [`AppendError::StorageError`] if the store fails. If another
+    /// writer appended to the store in the meantime, the handler catches up
+    /// with its events and tries once more, returning
+    /// [`AppendError::Conflict`] if that happens again.
+    pub fn handle(&mut self, command: Command) -> Result<(), AppendError<S::Error>> {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+        self.retry_on_conflict(|handler| handler.execute(&command))
+    }
+
+    /// Appends the event for `command`, unless another writer appended to the
+    /// store since the network was loaded.
+    fn execute(&mut self, command: &Command) -> Result<(), AppendError<S::Error>> {
+        let event = match *command {
+            Command::CreateNeuron {
+                id,
+                activation,
+                role,
+                bias,
+            } => Event::NeuronAdded(NeuronAdded {
                 neuron_id: id,
                 activation,
+                role,
+                bias,
             }),
             Command::RemoveNeuron { id } => Event::NeuronRemoved(NeuronRemoved { neuron_id: id }),
             Command::CreateSynapse {
@@ -38,11 +164,50 @@ impl<S: EventStore> CommandHandler<S> {
                 from,
                 to,
                 weight,
+                innovation: Some(self.innovations.innovation(from, to)),
             },
             Command::RemoveSynapse { id } => Event::SynapseRemoved { id },
+            Command::DisableSynapse { id } => {
+                Event::SynapseDisabled(SynapseDisabled { synapse_id: id })
+            }
+            Command::EnableSynapse { id } => {
+                Event::SynapseEnabled(SynapseEnabled { synapse_id: id })
+            }
         };
-        self.store.append(&event)?;
+        self.versions.current = self
+            .store
+            .append_expected(std::slice::from_ref(&event), self.versions.current)?;
         self.network.apply(&event);
         Ok(())
     }
 }
+
+impl<S: EventStore> Snapshotting for CommandHandler<S> {
+    type State = NetworkSnapshot;
+
+    fn versions(&self) -> Versions {
+        self.versions
+    }
+
+    fn versions_mut(&mut self) -> &mut Versions {
+        &mut self.versions
+    }
+
+    fn state(&self) -> NetworkSnapshot {
+        NetworkSnapshot::capture(&self.network, &self.innovations)
+    }
+}
+
+impl<S: EventStore> Reload for CommandHandler<S> {
+    type Error = S::Error;
+
+    fn reload(&mut self) -> Result<(), S::Error> {
+        let tail = self.store.load_from(self.versions.current)?;
+        for event in &tail {
+            self.network.apply(event);
+        }
+        self.innovations.observe_events(&tail);
+        self.versions.current += tail.len() as u64;
+        Ok(())
+    }
+}
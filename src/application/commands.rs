@@ -1,17 +1,21 @@
 //! Commands describing intent to change the domain state.
 
-use crate::domain::Activation;
+use crate::domain::{Activation, NeuronRole};
 use uuid::Uuid;
 
 /// Write-side operations handled by the [`CommandHandler`].
 #[derive(Debug, Clone)]
 pub enum Command {
-    /// Create a neuron with the specified identifier and activation.
+    /// Create a neuron with the specified identifier, activation, role and bias.
     CreateNeuron {
         /// Identifier of the neuron to create.
         id: Uuid,
         /// Activation function assigned to the neuron.
         activation: Activation,
+        /// Structural role of the neuron.
+        role: NeuronRole,
+        /// Bias added to the weighted input sum of the neuron.
+        bias: f64,
     },
     /// Remove a neuron by its identifier.
     RemoveNeuron {
@@ -27,4 +31,8 @@ pub enum Command {
     },
     /// Delete a synapse by its identifier.
     RemoveSynapse { id: Uuid },
+    /// Exclude a synapse from propagation while keeping its weight.
+    DisableSynapse { id: Uuid },
+    /// Include a disabled synapse in propagation again.
+    EnableSynapse { id: Uuid },
 }
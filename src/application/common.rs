@@ -5,6 +5,38 @@
 //! the store and rebuilds the network so handlers only need to focus on their
 //! specific logic.
 //!
+//! Identifiers of new neurons and synapses are drawn from the RNG through
+//! [`NetworkHandlerBase::next_id`]. A handler created with
+//! [`NetworkHandlerBase::with_seed`] records the run seed as a [`RunSeeded`]
+//! event and seeds its RNG from it, so replaying the same commands with the
+//! same seed writes an identical event log.
+//!
+//! New connections are numbered by the [`InnovationTracker`] of the base,
+//! which continues after the innovations recorded in the log and can be
+//! shared with other handlers through
+//! [`NetworkHandlerBase::share_innovations`].
+//!
+//! Mutation handlers pick the neuron or synapse they modify through
+//! [`NetworkHandlerBase::choose_target`], uniformly unless
+//! [`NetworkHandlerBase::select_targets`] switched the base to a
+//! curiosity-weighted [`TargetSelection`].
+//!
+//! Handlers persist their events through [`NetworkHandlerBase::append`],
+//! which refuses to write if another writer appended to the store since the
+//! network was loaded. The handler then reloads the network with
+//! [`NetworkHandlerBase::reload`] and runs the command once more before
+//! reporting the conflict. Handlers keeping their own state follow the same
+//! pattern through the crate's `Reload` trait.
+//!
+//! Each command gets a new correlation identifier, which stores recording
+//! [`EventEnvelope`](crate::infrastructure::EventEnvelope)s attach to its
+//! events, see [`NetworkHandlerBase::correlation_id`].
+//!
+//! A base created with [`NetworkHandlerBase::from_snapshot`] starts from a
+//! [`Snapshot`] and only replays the events appended after it.
+//! [`NetworkHandlerBase::snapshot_if_due`] takes new snapshots following a
+//! [`SnapshotPolicy`].
+//!
 //! # Examples
 //! ```
 //! use aei_framework::{application::NetworkHandlerBase, FileEventStore};
@@ -16,10 +48,16 @@
 //! let _base = NetworkHandlerBase::new(store, thread_rng())?;
 //! # Ok(()) }
 //! ```
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use uuid::{Builder, Uuid};
 
-use crate::domain::Network;
-use crate::infrastructure::EventStore;
+use crate::domain::{
+    Event, InnovationTracker, Network, RunSeeded, TargetSelection, TargetSelectionError,
+};
+use crate::infrastructure::{
+    AppendError, Correlation, EventStore, NetworkSnapshot, Snapshot, SnapshotPolicy, SnapshotStore,
+    Snapshotting, Versions,
+};
 
 /// Shared state for handlers operating on a [`Network`] with randomness.
 pub struct NetworkHandlerBase<S: EventStore, R: Rng> {
@@ -29,6 +67,16 @@ pub struct NetworkHandlerBase<S: EventStore, R: Rng> {
     pub network: Network,
     /// Random number generator.
     pub rng: R,
+    /// Tracker numbering the connections created by the handler.
+    pub innovations: InnovationTracker,
+    /// Strategy choosing mutation targets, validated by
+    /// [`NetworkHandlerBase::select_targets`].
+    targets: TargetSelection,
+    /// Number of events in the store that the network reflects, and
+    /// version of the latest snapshot taken or loaded.
+    versions: Versions,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
 }
 
 impl<S: EventStore, R: Rng> NetworkHandlerBase<S, R> {
@@ -40,6 +88,394 @@ impl<S: EventStore, R: Rng> NetworkHandlerBase<S, R> {
             store,
             network,
             rng,
+            innovations: InnovationTracker::from_events(&events),
+            targets: TargetSelection::default(),
+            versions: Versions::new(events.len() as u64),
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Starts from `snapshot` and replays the events appended to the store
+    /// after it, or replays the whole store like [`NetworkHandlerBase::new`]
+    /// if there is no snapshot yet.
+    ///
+    /// The snapshot must have been taken from the same store.
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::application::NetworkHandlerBase;
+    /// use aei_framework::{FileEventStore, FileSnapshotStore, SnapshotPolicy, SnapshotStore};
+    /// use rand::thread_rng;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let dir = std::env::temp_dir();
+    /// # let id = uuid::Uuid::new_v4();
+    /// # let (log, snapshot) = (dir.join(format!("{id}.log")), dir.join(format!("{id}.json")));
+    /// let mut snapshots = FileSnapshotStore::new(snapshot.clone());
+    /// let store = FileEventStore::new(log.clone());
+    /// let mut base = NetworkHandlerBase::from_snapshot(store, thread_rng(), snapshots.load_latest()?)?;
+    /// base.snapshot_if_due(&mut snapshots, SnapshotPolicy::EveryEvents(1_000))?;
+    /// # let _ = std::fs::remove_file(log);
+    /// # let _ = std::fs::remove_file(snapshot);
+    /// # Ok(()) }
+    /// ```
+    pub fn from_snapshot(
+        mut store: S,
+        rng: R,
+        snapshot: Option<Snapshot<NetworkSnapshot>>,
+    ) -> Result<Self, S::Error> {
+        let Some(snapshot) = snapshot else {
+            return Self::new(store, rng);
+        };
+        let (state, versions) = Versions::restore(snapshot, |version| store.load_from(version))?;
+        Ok(Self {
+            store,
+            innovations: state.innovations(),
+            network: state.network,
+            rng,
+            targets: TargetSelection::default(),
+            versions,
+            correlation: Correlation::default(),
         })
     }
+
+    /// Captures the network and innovation counter at the current version.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot<NetworkSnapshot> {
+        self.capture()
+    }
+
+    /// Saves a snapshot of the current state to `snapshots`.
+    ///
+    /// # Errors
+    /// Propagates snapshot store errors.
+    pub fn save_snapshot<P: SnapshotStore<NetworkSnapshot>>(
+        &mut self,
+        snapshots: &mut P,
+    ) -> Result<(), P::Error> {
+        self.save(snapshots)
+    }
+
+    /// Saves a snapshot to `snapshots` if `policy` says one is due, and
+    /// returns whether it did. Typically called after each command.
+    ///
+    /// # Errors
+    /// Propagates snapshot store errors.
+    pub fn snapshot_if_due<P: SnapshotStore<NetworkSnapshot>>(
+        &mut self,
+        snapshots: &mut P,
+        policy: SnapshotPolicy,
+    ) -> Result<bool, P::Error> {
+        self.save_if_due(snapshots, policy)
+    }
+
+    /// Returns the number of events in the store that the network reflects.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.versions.current
+    }
+
+    /// Persists `event` unless another writer appended to the store since
+    /// the network was loaded. The caller applies the event to the network.
+    ///
+    /// # Errors
+    /// Returns [`AppendError::Conflict`] if the store has moved on, in which
+    /// case nothing is written, and [`AppendError::StorageError`] if
+    /// persisting the event fails.
+    pub fn append(&mut self, event: &Event) -> Result<(), AppendError<S::Error>> {
+        self.append_all(std::slice::from_ref(event))
+    }
+
+    /// Persists `events` together, or none of them, under the same
+    /// conditions as [`NetworkHandlerBase::append`].
+    ///
+    /// # Errors
+    /// See [`NetworkHandlerBase::append`].
+    pub fn append_all(&mut self, events: &[Event]) -> Result<(), AppendError<S::Error>> {
+        self.versions.current = self.store.append_expected(events, self.versions.current)?;
+        Ok(())
+    }
+
+    /// Brings the network up to date by replaying the events appended to
+    /// the store by other writers since it was loaded.
+    ///
+    /// Innovations recorded by the other writers are reserved in the
+    /// tracker, which stays shared with the same handlers as before.
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn reload(&mut self) -> Result<(), S::Error> {
+        let tail = self.store.load_from(self.versions.current)?;
+        for event in &tail {
+            self.network.apply(event);
+        }
+        self.innovations.observe_events(&tail);
+        self.versions.current += tail.len() as u64;
+        Ok(())
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, if any was handled.
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands, such as the correlation identifier of the command that
+    /// triggered them.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
+    }
+
+    /// Starts a command: its events are correlated by a new identifier.
+    pub(crate) fn begin_command(&mut self) {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+    }
+
+    /// Runs `operation` as one command, see
+    /// [`NetworkHandlerBase::begin_command`] and [`Reload::retry_on_conflict`].
+    pub(crate) fn run_command<T, E>(
+        &mut self,
+        operation: impl FnMut(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: ConflictError<S::Error>,
+    {
+        self.begin_command();
+        self.retry_on_conflict(operation)
+    }
+
+    /// Draws a random version 4 identifier from the handler's RNG.
+    pub fn next_id(&mut self) -> Uuid {
+        Builder::from_random_bytes(self.rng.gen()).into_uuid()
+    }
+
+    /// Numbers new connections with `tracker` instead of the handler's own
+    /// tracker.
+    ///
+    /// The innovations already recorded in the handler's log are reserved in
+    /// `tracker`, so numbers stay unique across every log sharing it.
+    pub fn share_innovations(&mut self, tracker: &InnovationTracker) {
+        if let Some(last) = self.innovations.peek().checked_sub(1) {
+            tracker.observe(last);
+        }
+        self.innovations = tracker.clone();
+    }
+
+    /// Returns the strategy choosing mutation targets.
+    #[must_use]
+    pub fn targets(&self) -> TargetSelection {
+        self.targets
+    }
+
+    /// Sets the strategy choosing mutation targets.
+    ///
+    /// # Errors
+    /// Returns the [`TargetSelectionError`] of an invalid strategy, leaving
+    /// the current one in place.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{
+    ///     FileEventStore, MutateRandomSynapseWeightHandler, TargetSelection,
+    /// };
+    /// use rand::thread_rng;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let store = FileEventStore::new(std::env::temp_dir().join("targets_doc.log"));
+    /// let mut handler = MutateRandomSynapseWeightHandler::new(store, thread_rng())?;
+    /// handler
+    ///     .base
+    ///     .select_targets(TargetSelection::Curiosity { temperature: 0.5 })
+    ///     .unwrap();
+    /// # Ok(()) }
+    /// ```
+    pub fn select_targets(&mut self, targets: TargetSelection) -> Result<(), TargetSelectionError> {
+        targets.validate()?;
+        self.targets = targets;
+        Ok(())
+    }
+
+    /// Picks the target of a mutation among `candidates`, neurons or
+    /// synapses of the network, following [`NetworkHandlerBase::targets`].
+    ///
+    /// Returns `None` if `candidates` is empty.
+    pub fn choose_target(&mut self, candidates: &[Uuid]) -> Option<Uuid> {
+        let scored: Vec<(Uuid, f64)> = candidates
+            .iter()
+            .map(|id| {
+                let score = match self.network.neurons.get(id) {
+                    Some(neuron) => neuron.curiosity_score,
+                    None => self
+                        .network
+                        .synapses
+                        .get(id)
+                        .map_or(0.0, |synapse| synapse.curiosity_score),
+                };
+                (*id, score)
+            })
+            .collect();
+        self.targets.choose(&scored, &mut self.rng)
+    }
+}
+
+impl<S: EventStore, R: Rng> Snapshotting for NetworkHandlerBase<S, R> {
+    type State = NetworkSnapshot;
+
+    fn versions(&self) -> Versions {
+        self.versions
+    }
+
+    fn versions_mut(&mut self) -> &mut Versions {
+        &mut self.versions
+    }
+
+    fn state(&self) -> NetworkSnapshot {
+        NetworkSnapshot::capture(&self.network, &self.innovations)
+    }
+}
+
+impl<S: EventStore, R: Rng> Reload for NetworkHandlerBase<S, R> {
+    type Error = S::Error;
+
+    fn reload(&mut self) -> Result<(), S::Error> {
+        NetworkHandlerBase::reload(self)
+    }
+}
+
+impl<S: EventStore, R: Rng + SeedableRng> NetworkHandlerBase<S, R> {
+    /// Loads events from the store and seeds the RNG for a reproducible run.
+    ///
+    /// An empty log is started with a [`RunSeeded`] event holding `seed`. A
+    /// log that already starts with [`RunSeeded`] keeps its recorded seed and
+    /// `seed` is ignored, so a resumed run stays on the original sequence.
+    /// The RNG is derived from the run seed and the number of events in the
+    /// log, so handlers created one after another on the same log draw
+    /// different values.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{application::NetworkHandlerBase, FileEventStore};
+    /// use rand_chacha::ChaCha8Rng;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("seeded_run_doc.log");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let store = FileEventStore::new(path.clone());
+    /// let mut base = NetworkHandlerBase::<_, ChaCha8Rng>::with_seed(store, 42)?;
+    /// let _id = base.next_id();
+    /// std::fs::remove_file(path)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn with_seed(mut store: S, seed: u64) -> Result<Self, S::Error> {
+        let mut events = store.load()?;
+        let seed = loop {
+            match events.first() {
+                Some(Event::RunSeeded(recorded)) => break recorded.seed,
+                _ if events.is_empty() => {
+                    let event = Event::RunSeeded(RunSeeded { seed });
+                    match store.append_expected(std::slice::from_ref(&event), 0) {
+                        Ok(_) => {
+                            events.push(event);
+                            break seed;
+                        }
+                        // Another writer started the log first: use its seed.
+                        Err(AppendError::Conflict { .. }) => events = store.load()?,
+                        Err(AppendError::StorageError(error)) => return Err(error),
+                    }
+                }
+                _ => break seed,
+            }
+        };
+        let network = Network::hydrate(&events);
+        Ok(Self {
+            store,
+            network,
+            rng: R::seed_from_u64(derive_seed(seed, events.len() as u64)),
+            innovations: InnovationTracker::from_events(&events),
+            targets: TargetSelection::default(),
+            versions: Versions::new(events.len() as u64),
+            correlation: Correlation::default(),
+        })
+    }
+}
+
+/// Mixes the run seed with the log length using the SplitMix64 finalizer.
+fn derive_seed(seed: u64, position: u64) -> u64 {
+    let mut z = seed ^ position.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Handler state that can catch up with the events other writers appended
+/// to its store, so that a command refused with a conflict can run again.
+pub(crate) trait Reload {
+    /// Error of the underlying store.
+    type Error;
+
+    /// Replays the events appended to the store since the state was last
+    /// brought up to date.
+    fn reload(&mut self) -> Result<(), Self::Error>;
+
+    /// Runs `operation`, and runs it once more after a reload if it failed
+    /// with a conflict. A second conflict is returned as is; a failing
+    /// reload is reported as a storage error.
+    fn retry_on_conflict<T, E>(
+        &mut self,
+        mut operation: impl FnMut(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        Self: Sized,
+        E: ConflictError<Self::Error>,
+    {
+        match operation(self) {
+            Err(error) if error.is_conflict() => {
+                self.reload()
+                    .map_err(|error| E::from(AppendError::StorageError(error)))?;
+                operation(self)
+            }
+            result => result,
+        }
+    }
+}
+
+/// Errors of commands whose appends may conflict with another writer.
+pub(crate) trait ConflictError<E>: From<AppendError<E>> {
+    /// Returns `true` if the command was refused because another writer
+    /// appended first.
+    fn is_conflict(&self) -> bool;
+}
+
+impl<E> ConflictError<E> for AppendError<E> {
+    fn is_conflict(&self) -> bool {
+        matches!(self, AppendError::Conflict { .. })
+    }
+}
+
+/// Converts [`AppendError`]s into handler errors with `Conflict` and
+/// `StorageError` variants, the storage error itself being dropped.
+macro_rules! conflict_error {
+    ($($error:ty),+ $(,)?) => {$(
+        impl<E> From<$crate::infrastructure::AppendError<E>> for $error {
+            fn from(error: $crate::infrastructure::AppendError<E>) -> Self {
+                match error {
+                    $crate::infrastructure::AppendError::Conflict { .. } => Self::Conflict,
+                    $crate::infrastructure::AppendError::StorageError(_) => Self::StorageError,
+                }
+            }
+        }
+
+        impl<E> $crate::application::ConflictError<E> for $error {
+            fn is_conflict(&self) -> bool {
+                matches!(self, Self::Conflict)
+            }
+        }
+    )+};
 }
+pub(crate) use conflict_error;
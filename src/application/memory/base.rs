@@ -1,7 +1,11 @@
 //! Shared base for memory command handlers.
 //!
 //! Aggregates a memory event store and the hydrated [`AdaptiveMemory`].
-//! Provides helpers to persist events and prune excess entries.
+//! Provides helpers to persist events and prune excess entries, refusing
+//! to append if another writer appended to the store in the meantime.
+//!
+//! A base created with [`MemoryHandlerBase::from_snapshot`] starts from a
+//! [`Snapshot`] and only replays the events appended after it.
 //!
 //! # Examples
 //! ```
@@ -17,8 +21,12 @@
 
 use uuid::Uuid;
 
-use crate::domain::{AdaptiveMemory, MemoryEvent, MemoryPruned};
-use crate::infrastructure::MemoryEventStore;
+use crate::application::{ConflictError, Reload};
+use crate::domain::{AdaptiveMemory, MemoryEntry, MemoryEvent, MemoryPruned};
+use crate::infrastructure::{
+    AppendError, Correlation, MemoryEventStore, Snapshot, SnapshotPolicy, SnapshotStore,
+    Snapshotting, Versions,
+};
 
 /// Maintains shared state for memory handlers.
 pub struct MemoryHandlerBase<S: MemoryEventStore> {
@@ -26,6 +34,11 @@ pub struct MemoryHandlerBase<S: MemoryEventStore> {
     pub store: S,
     /// Current adaptive memory rebuilt from events.
     pub memory: AdaptiveMemory,
+    /// Number of events in the store that the memory reflects, and version
+    /// of the latest snapshot taken or loaded.
+    versions: Versions,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
 }
 
 impl<S: MemoryEventStore> MemoryHandlerBase<S> {
@@ -40,16 +53,125 @@ impl<S: MemoryEventStore> MemoryHandlerBase<S> {
     pub fn new(mut store: S, max_size: usize) -> Result<Self, S::Error> {
         let events = store.load()?;
         let memory = AdaptiveMemory::hydrate(max_size, &events);
-        Ok(Self { store, memory })
+        Ok(Self {
+            store,
+            memory,
+            versions: Versions::new(events.len() as u64),
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Starts from `snapshot` and replays the events appended to the store
+    /// after it, or replays the whole store like [`MemoryHandlerBase::new`]
+    /// if there is no snapshot yet. The memory keeps `max_size` as its
+    /// capacity, whatever the capacity recorded in the snapshot.
+    ///
+    /// # Errors
+    /// Returns [`MemoryEventStore::Error`] if loading events fails.
+    pub fn from_snapshot(
+        mut store: S,
+        max_size: usize,
+        snapshot: Option<Snapshot<AdaptiveMemory>>,
+    ) -> Result<Self, S::Error> {
+        let Some(snapshot) = snapshot else {
+            return Self::new(store, max_size);
+        };
+        let (mut memory, versions) =
+            Versions::restore(snapshot, |version| store.load_from(version))?;
+        memory.max_size = max_size;
+        Ok(Self {
+            store,
+            memory,
+            versions,
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Captures the memory at the current version.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot<AdaptiveMemory> {
+        self.capture()
+    }
+
+    /// Saves a snapshot of the current memory to `snapshots`.
+    ///
+    /// # Errors
+    /// Propagates snapshot store errors.
+    pub fn save_snapshot<P: SnapshotStore<AdaptiveMemory>>(
+        &mut self,
+        snapshots: &mut P,
+    ) -> Result<(), P::Error> {
+        self.save(snapshots)
+    }
+
+    /// Saves a snapshot to `snapshots` if `policy` says one is due, and
+    /// returns whether it did.
+    ///
+    /// # Errors
+    /// Propagates snapshot store errors.
+    pub fn snapshot_if_due<P: SnapshotStore<AdaptiveMemory>>(
+        &mut self,
+        snapshots: &mut P,
+        policy: SnapshotPolicy,
+    ) -> Result<bool, P::Error> {
+        self.save_if_due(snapshots, policy)
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`](crate::application::NetworkHandlerBase::correlation_id).
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
+    }
+
+    /// Starts a command: its events are correlated by a new identifier.
+    pub(crate) fn begin_command(&mut self) {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+    }
+
+    /// Runs `operation` as one command, retrying it once on the reloaded
+    /// memory if another writer appended to the store in the meantime.
+    pub(crate) fn run_command<T, E>(
+        &mut self,
+        operation: impl FnMut(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        E: ConflictError<S::Error>,
+    {
+        self.begin_command();
+        self.retry_on_conflict(operation)
     }
 
     /// Persists an event and applies it to the memory state.
     ///
     /// # Errors
-    /// Returns [`MemoryEventStore::Error`] if persistence fails.
-    pub fn persist(&mut self, event: &MemoryEvent) -> Result<(), S::Error> {
-        self.store.append(event)?;
-        self.memory.apply(event);
+    /// Returns [`AppendError::Conflict`] if another writer appended to the
+    /// store since the memory was loaded, in which case nothing is written,
+    /// and [`AppendError::StorageError`] if persistence fails.
+    pub fn persist(&mut self, event: &MemoryEvent) -> Result<(), AppendError<S::Error>> {
+        self.persist_all(std::slice::from_ref(event))
+    }
+
+    /// Persists `events` together and applies them to the memory state, see
+    /// [`MemoryHandlerBase::persist`].
+    ///
+    /// # Errors
+    /// Returns [`AppendError::Conflict`] if another writer appended to the
+    /// store since the memory was loaded, in which case nothing is written,
+    /// and [`AppendError::StorageError`] if persistence fails.
+    pub fn persist_all(&mut self, events: &[MemoryEvent]) -> Result<(), AppendError<S::Error>> {
+        self.versions.current = self.store.append_expected(events, self.versions.current)?;
+        for event in events {
+            self.memory.apply(event);
+        }
         Ok(())
     }
 
@@ -58,20 +180,58 @@ impl<S: MemoryEventStore> MemoryHandlerBase<S> {
     /// Returns the identifiers of removed entries.
     ///
     /// # Errors
-    /// Returns [`MemoryEventStore::Error`] if persisting the pruning event fails.
-    pub fn prune(&mut self) -> Result<Vec<Uuid>, S::Error> {
-        if self.memory.entries.len() <= self.memory.max_size {
+    /// Returns [`AppendError::Conflict`] if another writer appended to the
+    /// store since the memory was loaded, and [`AppendError::StorageError`]
+    /// if persisting the pruning event fails.
+    pub fn prune(&mut self) -> Result<Vec<Uuid>, AppendError<S::Error>> {
+        let Some(pruned) = self.pruning(None) else {
             return Ok(Vec::new());
+        };
+        let removed = pruned.removed_entries.clone();
+        self.persist(&MemoryEvent::MemoryPruned(pruned))?;
+        Ok(removed)
+    }
+
+    /// Returns the event removing the lowest scoring entries that exceed the
+    /// capacity once `added` joins the memory, if any do.
+    pub(crate) fn pruning(&self, added: Option<&MemoryEntry>) -> Option<MemoryPruned> {
+        let mut entries: Vec<&MemoryEntry> = self.memory.entries.iter().chain(added).collect();
+        let excess = entries.len().checked_sub(self.memory.max_size)?;
+        if excess == 0 {
+            return None;
         }
-        let excess = self.memory.entries.len() - self.memory.max_size;
-        let mut entries = self.memory.entries.clone();
         entries.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
-        let removed: Vec<Uuid> = entries.iter().take(excess).map(|e| e.id).collect();
-        let event = MemoryEvent::MemoryPruned(MemoryPruned {
-            removed_entries: removed.clone(),
-        });
-        self.store.append(&event)?;
-        self.memory.apply(&event);
-        Ok(removed)
+        Some(MemoryPruned {
+            removed_entries: entries.iter().take(excess).map(|e| e.id).collect(),
+        })
+    }
+}
+
+impl<S: MemoryEventStore> Snapshotting for MemoryHandlerBase<S> {
+    type State = AdaptiveMemory;
+
+    fn versions(&self) -> Versions {
+        self.versions
+    }
+
+    fn versions_mut(&mut self) -> &mut Versions {
+        &mut self.versions
+    }
+
+    fn state(&self) -> AdaptiveMemory {
+        self.memory.clone()
+    }
+}
+
+impl<S: MemoryEventStore> Reload for MemoryHandlerBase<S> {
+    type Error = S::Error;
+
+    fn reload(&mut self) -> Result<(), S::Error> {
+        let tail = self.store.load_from(self.versions.current)?;
+        for event in &tail {
+            self.memory.apply(event);
+        }
+        self.versions.current += tail.len() as u64;
+        Ok(())
     }
 }
@@ -3,6 +3,7 @@
 use chrono::Utc;
 use uuid::Uuid;
 
+use crate::application::conflict_error;
 use crate::domain::{
     MemoryEntry, MemoryEntryAdded, MemoryEntryRemoved, MemoryEvent, MemoryScoreUpdated,
 };
@@ -26,8 +27,13 @@ pub enum AddMemoryEntryError {
     InvalidScore,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the memory
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(AddMemoryEntryError);
+
 impl<S: MemoryEventStore> AddMemoryEntryHandler<S> {
     /// Loads the memory state from the event store.
     pub fn new(store: S, max_size: usize) -> Result<Self, S::Error> {
@@ -48,16 +54,18 @@ impl<S: MemoryEventStore> AddMemoryEntryHandler<S> {
             payload: cmd.payload,
             score: cmd.score,
         };
-        let event = MemoryEvent::MemoryEntryAdded(MemoryEntryAdded {
-            entry: entry.clone(),
-        });
-        self.base
-            .persist(&event)
-            .map_err(|_| AddMemoryEntryError::StorageError)?;
-        self.base
-            .prune()
-            .map_err(|_| AddMemoryEntryError::StorageError)?;
-        Ok(entry.id)
+        let id = entry.id;
+        // The entry and the pruning it causes are appended together, so a
+        // retry never adds the entry twice.
+        self.base.run_command(|base| {
+            let pruned = base.pruning(Some(&entry));
+            let mut events = vec![MemoryEvent::MemoryEntryAdded(MemoryEntryAdded {
+                entry: entry.clone(),
+            })];
+            events.extend(pruned.map(MemoryEvent::MemoryPruned));
+            base.persist_all(&events)
+        })?;
+        Ok(id)
     }
 }
 
@@ -74,8 +82,13 @@ pub enum RemoveMemoryEntryError {
     NotFound,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the memory
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(RemoveMemoryEntryError);
+
 impl<S: MemoryEventStore> RemoveMemoryEntryHandler<S> {
     /// Loads state from the event store.
     pub fn new(store: S, max_size: usize) -> Result<Self, S::Error> {
@@ -86,22 +99,15 @@ impl<S: MemoryEventStore> RemoveMemoryEntryHandler<S> {
 
     /// Handles the command.
     pub fn handle(&mut self, cmd: RemoveMemoryEntryCommand) -> Result<(), RemoveMemoryEntryError> {
-        if !self
-            .base
-            .memory
-            .entries
-            .iter()
-            .any(|e| e.id == cmd.entry_id)
-        {
-            return Err(RemoveMemoryEntryError::NotFound);
-        }
-        let event = MemoryEvent::MemoryEntryRemoved(MemoryEntryRemoved {
-            entry_id: cmd.entry_id,
-        });
-        self.base
-            .persist(&event)
-            .map_err(|_| RemoveMemoryEntryError::StorageError)?;
-        Ok(())
+        self.base.run_command(|base| {
+            if !base.memory.entries.iter().any(|e| e.id == cmd.entry_id) {
+                return Err(RemoveMemoryEntryError::NotFound);
+            }
+            let event = MemoryEvent::MemoryEntryRemoved(MemoryEntryRemoved {
+                entry_id: cmd.entry_id,
+            });
+            Ok(base.persist(&event)?)
+        })
     }
 }
 
@@ -116,8 +122,13 @@ pub struct PruneMemoryHandler<S: MemoryEventStore> {
 pub enum PruneMemoryError {
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the memory
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(PruneMemoryError);
+
 impl<S: MemoryEventStore> PruneMemoryHandler<S> {
     /// Loads state from the event store.
     pub fn new(store: S, max_size: usize) -> Result<Self, S::Error> {
@@ -128,9 +139,7 @@ impl<S: MemoryEventStore> PruneMemoryHandler<S> {
 
     /// Removes lowest scoring entries if capacity is exceeded.
     pub fn handle(&mut self, _cmd: PruneMemoryCommand) -> Result<Vec<Uuid>, PruneMemoryError> {
-        self.base
-            .prune()
-            .map_err(|_| PruneMemoryError::StorageError)
+        Ok(self.base.run_command(MemoryHandlerBase::prune)?)
     }
 }
 
@@ -149,8 +158,13 @@ pub enum UpdateMemoryScoreError {
     InvalidScore,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the memory
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(UpdateMemoryScoreError);
+
 impl<S: MemoryEventStore> UpdateMemoryScoreHandler<S> {
     /// Loads state from the event store.
     pub fn new(store: S, max_size: usize) -> Result<Self, S::Error> {
@@ -164,22 +178,19 @@ impl<S: MemoryEventStore> UpdateMemoryScoreHandler<S> {
         if !(0.0..=1.0).contains(&cmd.new_score) {
             return Err(UpdateMemoryScoreError::InvalidScore);
         }
-        let entry = self
-            .base
-            .memory
-            .entries
-            .iter()
-            .find(|e| e.id == cmd.entry_id)
-            .cloned()
-            .ok_or(UpdateMemoryScoreError::NotFound)?;
-        let event = MemoryEvent::MemoryScoreUpdated(MemoryScoreUpdated {
-            entry_id: cmd.entry_id,
-            old_score: entry.score,
-            new_score: cmd.new_score,
-        });
-        self.base
-            .persist(&event)
-            .map_err(|_| UpdateMemoryScoreError::StorageError)?;
-        Ok(())
+        self.base.run_command(|base| {
+            let entry = base
+                .memory
+                .entries
+                .iter()
+                .find(|e| e.id == cmd.entry_id)
+                .ok_or(UpdateMemoryScoreError::NotFound)?;
+            let event = MemoryEvent::MemoryScoreUpdated(MemoryScoreUpdated {
+                entry_id: cmd.entry_id,
+                old_score: entry.score,
+                new_score: cmd.new_score,
+            });
+            Ok(base.persist(&event)?)
+        })
     }
 }
@@ -7,13 +7,20 @@ mod commands;
 mod common;
 pub mod memory;
 mod mutate_random_neuron_activation;
+mod mutate_random_neuron_bias;
 mod mutate_random_synapse_weight;
+mod mutation_policy;
+mod population;
 mod queries;
 mod query_handler;
 mod recalculate_curiosity_score;
 mod remove_random_neuron;
 mod remove_random_synapse;
+mod set_neuron_bias;
 mod set_synapse_weight;
+mod split_random_synapse;
+mod toggle_random_synapse;
+mod train_network;
 
 pub use add_random_neuron::{AddRandomNeuronCommand, AddRandomNeuronError, AddRandomNeuronHandler};
 pub use add_random_synapse::{
@@ -22,18 +29,31 @@ pub use add_random_synapse::{
 pub use command_handler::CommandHandler;
 pub use commands::Command;
 pub use common::NetworkHandlerBase;
+pub(crate) use common::{conflict_error, ConflictError, Reload};
 pub use mutate_random_neuron_activation::{
     MutateNeuronActivationError, MutateRandomNeuronActivationCommand,
     MutateRandomNeuronActivationHandler,
 };
+pub use mutate_random_neuron_bias::{
+    MutateRandomNeuronBiasCommand, MutateRandomNeuronBiasError, MutateRandomNeuronBiasHandler,
+};
 pub use mutate_random_synapse_weight::{
     MutateRandomSynapseWeightCommand, MutateRandomSynapseWeightError,
     MutateRandomSynapseWeightHandler,
 };
+pub use mutation_policy::{
+    AppliedMutation, Mutation, MutationKind, MutationOperator, MutationPolicy, MutationPolicyError,
+    MutationReport,
+};
+pub use population::{
+    EvolvePopulationCommand, PopulationError, PopulationHandler, PopulationQuery,
+    PopulationQueryHandler, PopulationQueryResult,
+};
 pub use queries::Query;
 pub use query_handler::{QueryHandler, QueryResult};
 pub use recalculate_curiosity_score::{
-    CuriosityScope, RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
+    CuriosityDecay, CuriosityScope, DecayCuriosityScoresCommand, DecayCuriosityScoresError,
+    RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
 };
 pub use remove_random_neuron::{
     RemoveRandomNeuronCommand, RemoveRandomNeuronError, RemoveRandomNeuronHandler,
@@ -41,6 +61,14 @@ pub use remove_random_neuron::{
 pub use remove_random_synapse::{
     RemoveRandomSynapseCommand, RemoveRandomSynapseError, RemoveRandomSynapseHandler,
 };
+pub use set_neuron_bias::{SetNeuronBiasCommand, SetNeuronBiasError, SetNeuronBiasHandler};
 pub use set_synapse_weight::{
     SetSynapseWeightCommand, SetSynapseWeightError, SetSynapseWeightHandler,
 };
+pub use split_random_synapse::{
+    SplitRandomSynapseCommand, SplitRandomSynapseError, SplitRandomSynapseHandler,
+};
+pub use toggle_random_synapse::{
+    ToggleRandomSynapseCommand, ToggleRandomSynapseError, ToggleRandomSynapseHandler,
+};
+pub use train_network::{TrainNetworkCommand, TrainNetworkError, TrainNetworkHandler};
@@ -4,18 +4,24 @@
 //! selected neuron with a different one. The change is persisted as a
 //! [`NeuronActivationMutated`] event and applied to the [`Network`] aggregate.
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{Rng, SeedableRng};
 use uuid::Uuid;
 
-use super::NetworkHandlerBase;
-use crate::domain::{Activation, Event, NeuronActivationMutated};
-use crate::infrastructure::EventStore;
+use super::{conflict_error, NetworkHandlerBase};
+use crate::domain::{
+    ActivationDistribution, ActivationDistributionError, Event, NeuronActivationMutated,
+};
+use crate::infrastructure::{AppendError, EventStore};
 
 /// Command requesting mutation of a random neuron's activation.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Default)]
 pub struct MutateRandomNeuronActivationCommand {
-    /// When true, input and output neurons are excluded from selection.
+    /// When true, neurons with a protected role (input, output or bias) are
+    /// excluded from selection.
     pub exclude_io: bool,
+    /// Activations the neuron may switch to; defaults to a uniform choice
+    /// over [`Activation::all`](crate::domain::Activation::all).
+    pub activations: ActivationDistribution,
 }
 
 /// Errors that can occur while mutating a neuron's activation.
@@ -23,10 +29,20 @@ pub struct MutateRandomNeuronActivationCommand {
 pub enum MutateNeuronActivationError {
     /// No neuron matched the selection criteria.
     NoEligibleNeuron,
+    /// The activation distribution of the command cannot be sampled.
+    InvalidActivationDistribution(ActivationDistributionError),
+    /// The distribution only allows the current activation of the selected
+    /// neuron.
+    NoAlternativeActivation,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(MutateNeuronActivationError);
+
 /// Handles [`MutateRandomNeuronActivationCommand`], emitting and applying
 /// [`NeuronActivationMutated`] events.
 pub struct MutateRandomNeuronActivationHandler<S: EventStore, R: Rng> {
@@ -42,13 +58,31 @@ impl<S: EventStore, R: Rng> MutateRandomNeuronActivationHandler<S, R> {
         })
     }
 
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
     /// Handles the command and returns the identifier of the mutated neuron.
     ///
     /// # Errors
-    /// Returns [`MutateNeuronActivationError::NoEligibleNeuron`] if no neuron
-    /// satisfies the selection criteria and
+    /// Returns [`MutateNeuronActivationError::InvalidActivationDistribution`]
+    /// if the distribution is invalid,
+    /// [`MutateNeuronActivationError::NoEligibleNeuron`] if no neuron
+    /// satisfies the selection criteria,
+    /// [`MutateNeuronActivationError::NoAlternativeActivation`] if the
+    /// distribution offers no activation other than the current one and
     /// [`MutateNeuronActivationError::StorageError`] if persisting the event
     /// fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`MutateNeuronActivationError::Conflict`] is returned if that happens again.
     ///
     /// # Examples
     /// ```
@@ -62,25 +96,41 @@ impl<S: EventStore, R: Rng> MutateRandomNeuronActivationHandler<S, R> {
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let store = FileEventStore::new(PathBuf::from("events.log"));
     /// let mut handler = MutateRandomNeuronActivationHandler::new(store, thread_rng())?;
-    /// let _ = handler.handle(MutateRandomNeuronActivationCommand { exclude_io: false });
+    /// let _ = handler.handle(MutateRandomNeuronActivationCommand::default());
     /// # Ok(()) }
     /// ```
     pub fn handle(
         &mut self,
         cmd: MutateRandomNeuronActivationCommand,
     ) -> Result<Uuid, MutateNeuronActivationError> {
-        let base = &mut self.base;
-        let mut candidates: Vec<Uuid> = base.network.neurons.keys().copied().collect();
-        if cmd.exclude_io {
-            candidates.retain(|id| {
-                let has_in = base.network.synapses.values().any(|s| s.to == *id);
-                let has_out = base.network.synapses.values().any(|s| s.from == *id);
-                has_in && has_out
-            });
+        self.base
+            .run_command(|base| Self::execute(base, cmd.clone()))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: MutateRandomNeuronActivationCommand,
+    ) -> Result<Result<Uuid, MutateNeuronActivationError>, AppendError<S::Error>> {
+        if let Err(error) = cmd.activations.validate() {
+            return Ok(Err(
+                MutateNeuronActivationError::InvalidActivationDistribution(error),
+            ));
         }
-        let neuron_id = *candidates
-            .choose(&mut base.rng)
-            .ok_or(MutateNeuronActivationError::NoEligibleNeuron)?;
+        let candidates: Vec<Uuid> = base
+            .network
+            .neurons
+            .values()
+            .filter(|n| !(cmd.exclude_io && n.role.is_protected()))
+            .map(|n| n.id)
+            .collect();
+        let Some(neuron_id) = base.choose_target(&candidates) else {
+            return Ok(Err(MutateNeuronActivationError::NoEligibleNeuron));
+        };
 
         let old_activation = base
             .network
@@ -88,26 +138,18 @@ impl<S: EventStore, R: Rng> MutateRandomNeuronActivationHandler<S, R> {
             .get(&neuron_id)
             .expect("neuron exists")
             .activation;
-        let mut activations = vec![
-            Activation::Identity,
-            Activation::Sigmoid,
-            Activation::ReLU,
-            Activation::Tanh,
-        ];
-        activations.retain(|a| *a != old_activation);
-        let new_activation = *activations
-            .choose(&mut base.rng)
-            .expect("activation list is non-empty");
+        let Some(new_activation) = cmd.activations.sample(&mut base.rng, Some(old_activation))
+        else {
+            return Ok(Err(MutateNeuronActivationError::NoAlternativeActivation));
+        };
 
         let event = Event::NeuronActivationMutated(NeuronActivationMutated {
             neuron_id,
             old_activation,
             new_activation,
         });
-        base.store
-            .append(&event)
-            .map_err(|_| MutateNeuronActivationError::StorageError)?;
+        base.append(&event)?;
         base.network.apply(&event);
-        Ok(neuron_id)
+        Ok(Ok(neuron_id))
     }
 }
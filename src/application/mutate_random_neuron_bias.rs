@@ -0,0 +1,145 @@
+//! Command and handler for mutating the bias of a random neuron.
+//!
+//! The mutation adds Gaussian noise with a configurable standard deviation to
+//! the existing bias. Only hidden and output neurons are eligible, since the
+//! values of input and bias neurons ignore their bias. A corresponding
+//! [`NeuronBiasMutated`] event is emitted, persisted, and applied to the domain.
+
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use uuid::Uuid;
+
+use super::{conflict_error, NetworkHandlerBase};
+use crate::domain::{Event, NeuronBiasMutated, NeuronRole};
+use crate::infrastructure::{AppendError, EventStore};
+
+/// Command requesting mutation of a random neuron bias.
+#[derive(Debug, Clone, Copy)]
+pub struct MutateRandomNeuronBiasCommand {
+    /// Standard deviation of the Gaussian noise to add to the bias.
+    pub std_dev: f64,
+}
+
+/// Errors that can occur while mutating a neuron bias.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MutateRandomNeuronBiasError {
+    /// The network does not contain any hidden or output neuron.
+    NoNeuronAvailable,
+    /// The provided standard deviation is not valid (must be positive).
+    InvalidStdDev,
+    /// Persisting the event failed.
+    StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
+}
+
+conflict_error!(MutateRandomNeuronBiasError);
+
+/// Handles [`MutateRandomNeuronBiasCommand`], emitting and applying
+/// [`NeuronBiasMutated`] events.
+pub struct MutateRandomNeuronBiasHandler<S: EventStore, R: Rng> {
+    /// Shared handler state including store, network and RNG.
+    pub base: NetworkHandlerBase<S, R>,
+}
+
+impl<S: EventStore, R: Rng> MutateRandomNeuronBiasHandler<S, R> {
+    /// Loads events from the store to initialize the handler.
+    pub fn new(store: S, rng: R) -> Result<Self, S::Error> {
+        Ok(Self {
+            base: NetworkHandlerBase::new(store, rng)?,
+        })
+    }
+
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
+    /// Handles the command and returns the identifier of the mutated neuron.
+    ///
+    /// # Errors
+    /// Returns [`MutateRandomNeuronBiasError::NoNeuronAvailable`] if the
+    /// network contains no hidden or output neuron,
+    /// [`MutateRandomNeuronBiasError::InvalidStdDev`] if the provided standard
+    /// deviation is non-positive, and
+    /// [`MutateRandomNeuronBiasError::StorageError`] if persisting the event
+    /// fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`MutateRandomNeuronBiasError::Conflict`] is returned if that happens again.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{
+    ///     FileEventStore, MutateRandomNeuronBiasCommand, MutateRandomNeuronBiasHandler,
+    /// };
+    /// use rand::thread_rng;
+    /// use std::path::PathBuf;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let store = FileEventStore::new(PathBuf::from("events.log"));
+    /// let mut handler = MutateRandomNeuronBiasHandler::new(store, thread_rng())?;
+    /// let _ = handler.handle(MutateRandomNeuronBiasCommand { std_dev: 0.1 });
+    /// # Ok(()) }
+    /// ```
+    pub fn handle(
+        &mut self,
+        cmd: MutateRandomNeuronBiasCommand,
+    ) -> Result<Uuid, MutateRandomNeuronBiasError> {
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: MutateRandomNeuronBiasCommand,
+    ) -> Result<Result<Uuid, MutateRandomNeuronBiasError>, AppendError<S::Error>> {
+        if cmd.std_dev <= 0.0 {
+            return Ok(Err(MutateRandomNeuronBiasError::InvalidStdDev));
+        }
+        let ids: Vec<Uuid> = base
+            .network
+            .neurons
+            .values()
+            .filter(|n| matches!(n.role, NeuronRole::Hidden | NeuronRole::Output))
+            .map(|n| n.id)
+            .collect();
+        if ids.is_empty() {
+            return Ok(Err(MutateRandomNeuronBiasError::NoNeuronAvailable));
+        }
+        let neuron_id = base
+            .choose_target(&ids)
+            .expect("candidate list is non-empty");
+        let old_bias = base
+            .network
+            .neurons
+            .get(&neuron_id)
+            .expect("neuron exists")
+            .bias;
+        let Ok(normal) = Normal::new(0.0, cmd.std_dev) else {
+            return Ok(Err(MutateRandomNeuronBiasError::InvalidStdDev));
+        };
+        let noise = normal.sample(&mut base.rng);
+        let new_bias = old_bias + noise;
+        let event = Event::NeuronBiasMutated(NeuronBiasMutated {
+            neuron_id,
+            old_bias,
+            new_bias,
+        });
+        base.append(&event)?;
+        base.network.apply(&event);
+        Ok(Ok(neuron_id))
+    }
+}
@@ -4,13 +4,13 @@
 //! the existing weight. A corresponding [`SynapseWeightMutated`] event is
 //! emitted, persisted, and applied to the domain.
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{Rng, SeedableRng};
 use rand_distr::{Distribution, Normal};
 use uuid::Uuid;
 
-use super::NetworkHandlerBase;
+use super::{conflict_error, NetworkHandlerBase};
 use crate::domain::{Event, SynapseWeightMutated};
-use crate::infrastructure::EventStore;
+use crate::infrastructure::{AppendError, EventStore};
 
 /// Command requesting mutation of a random synapse weight.
 #[derive(Debug, Clone, Copy)]
@@ -28,8 +28,13 @@ pub enum MutateRandomSynapseWeightError {
     InvalidStdDev,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(MutateRandomSynapseWeightError);
+
 /// Handles [`MutateRandomSynapseWeightCommand`], emitting and applying
 /// [`SynapseWeightMutated`] events.
 pub struct MutateRandomSynapseWeightHandler<S: EventStore, R: Rng> {
@@ -45,6 +50,17 @@ impl<S: EventStore, R: Rng> MutateRandomSynapseWeightHandler<S, R> {
         })
     }
 
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
     /// Handles the command and returns the identifier of the mutated synapse.
     ///
     /// # Errors
@@ -53,6 +69,9 @@ impl<S: EventStore, R: Rng> MutateRandomSynapseWeightHandler<S, R> {
     /// if the provided standard deviation is non-positive, and
     /// [`MutateRandomSynapseWeightError::StorageError`] if persisting the event
     /// fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`MutateRandomSynapseWeightError::Conflict`] is returned if that happens again.
     ///
     /// # Examples
     /// ```
@@ -72,16 +91,28 @@ impl<S: EventStore, R: Rng> MutateRandomSynapseWeightHandler<S, R> {
         &mut self,
         cmd: MutateRandomSynapseWeightCommand,
     ) -> Result<Uuid, MutateRandomSynapseWeightError> {
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: MutateRandomSynapseWeightCommand,
+    ) -> Result<Result<Uuid, MutateRandomSynapseWeightError>, AppendError<S::Error>> {
         if cmd.std_dev <= 0.0 {
-            return Err(MutateRandomSynapseWeightError::InvalidStdDev);
+            return Ok(Err(MutateRandomSynapseWeightError::InvalidStdDev));
         }
-        let base = &mut self.base;
         let ids: Vec<Uuid> = base.network.synapses.keys().copied().collect();
         if ids.is_empty() {
-            return Err(MutateRandomSynapseWeightError::NoSynapseAvailable);
+            return Ok(Err(MutateRandomSynapseWeightError::NoSynapseAvailable));
         }
-        let synapse_id = *ids
-            .choose(&mut base.rng)
+        let synapse_id = base
+            .choose_target(&ids)
             .expect("candidate list is non-empty");
         let old_weight = base
             .network
@@ -89,8 +120,9 @@ impl<S: EventStore, R: Rng> MutateRandomSynapseWeightHandler<S, R> {
             .get(&synapse_id)
             .expect("synapse exists")
             .weight;
-        let normal = Normal::new(0.0, cmd.std_dev)
-            .map_err(|_| MutateRandomSynapseWeightError::InvalidStdDev)?;
+        let Ok(normal) = Normal::new(0.0, cmd.std_dev) else {
+            return Ok(Err(MutateRandomSynapseWeightError::InvalidStdDev));
+        };
         let noise = normal.sample(&mut base.rng);
         let new_weight = old_weight + noise;
         let event = Event::SynapseWeightMutated(SynapseWeightMutated {
@@ -98,10 +130,8 @@ impl<S: EventStore, R: Rng> MutateRandomSynapseWeightHandler<S, R> {
             old_weight,
             new_weight,
         });
-        base.store
-            .append(&event)
-            .map_err(|_| MutateRandomSynapseWeightError::StorageError)?;
+        base.append(&event)?;
         base.network.apply(&event);
-        Ok(synapse_id)
+        Ok(Ok(synapse_id))
     }
 }
@@ -0,0 +1,299 @@
+//! Composite mutation policy driving the random mutation handlers.
+//!
+//! A [`MutationPolicy`] lists [`Mutation`] operators, each with the
+//! probability of being applied during a mutation step. A step runs against
+//! a single [`NetworkHandlerBase`], so every operator sees the network left
+//! by the previous ones and all of them draw from the same RNG and number
+//! new connections with the same [`InnovationTracker`](crate::domain::InnovationTracker).
+//!
+//! # Examples
+//! ```
+//! use aei_framework::{
+//!     application::NetworkHandlerBase, AddRandomNeuronCommand, FileEventStore,
+//!     MutateRandomSynapseWeightCommand, Mutation, MutationPolicy,
+//! };
+//! use rand::thread_rng;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let path = std::env::temp_dir().join("mutation_policy_doc.log");
+//! # let _ = std::fs::remove_file(&path);
+//! let mut base = NetworkHandlerBase::new(FileEventStore::new(path.clone()), thread_rng())?;
+//! let policy = MutationPolicy::new()
+//!     .with_operator(
+//!         0.3,
+//!         Mutation::MutateSynapseWeight(MutateRandomSynapseWeightCommand { std_dev: 0.1 }),
+//!     )
+//!     .with_operator(0.05, Mutation::AddNeuron(AddRandomNeuronCommand::default()));
+//! let report = policy.step(&mut base).unwrap();
+//! assert!(report.applied.len() + report.skipped.len() <= 2);
+//! # let _ = std::fs::remove_file(path);
+//! # Ok(()) }
+//! ```
+
+use rand::Rng;
+use uuid::Uuid;
+
+use super::{
+    AddRandomNeuronCommand, AddRandomNeuronHandler, AddRandomSynapseCommand,
+    AddRandomSynapseHandler, ConflictError, MutateRandomNeuronActivationCommand,
+    MutateRandomNeuronActivationHandler, MutateRandomNeuronBiasCommand,
+    MutateRandomNeuronBiasHandler, MutateRandomSynapseWeightCommand,
+    MutateRandomSynapseWeightHandler, NetworkHandlerBase, RemoveRandomNeuronCommand,
+    RemoveRandomNeuronHandler, RemoveRandomSynapseCommand, RemoveRandomSynapseHandler,
+    SplitRandomSynapseCommand, SplitRandomSynapseHandler, ToggleRandomSynapseCommand,
+    ToggleRandomSynapseHandler,
+};
+use crate::infrastructure::{AppendError, EventStore};
+
+/// Random mutation carrying the command of the corresponding handler.
+#[derive(Debug, Clone)]
+pub enum Mutation {
+    /// Runs [`AddRandomNeuronHandler`].
+    AddNeuron(AddRandomNeuronCommand),
+    /// Runs [`RemoveRandomNeuronHandler`].
+    RemoveNeuron(RemoveRandomNeuronCommand),
+    /// Runs [`AddRandomSynapseHandler`].
+    AddSynapse(AddRandomSynapseCommand),
+    /// Runs [`RemoveRandomSynapseHandler`].
+    RemoveSynapse(RemoveRandomSynapseCommand),
+    /// Runs [`MutateRandomSynapseWeightHandler`].
+    MutateSynapseWeight(MutateRandomSynapseWeightCommand),
+    /// Runs [`MutateRandomNeuronActivationHandler`].
+    MutateNeuronActivation(MutateRandomNeuronActivationCommand),
+    /// Runs [`MutateRandomNeuronBiasHandler`].
+    MutateNeuronBias(MutateRandomNeuronBiasCommand),
+    /// Runs [`SplitRandomSynapseHandler`].
+    SplitSynapse(SplitRandomSynapseCommand),
+    /// Runs [`ToggleRandomSynapseHandler`].
+    ToggleSynapse(ToggleRandomSynapseCommand),
+}
+
+/// Kind of a [`Mutation`], without its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MutationKind {
+    /// [`Mutation::AddNeuron`].
+    AddNeuron,
+    /// [`Mutation::RemoveNeuron`].
+    RemoveNeuron,
+    /// [`Mutation::AddSynapse`].
+    AddSynapse,
+    /// [`Mutation::RemoveSynapse`].
+    RemoveSynapse,
+    /// [`Mutation::MutateSynapseWeight`].
+    MutateSynapseWeight,
+    /// [`Mutation::MutateNeuronActivation`].
+    MutateNeuronActivation,
+    /// [`Mutation::MutateNeuronBias`].
+    MutateNeuronBias,
+    /// [`Mutation::SplitSynapse`].
+    SplitSynapse,
+    /// [`Mutation::ToggleSynapse`].
+    ToggleSynapse,
+}
+
+impl Mutation {
+    /// Returns `true` if the handler would accept the parameters of the
+    /// command.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Mutation::AddNeuron(cmd) => cmd.activations.validate().is_ok(),
+            Mutation::MutateSynapseWeight(cmd) => cmd.std_dev > 0.0,
+            Mutation::MutateNeuronActivation(cmd) => cmd.activations.validate().is_ok(),
+            Mutation::MutateNeuronBias(cmd) => cmd.std_dev > 0.0,
+            _ => true,
+        }
+    }
+
+    /// Returns the kind of the mutation.
+    #[must_use]
+    pub fn kind(&self) -> MutationKind {
+        match self {
+            Mutation::AddNeuron(_) => MutationKind::AddNeuron,
+            Mutation::RemoveNeuron(_) => MutationKind::RemoveNeuron,
+            Mutation::AddSynapse(_) => MutationKind::AddSynapse,
+            Mutation::RemoveSynapse(_) => MutationKind::RemoveSynapse,
+            Mutation::MutateSynapseWeight(_) => MutationKind::MutateSynapseWeight,
+            Mutation::MutateNeuronActivation(_) => MutationKind::MutateNeuronActivation,
+            Mutation::MutateNeuronBias(_) => MutationKind::MutateNeuronBias,
+            Mutation::SplitSynapse(_) => MutationKind::SplitSynapse,
+            Mutation::ToggleSynapse(_) => MutationKind::ToggleSynapse,
+        }
+    }
+
+    /// Runs the handler of the mutation against `base` and returns the
+    /// identifier it reports, or `None` if the mutation does not apply to
+    /// the network, such as removing a synapse from a network without
+    /// synapses. Invalid parameters also yield `None`; see
+    /// [`Mutation::is_valid`]. Each mutation counts as one command, see
+    /// [`NetworkHandlerBase::correlation_id`].
+    ///
+    /// # Errors
+    /// Returns [`MutationPolicyError::StorageError`] if persisting an event
+    /// fails and [`MutationPolicyError::Conflict`] if another writer appended
+    /// to the store since the network was loaded.
+    pub fn apply<S: EventStore, R: Rng>(
+        &self,
+        base: &mut NetworkHandlerBase<S, R>,
+    ) -> Result<Option<Uuid>, MutationPolicyError> {
+        base.begin_command();
+        match self {
+            Mutation::AddNeuron(cmd) => applied(AddRandomNeuronHandler::execute(base, cmd.clone())),
+            Mutation::RemoveNeuron(cmd) => applied(RemoveRandomNeuronHandler::execute(base, *cmd)),
+            Mutation::AddSynapse(cmd) => applied(AddRandomSynapseHandler::execute(base, *cmd)),
+            Mutation::RemoveSynapse(cmd) => {
+                applied(RemoveRandomSynapseHandler::execute(base, *cmd))
+            }
+            Mutation::MutateSynapseWeight(cmd) => {
+                applied(MutateRandomSynapseWeightHandler::execute(base, *cmd))
+            }
+            Mutation::MutateNeuronActivation(cmd) => applied(
+                MutateRandomNeuronActivationHandler::execute(base, cmd.clone()),
+            ),
+            Mutation::MutateNeuronBias(cmd) => {
+                applied(MutateRandomNeuronBiasHandler::execute(base, *cmd))
+            }
+            Mutation::SplitSynapse(cmd) => applied(SplitRandomSynapseHandler::execute(base, *cmd)),
+            Mutation::ToggleSynapse(cmd) => {
+                applied(ToggleRandomSynapseHandler::execute(base, *cmd))
+            }
+        }
+    }
+}
+
+/// A [`Mutation`] with the probability of applying it during a step.
+#[derive(Debug, Clone)]
+pub struct MutationOperator {
+    /// Probability, in `[0, 1]`, that the mutation is applied.
+    pub probability: f64,
+    /// Mutation to apply.
+    pub mutation: Mutation,
+}
+
+/// Ordered set of mutation operators applied together as one step.
+#[derive(Debug, Clone, Default)]
+pub struct MutationPolicy {
+    /// Operators, drawn and applied in order.
+    pub operators: Vec<MutationOperator>,
+}
+
+/// Errors that may occur while running a mutation step.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MutationPolicyError {
+    /// The probability of the operator at the given index lies outside
+    /// `[0, 1]`.
+    InvalidProbability(usize),
+    /// The mutation of the operator at the given index has invalid
+    /// parameters.
+    InvalidMutation(usize),
+    /// Persisting an event failed.
+    StorageError,
+    /// Another writer appended to the store since the network was loaded;
+    /// see [`NetworkHandlerBase::reload`].
+    Conflict,
+}
+
+/// Operator applied during a mutation step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AppliedMutation {
+    /// Index of the operator in the policy.
+    pub operator: usize,
+    /// Kind of the applied mutation.
+    pub kind: MutationKind,
+    /// Identifier returned by the handler: the created, removed or mutated
+    /// neuron or synapse.
+    pub target: Uuid,
+}
+
+/// Outcome of a mutation step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MutationReport {
+    /// Operators applied, in order.
+    pub applied: Vec<AppliedMutation>,
+    /// Indices of the operators drawn but not applicable to the network.
+    pub skipped: Vec<usize>,
+}
+
+impl MutationPolicy {
+    /// Creates a policy without operators.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends an operator applying `mutation` with the given probability.
+    #[must_use]
+    pub fn with_operator(mut self, probability: f64, mutation: Mutation) -> Self {
+        self.operators.push(MutationOperator {
+            probability,
+            mutation,
+        });
+        self
+    }
+
+    /// Checks the probability and parameters of every operator.
+    ///
+    /// # Errors
+    /// Returns [`MutationPolicyError::InvalidProbability`] or
+    /// [`MutationPolicyError::InvalidMutation`] for the first invalid
+    /// operator.
+    pub fn validate(&self) -> Result<(), MutationPolicyError> {
+        for (index, operator) in self.operators.iter().enumerate() {
+            if !(0.0..=1.0).contains(&operator.probability) {
+                return Err(MutationPolicyError::InvalidProbability(index));
+            }
+            if !operator.mutation.is_valid() {
+                return Err(MutationPolicyError::InvalidMutation(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs one mutation step against `base`.
+    ///
+    /// Each operator is drawn in order with its probability and, when
+    /// drawn, applied to the network left by the previous ones.
+    ///
+    /// # Errors
+    /// Returns [`MutationPolicyError::InvalidProbability`] or
+    /// [`MutationPolicyError::InvalidMutation`] if the policy is invalid, in
+    /// which case nothing is applied, and
+    /// [`MutationPolicyError::StorageError`] if persisting an event fails.
+    /// A step interrupted by [`MutationPolicyError::Conflict`] keeps the
+    /// mutations applied before it; reload the base before the next step.
+    pub fn step<S: EventStore, R: Rng>(
+        &self,
+        base: &mut NetworkHandlerBase<S, R>,
+    ) -> Result<MutationReport, MutationPolicyError> {
+        self.validate()?;
+        let mut report = MutationReport::default();
+        for (index, operator) in self.operators.iter().enumerate() {
+            if !base.rng.gen_bool(operator.probability) {
+                continue;
+            }
+            match operator.mutation.apply(base)? {
+                Some(target) => report.applied.push(AppliedMutation {
+                    operator: index,
+                    kind: operator.mutation.kind(),
+                    target,
+                }),
+                None => report.skipped.push(index),
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Turns the outcome of a mutation handler into the identifier of its
+/// target, or `None` if the mutation does not apply to the network, which is
+/// then left unchanged. Failed appends become
+/// [`MutationPolicyError::Conflict`] or [`MutationPolicyError::StorageError`].
+fn applied<E, M>(
+    result: Result<Result<Uuid, M>, AppendError<E>>,
+) -> Result<Option<Uuid>, MutationPolicyError> {
+    match result {
+        Ok(outcome) => Ok(outcome.ok()),
+        Err(error) if error.is_conflict() => Err(MutationPolicyError::Conflict),
+        Err(_) => Err(MutationPolicyError::StorageError),
+    }
+}
@@ -0,0 +1,541 @@
+//! Generational evolution of a population of networks.
+//!
+//! A [`PopulationHandler`] manages several networks, each rebuilt from its
+//! own [`EventStore`] opened by a user-supplied function from the member's
+//! identifier. Every call to [`PopulationHandler::evolve`] runs one
+//! generation:
+//!
+//! 1. every member is evaluated with the fitness function and a
+//!    [`FitnessEvaluated`] event is recorded;
+//! 2. if the command enables speciation, members are grouped into species
+//!    (see [`PopulationHandler::speciate`]) and parents are selected on
+//!    their shared fitness, the fitness divided by the number of members
+//!    of their species;
+//! 3. the fittest `elitism` members survive unchanged;
+//! 4. the other places are filled with offspring: a parent is chosen with
+//!    the configured [`Selection`] and its event stream is copied into a new
+//!    stream or, with the command's crossover rate, two parents of the same
+//!    species are chosen and the stream produced by [`crossover`] is
+//!    written instead; the
+//!    [`Mutation`]s of the command are then applied to the new stream
+//!    through the random mutation handlers;
+//! 5. the replaced members are retired and a [`GenerationCompleted`] event
+//!    closes the generation.
+//!
+//! Membership and generation boundaries are recorded in a
+//! [`PopulationEventStore`]. The streams of retired members are left in
+//! place, so the genome of every past member can still be replayed. The
+//! handler shares one [`InnovationTracker`] between all offspring and starts
+//! a new innovation generation after each call.
+//!
+//! Members and species are read through [`PopulationQueryHandler`].
+
+mod queries;
+mod query_handler;
+
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use uuid::{Builder, Uuid};
+
+use super::{Mutation, NetworkHandlerBase};
+use crate::domain::{
+    crossover, CompatibilityThresholdAdjusted, CrossoverParent, Event, FitnessEvaluated,
+    GenerationCompleted, InnovationTracker, MemberAdded, MemberRetired, Network, Population,
+    PopulationEvent, Selection, SpeciationConfig, SpeciesAssigned, SpeciesCreated, SpeciesExtinct,
+};
+use crate::infrastructure::{Correlation, EventStore, PopulationEventStore};
+
+pub use queries::PopulationQuery;
+pub use query_handler::{PopulationQueryHandler, PopulationQueryResult};
+
+/// Command requesting one generation of evolution.
+#[derive(Debug, Clone)]
+pub struct EvolvePopulationCommand {
+    /// Scheme choosing the parent of each offspring.
+    pub selection: Selection,
+    /// Number of fittest members kept unchanged in the next generation.
+    pub elitism: usize,
+    /// Probability, in `[0, 1]`, that an offspring is the crossover of two
+    /// selected parents rather than a copy of one.
+    pub crossover_rate: f64,
+    /// Mutations applied, in order, to every offspring. A mutation that
+    /// cannot apply to a genome, such as removing a synapse from a network
+    /// without synapses, is skipped.
+    pub mutations: Vec<Mutation>,
+    /// Speciation run after evaluation, enabling fitness sharing; `None`
+    /// selects parents on raw fitness across the whole population.
+    pub speciation: Option<SpeciationConfig>,
+}
+
+/// Errors that may occur while managing a population.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PopulationError {
+    /// The population does not contain any member.
+    EmptyPopulation,
+    /// A parameter of the selection scheme lies outside its valid range.
+    InvalidSelection,
+    /// More members should be kept than the population contains.
+    InvalidElitism,
+    /// The crossover rate lies outside `[0, 1]`.
+    InvalidCrossoverRate,
+    /// The mutation at the given index of the command has invalid
+    /// parameters.
+    InvalidMutation(usize),
+    /// A speciation parameter lies outside its valid range.
+    InvalidSpeciation,
+    /// The fitness function returned a non-finite value for the member.
+    NonFiniteFitness(Uuid),
+    /// Loading or persisting an event failed.
+    StorageError,
+}
+
+/// Evolves a population of networks, emitting [`PopulationEvent`]s.
+pub struct PopulationHandler<P, S, O, R>
+where
+    P: PopulationEventStore,
+    S: EventStore,
+    O: FnMut(Uuid) -> S,
+    R: Rng,
+{
+    /// Event store recording membership and generation boundaries.
+    pub store: P,
+    /// Current population rebuilt from events.
+    pub population: Population,
+    /// Tracker numbering the connections created in every member.
+    pub innovations: InnovationTracker,
+    /// Random number generator driving selection and mutation.
+    pub rng: R,
+    /// Opens the event stream of a member.
+    open: O,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
+}
+
+impl<P, S, O, R> PopulationHandler<P, S, O, R>
+where
+    P: PopulationEventStore,
+    S: EventStore,
+    O: FnMut(Uuid) -> S,
+    R: Rng,
+{
+    /// Loads the population from `store`, using `open` to access the event
+    /// stream of each member.
+    ///
+    /// # Errors
+    /// Returns [`PopulationError::StorageError`] if loading the population or
+    /// a member stream fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{FileEventStore, FilePopulationEventStore, PopulationHandler};
+    /// use rand::thread_rng;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dir = std::env::temp_dir().join("population_doc");
+    /// std::fs::create_dir_all(&dir)?;
+    /// let store = FilePopulationEventStore::new(dir.join("population.log"));
+    /// let streams = dir.clone();
+    /// let open = move |id| FileEventStore::new(streams.join(format!("{id}.log")));
+    /// let handler = PopulationHandler::new(store, open, thread_rng()).unwrap();
+    /// # let _ = handler;
+    /// # std::fs::remove_dir_all(dir)?;
+    /// # Ok(()) }
+    /// ```
+    pub fn new(mut store: P, open: O, rng: R) -> Result<Self, PopulationError> {
+        let events = store.load().map_err(|_| PopulationError::StorageError)?;
+        let population = Population::hydrate(&events);
+        let mut handler = Self {
+            store,
+            population,
+            innovations: InnovationTracker::new(),
+            rng,
+            open,
+            correlation: Correlation::default(),
+        };
+        let ids: Vec<Uuid> = handler.population.members.keys().copied().collect();
+        for id in ids {
+            let events = handler.events(id)?;
+            handler.innovations.observe_events(&events);
+        }
+        Ok(handler)
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`]. The events of a command
+    /// share it in the population store and in the streams of the members
+    /// it writes; the mutations applied to an offspring are recorded as
+    /// caused by it.
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
+    }
+
+    /// Adds a founder whose event stream starts with `genome`, returning its
+    /// identifier.
+    ///
+    /// # Errors
+    /// Returns [`PopulationError::StorageError`] if persisting an event
+    /// fails.
+    pub fn add_member(&mut self, genome: &[Event]) -> Result<Uuid, PopulationError> {
+        self.begin_command();
+        let member_id = self.next_id();
+        let mut stream = self.stream(member_id);
+        for event in genome {
+            stream
+                .append(event)
+                .map_err(|_| PopulationError::StorageError)?;
+        }
+        self.innovations.observe_events(genome);
+        self.persist(&PopulationEvent::MemberAdded(MemberAdded {
+            member_id,
+            parents: Vec::new(),
+            generation: self.population.generation,
+        }))?;
+        Ok(member_id)
+    }
+
+    /// Rebuilds the network of a member, current or retired, from its event
+    /// stream.
+    ///
+    /// # Errors
+    /// Returns [`PopulationError::StorageError`] if loading the stream fails.
+    pub fn network(&mut self, member_id: Uuid) -> Result<Network, PopulationError> {
+        Ok(Network::hydrate(&self.events(member_id)?))
+    }
+
+    /// Runs one generation and returns the event closing it.
+    ///
+    /// `fitness` scores the network of each member, freshly rebuilt from its
+    /// stream; higher is better.
+    ///
+    /// # Errors
+    /// Returns [`PopulationError::EmptyPopulation`] if there is no member,
+    /// [`PopulationError::InvalidSelection`],
+    /// [`PopulationError::InvalidElitism`],
+    /// [`PopulationError::InvalidCrossoverRate`],
+    /// [`PopulationError::InvalidMutation`] or
+    /// [`PopulationError::InvalidSpeciation`] if the command is invalid,
+    /// [`PopulationError::NonFiniteFitness`] if a fitness is NaN or infinite
+    /// and [`PopulationError::StorageError`] if loading or persisting an
+    /// event fails.
+    pub fn evolve<F>(
+        &mut self,
+        cmd: &EvolvePopulationCommand,
+        mut fitness: F,
+    ) -> Result<GenerationCompleted, PopulationError>
+    where
+        F: FnMut(&mut Network) -> f64,
+    {
+        self.begin_command();
+        let size = self.population.members.len();
+        if size == 0 {
+            return Err(PopulationError::EmptyPopulation);
+        }
+        if !cmd.selection.is_valid() {
+            return Err(PopulationError::InvalidSelection);
+        }
+        if cmd.elitism > size {
+            return Err(PopulationError::InvalidElitism);
+        }
+        if !(0.0..=1.0).contains(&cmd.crossover_rate) {
+            return Err(PopulationError::InvalidCrossoverRate);
+        }
+        if let Some(index) = cmd.mutations.iter().position(|m| !m.is_valid()) {
+            return Err(PopulationError::InvalidMutation(index));
+        }
+        if cmd.speciation.is_some_and(|config| !config.is_valid()) {
+            return Err(PopulationError::InvalidSpeciation);
+        }
+        let generation = self.population.generation;
+
+        let ids: Vec<Uuid> = self.population.members.keys().copied().collect();
+        let mut ranked = Vec::with_capacity(size);
+        for member_id in ids {
+            let score = fitness(&mut self.network(member_id)?);
+            if !score.is_finite() {
+                return Err(PopulationError::NonFiniteFitness(member_id));
+            }
+            ranked.push((member_id, score));
+        }
+        for (member_id, score) in &ranked {
+            self.persist(&PopulationEvent::FitnessEvaluated(FitnessEvaluated {
+                member_id: *member_id,
+                generation,
+                fitness: *score,
+            }))?;
+        }
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let eligible = match &cmd.speciation {
+            Some(config) => {
+                self.assign_species(config)?;
+                self.shared_fitness(&ranked)
+            }
+            None => ranked.clone(),
+        };
+
+        let mut offspring = Vec::with_capacity(size - cmd.elitism);
+        for _ in cmd.elitism..size {
+            let first = self.select(cmd.selection, &eligible);
+            let mut parents = vec![first];
+            if self.rng.gen_bool(cmd.crossover_rate) {
+                let species = self.population.members[&first].species;
+                let mates: Vec<(Uuid, f64)> = eligible
+                    .iter()
+                    .filter(|(id, _)| self.population.members[id].species == species)
+                    .copied()
+                    .collect();
+                parents.push(self.select(cmd.selection, &mates));
+            }
+            let child = self.breed(&parents, &ranked, &cmd.mutations)?;
+            offspring.push((child, parents));
+        }
+        for (member_id, _) in &ranked[cmd.elitism..] {
+            self.persist(&PopulationEvent::MemberRetired(MemberRetired {
+                member_id: *member_id,
+                generation,
+            }))?;
+        }
+        for (member_id, parents) in offspring {
+            self.persist(&PopulationEvent::MemberAdded(MemberAdded {
+                member_id,
+                parents,
+                generation: generation + 1,
+            }))?;
+        }
+
+        let completed = GenerationCompleted {
+            generation,
+            best_member_id: ranked[0].0,
+            best_fitness: ranked[0].1,
+            mean_fitness: ranked.iter().map(|(_, score)| score).sum::<f64>() / size as f64,
+        };
+        self.persist(&PopulationEvent::GenerationCompleted(completed.clone()))?;
+        self.innovations.next_generation();
+        Ok(completed)
+    }
+
+    /// Groups the current members into species and returns the number of
+    /// living species.
+    ///
+    /// Members are visited in identifier order and join the first species,
+    /// in creation order, whose representative lies closer than the
+    /// compatibility threshold; a member compatible with no species founds a
+    /// new one and represents it until the species dies out. Species left
+    /// without members become extinct. The threshold is the last one
+    /// recorded by a [`CompatibilityThresholdAdjusted`] event, or
+    /// `config.threshold` before any adjustment, and is moved towards
+    /// `config.target_species` afterwards.
+    ///
+    /// # Errors
+    /// Returns [`PopulationError::InvalidSpeciation`] if `config` is invalid
+    /// and [`PopulationError::StorageError`] if loading or persisting an
+    /// event fails.
+    pub fn speciate(&mut self, config: &SpeciationConfig) -> Result<usize, PopulationError> {
+        self.begin_command();
+        self.assign_species(config)
+    }
+
+    /// Runs [`PopulationHandler::speciate`] as part of the command in
+    /// progress.
+    fn assign_species(&mut self, config: &SpeciationConfig) -> Result<usize, PopulationError> {
+        if !config.is_valid() {
+            return Err(PopulationError::InvalidSpeciation);
+        }
+        let generation = self.population.generation;
+        let threshold = self
+            .population
+            .compatibility_threshold
+            .unwrap_or(config.threshold);
+
+        let species: Vec<(u64, Uuid)> = self
+            .population
+            .species
+            .values()
+            .map(|s| (s.id, s.representative_id))
+            .collect();
+        let mut representatives = Vec::with_capacity(species.len());
+        for (species_id, representative_id) in species {
+            representatives.push((species_id, self.network(representative_id)?));
+        }
+        let mut sizes: BTreeMap<u64, usize> =
+            representatives.iter().map(|(id, _)| (*id, 0)).collect();
+
+        let ids: Vec<Uuid> = self.population.members.keys().copied().collect();
+        for member_id in ids {
+            let genome = self.network(member_id)?;
+            let compatible = representatives.iter().find_map(|(species_id, rep)| {
+                let distance = config.coefficients.distance(rep, &genome);
+                (distance < threshold).then_some((*species_id, distance))
+            });
+            let (species_id, distance) = match compatible {
+                Some(found) => found,
+                None => {
+                    let species_id = self.population.next_species_id;
+                    self.persist(&PopulationEvent::SpeciesCreated(SpeciesCreated {
+                        species_id,
+                        representative_id: member_id,
+                        generation,
+                    }))?;
+                    representatives.push((species_id, genome));
+                    (species_id, 0.0)
+                }
+            };
+            self.persist(&PopulationEvent::SpeciesAssigned(SpeciesAssigned {
+                member_id,
+                species_id,
+                generation,
+                distance,
+            }))?;
+            *sizes.entry(species_id).or_default() += 1;
+        }
+        for (species_id, _) in sizes.iter().filter(|(_, size)| **size == 0) {
+            self.persist(&PopulationEvent::SpeciesExtinct(SpeciesExtinct {
+                species_id: *species_id,
+                generation,
+            }))?;
+        }
+
+        let count = self.population.species.len();
+        let adjusted = config.adjust(threshold, count);
+        if adjusted != threshold {
+            self.persist(&PopulationEvent::CompatibilityThresholdAdjusted(
+                CompatibilityThresholdAdjusted {
+                    generation,
+                    old_threshold: threshold,
+                    new_threshold: adjusted,
+                },
+            ))?;
+        }
+        Ok(count)
+    }
+
+    /// Returns the ranked members with their fitness divided by the size of
+    /// their species, sorted from the highest shared fitness. Negative
+    /// fitness is first shifted so that the least fit member scores zero.
+    fn shared_fitness(&self, ranked: &[(Uuid, f64)]) -> Vec<(Uuid, f64)> {
+        let offset = ranked.last().map_or(0.0, |(_, fitness)| fitness.min(0.0));
+        let mut sizes: BTreeMap<Option<u64>, usize> = BTreeMap::new();
+        for member in self.population.members.values() {
+            *sizes.entry(member.species).or_default() += 1;
+        }
+        let mut shared: Vec<(Uuid, f64)> = ranked
+            .iter()
+            .map(|(id, fitness)| {
+                let species = self.population.members[id].species;
+                let size = species.map_or(1, |_| sizes[&species]);
+                (*id, (fitness - offset) / size as f64)
+            })
+            .collect();
+        shared.sort_by(|a, b| b.1.total_cmp(&a.1));
+        shared
+    }
+
+    /// Selects a parent among the ranked members.
+    fn select(&mut self, selection: Selection, ranked: &[(Uuid, f64)]) -> Uuid {
+        selection
+            .select(ranked, &mut self.rng)
+            .expect("population is not empty")
+    }
+
+    /// Writes the genome of a child of `parents` to a new stream and mutates
+    /// it, returning the identifier of the child.
+    ///
+    /// A single parent is copied; two parents are combined by [`crossover`]
+    /// using the fitness found in `ranked`.
+    fn breed(
+        &mut self,
+        parents: &[Uuid],
+        ranked: &[(Uuid, f64)],
+        mutations: &[Mutation],
+    ) -> Result<Uuid, PopulationError> {
+        let genome = match *parents {
+            [first, second] => {
+                let fitness = |id: Uuid| {
+                    ranked
+                        .iter()
+                        .find(|(member, _)| *member == id)
+                        .map_or(f64::MIN, |(_, fitness)| *fitness)
+                };
+                let (a, b) = (self.network(first)?, self.network(second)?);
+                crossover(
+                    CrossoverParent {
+                        id: first,
+                        network: &a,
+                        fitness: fitness(first),
+                    },
+                    CrossoverParent {
+                        id: second,
+                        network: &b,
+                        fitness: fitness(second),
+                    },
+                    &mut self.rng,
+                )
+            }
+            _ => self.events(parents[0])?,
+        };
+        let child = self.next_id();
+        let mut stream = self.stream(child);
+        for event in &genome {
+            stream
+                .append(event)
+                .map_err(|_| PopulationError::StorageError)?;
+        }
+        let mut base = NetworkHandlerBase::new(stream, &mut self.rng)
+            .map_err(|_| PopulationError::StorageError)?;
+        base.share_innovations(&self.innovations);
+        base.set_causation(self.correlation.correlation_id);
+        for mutation in mutations {
+            mutation
+                .apply(&mut base)
+                .map_err(|_| PopulationError::StorageError)?;
+        }
+        Ok(child)
+    }
+
+    /// Starts a new command, whose events share a fresh correlation
+    /// identifier.
+    fn begin_command(&mut self) {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+    }
+
+    /// Opens the event stream of a member for writing the events of the
+    /// command in progress.
+    fn stream(&mut self, member_id: Uuid) -> S {
+        let mut stream = (self.open)(member_id);
+        stream.set_correlation(
+            self.correlation.correlation_id,
+            self.correlation.causation_id,
+        );
+        stream
+    }
+
+    /// Loads the event stream of a member.
+    fn events(&mut self, member_id: Uuid) -> Result<Vec<Event>, PopulationError> {
+        (self.open)(member_id)
+            .load()
+            .map_err(|_| PopulationError::StorageError)
+    }
+
+    /// Persists a population event and applies it to the population state.
+    fn persist(&mut self, event: &PopulationEvent) -> Result<(), PopulationError> {
+        self.store
+            .append(event)
+            .map_err(|_| PopulationError::StorageError)?;
+        self.population.apply(event);
+        Ok(())
+    }
+
+    /// Draws a random version 4 identifier from the handler's RNG.
+    fn next_id(&mut self) -> Uuid {
+        Builder::from_random_bytes(self.rng.gen()).into_uuid()
+    }
+}
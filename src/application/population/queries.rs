@@ -0,0 +1,18 @@
+//! Queries against the population projection.
+
+use uuid::Uuid;
+
+/// Read-side population queries.
+#[derive(Debug, Clone)]
+pub enum PopulationQuery {
+    /// Retrieve all current members.
+    ListMembers,
+    /// Retrieve a single member by identifier.
+    GetMember { id: Uuid },
+    /// Retrieve all living species.
+    ListSpecies,
+    /// Retrieve the members of a species.
+    ListSpeciesMembers { species_id: u64 },
+    /// Retrieve the species a member belongs to.
+    GetMemberSpecies { id: Uuid },
+}
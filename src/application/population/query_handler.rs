@@ -0,0 +1,53 @@
+//! Handles read-side population queries using the projection.
+
+use crate::domain::{Member, Species};
+use crate::infrastructure::projection::PopulationProjection;
+
+use super::queries::PopulationQuery;
+
+/// Result of executing a [`PopulationQuery`].
+pub enum PopulationQueryResult<'a> {
+    /// Current members.
+    Members(Vec<&'a Member>),
+    /// Single member lookup.
+    Member(Option<&'a Member>),
+    /// Living species.
+    Species(Vec<&'a Species>),
+    /// Members of a species.
+    SpeciesMembers(Vec<&'a Member>),
+    /// Species of a member.
+    MemberSpecies(Option<&'a Species>),
+}
+
+/// Provides read-only access to the population.
+pub struct PopulationQueryHandler<'a> {
+    projection: &'a PopulationProjection,
+}
+
+impl<'a> PopulationQueryHandler<'a> {
+    /// Creates a new query handler from the projection.
+    pub fn new(projection: &'a PopulationProjection) -> Self {
+        Self { projection }
+    }
+
+    /// Executes a population query.
+    pub fn handle(&self, query: PopulationQuery) -> PopulationQueryResult<'a> {
+        match query {
+            PopulationQuery::ListMembers => {
+                PopulationQueryResult::Members(self.projection.members())
+            }
+            PopulationQuery::GetMember { id } => {
+                PopulationQueryResult::Member(self.projection.member(id))
+            }
+            PopulationQuery::ListSpecies => {
+                PopulationQueryResult::Species(self.projection.species())
+            }
+            PopulationQuery::ListSpeciesMembers { species_id } => {
+                PopulationQueryResult::SpeciesMembers(self.projection.species_members(species_id))
+            }
+            PopulationQuery::GetMemberSpecies { id } => {
+                PopulationQueryResult::MemberSpecies(self.projection.member_species(id))
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Read-side queries executed against projections of the domain state.
 
+use crate::domain::NeuronRole;
 use uuid::Uuid;
 
 /// Query operations handled by the [`QueryHandler`].
@@ -9,12 +10,16 @@ pub enum Query {
     GetNeuron { id: Uuid },
     /// Return all known neurons.
     ListNeurons,
+    /// Return all neurons with the given role.
+    ListNeuronsByRole { role: NeuronRole },
     /// Return all known synapses.
     ListSynapses,
     /// Fetch a synapse by identifier.
     GetSynapse { id: Uuid },
     /// Fetch the activation function of a neuron by identifier.
     GetNeuronActivation { id: Uuid },
+    /// Fetch the bias of a neuron by identifier.
+    GetNeuronBias { id: Uuid },
     /// Fetch the curiosity score for a neuron or synapse by identifier.
     GetCuriosityScore { id: Uuid },
 }
@@ -1,7 +1,7 @@
 //! Handles read-side queries against the current state.
 
 use crate::application::Query;
-use crate::domain::{Activation, Neuron, Synapse};
+use crate::domain::{Activation, Neuron, NeuronRole, Synapse};
 use crate::infrastructure::projection::{CuriosityScoreProjection, NetworkProjection};
 use uuid::Uuid;
 
@@ -17,6 +17,8 @@ pub enum QueryResult<'a> {
     Synapse(Option<&'a Synapse>),
     /// Activation lookup.
     Activation(Option<Activation>),
+    /// Bias lookup.
+    Bias(Option<f64>),
     /// Curiosity score lookup.
     CuriosityScore(Option<f64>),
 }
@@ -46,14 +48,21 @@ impl<'a> QueryHandler<'a> {
     ///
     /// ```
     /// use aei_framework::application::{Query, QueryHandler, QueryResult};
-    /// use aei_framework::domain::{Activation, CuriosityScoreUpdated, Event, RandomNeuronAdded};
+    /// use aei_framework::domain::{
+    ///     Activation, CuriosityScoreUpdated, Event, NeuronRole, RandomNeuronAdded,
+    /// };
     /// use aei_framework::infrastructure::projection::{CuriosityScoreProjection, NetworkProjection};
     /// use uuid::Uuid;
     ///
     /// let id = Uuid::new_v4();
     /// let events = vec![
-    ///     Event::RandomNeuronAdded(RandomNeuronAdded { neuron_id: id, activation: Activation::ReLU }),
-    ///     Event::CuriosityScoreUpdated(CuriosityScoreUpdated { target_id: id, old_score: 0.0, new_score: 0.42 }),
+    ///     Event::RandomNeuronAdded(RandomNeuronAdded {
+    ///         neuron_id: id,
+    ///         activation: Activation::ReLU,
+    ///         role: NeuronRole::Hidden,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::CuriosityScoreUpdated(CuriosityScoreUpdated { target_id: id, old_score: 0.0, new_score: 0.42, strategy: "rarity".into() }),
     /// ];
     /// let network = NetworkProjection::from_events(&events);
     /// let curiosity = CuriosityScoreProjection::from_events(&events);
@@ -72,11 +81,15 @@ impl<'a> QueryHandler<'a> {
         match query {
             Query::GetNeuron { id } => QueryResult::Neuron(self.network.neuron(id)),
             Query::ListNeurons => QueryResult::Neurons(self.network.neurons()),
+            Query::ListNeuronsByRole { role } => {
+                QueryResult::Neurons(self.network.neurons_by_role(role))
+            }
             Query::ListSynapses => QueryResult::Synapses(self.network.synapses()),
             Query::GetSynapse { id } => QueryResult::Synapse(self.network.synapse(id)),
             Query::GetNeuronActivation { id } => {
                 QueryResult::Activation(self.network.activation(id))
             }
+            Query::GetNeuronBias { id } => QueryResult::Bias(self.network.bias(id)),
             Query::GetCuriosityScore { id } => {
                 QueryResult::CuriosityScore(self.curiosity.and_then(|c| c.get(id)))
             }
@@ -89,6 +102,12 @@ impl<'a> QueryHandler<'a> {
         self.network.neuron(id)
     }
 
+    /// Convenience method to list neurons with the given role.
+    #[must_use]
+    pub fn neurons_by_role(&self, role: NeuronRole) -> Vec<&'a Neuron> {
+        self.network.neurons_by_role(role)
+    }
+
     /// Convenience method to fetch a synapse directly.
     #[must_use]
     pub fn synapse(&self, id: Uuid) -> Option<&'a Synapse> {
@@ -101,6 +120,12 @@ impl<'a> QueryHandler<'a> {
         self.network.activation(id)
     }
 
+    /// Convenience method to fetch a neuron's bias directly.
+    #[must_use]
+    pub fn bias(&self, id: Uuid) -> Option<f64> {
+        self.network.bias(id)
+    }
+
     /// Convenience method to fetch a curiosity score directly.
     ///
     /// # Arguments
@@ -115,14 +140,21 @@ impl<'a> QueryHandler<'a> {
     ///
     /// ```
     /// use aei_framework::application::QueryHandler;
-    /// use aei_framework::domain::{Activation, CuriosityScoreUpdated, Event, RandomNeuronAdded};
+    /// use aei_framework::domain::{
+    ///     Activation, CuriosityScoreUpdated, Event, NeuronRole, RandomNeuronAdded,
+    /// };
     /// use aei_framework::infrastructure::projection::{CuriosityScoreProjection, NetworkProjection};
     /// use uuid::Uuid;
     ///
     /// let id = Uuid::new_v4();
     /// let events = vec![
-    ///     Event::RandomNeuronAdded(RandomNeuronAdded { neuron_id: id, activation: Activation::ReLU }),
-    ///     Event::CuriosityScoreUpdated(CuriosityScoreUpdated { target_id: id, old_score: 0.0, new_score: 0.5 }),
+    ///     Event::RandomNeuronAdded(RandomNeuronAdded {
+    ///         neuron_id: id,
+    ///         activation: Activation::ReLU,
+    ///         role: NeuronRole::Hidden,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::CuriosityScoreUpdated(CuriosityScoreUpdated { target_id: id, old_score: 0.0, new_score: 0.5, strategy: "rarity".into() }),
     /// ];
     /// let network = NetworkProjection::from_events(&events);
     /// let curiosity = CuriosityScoreProjection::from_events(&events);
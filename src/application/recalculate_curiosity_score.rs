@@ -1,9 +1,18 @@
-//! Command and handler to recalculate curiosity scores.
+//! Commands and handler to recalculate and decay curiosity scores.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use uuid::Uuid;
 
-use crate::domain::{CuriosityScoreUpdated, Event, Network};
-use crate::infrastructure::EventStore;
+use crate::core::scheduler::Scheduler;
+
+use super::{conflict_error, Reload};
+use crate::domain::{
+    CuriosityIndex, CuriosityScoreUpdated, CuriosityStrategy, Event, Network, Rarity,
+};
+use crate::infrastructure::{AppendError, Correlation, EventStore};
 
 /// Scope of targets whose curiosity score should be recomputed.
 #[derive(Debug, Clone, Copy)]
@@ -18,19 +27,89 @@ pub enum CuriosityScope {
 
 /// Command requesting curiosity score recalculation.
 #[derive(Debug, Clone)]
-pub struct RecalculateCuriosityScoreCommand {
+pub struct RecalculateCuriosityScoreCommand<C: CuriosityStrategy = Rarity> {
     /// Identifiers of targets to update.
     pub target_ids: Vec<Uuid>,
     /// Scope describing the type of targets.
     pub scope: CuriosityScope,
+    /// Strategy computing the new scores.
+    pub strategy: C,
+}
+
+/// Parameters of the periodic decay of curiosity scores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CuriosityDecay {
+    /// Time after which a score is halved; non-zero.
+    pub half_life: Duration,
+    /// Maximum number of scores lowered by one run; non-zero.
+    pub batch_size: usize,
 }
 
-/// Handles [`RecalculateCuriosityScoreCommand`].
+impl CuriosityDecay {
+    /// Returns `true` if the half-life and the batch size are non-zero.
+    pub fn is_valid(&self) -> bool {
+        !self.half_life.is_zero() && self.batch_size > 0
+    }
+
+    /// Factor applied to a score left undecayed for `elapsed`.
+    fn factor(&self, elapsed: Duration) -> f64 {
+        0.5f64.powf(elapsed.as_secs_f64() / self.half_life.as_secs_f64())
+    }
+}
+
+/// Command lowering curiosity scores towards zero.
+///
+/// Each run lowers at most [`CuriosityDecay::batch_size`] scores, resuming
+/// where the previous run stopped, so that large networks are covered over
+/// several runs. A score is lowered by the time elapsed since its last
+/// decay or recalculation, which keeps the decay exact whatever the batch
+/// size. The emitted events record `"decay"` as their strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct DecayCuriosityScoresCommand {
+    /// Time elapsed since the previous run.
+    pub elapsed: Duration,
+    /// Half-life and batch size of the decay.
+    pub decay: CuriosityDecay,
+}
+
+/// Errors that can occur while decaying curiosity scores.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecayCuriosityScoresError {
+    /// The half-life or the batch size is zero.
+    InvalidDecay,
+    /// Persisting an event failed.
+    StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
+}
+
+conflict_error!(DecayCuriosityScoresError);
+
+/// Handles [`RecalculateCuriosityScoreCommand`] and
+/// [`DecayCuriosityScoresCommand`].
+///
+/// The history is read once, when the handler is created, and then kept up
+/// to date in a [`CuriosityIndex`]: each command first replays the events
+/// appended since, by this handler or other writers, so that it costs time
+/// proportional to the number of new events and targets.
 pub struct RecalculateCuriosityScoreHandler<S: EventStore> {
     /// Event store used for persistence.
     pub store: S,
     /// Current network state reconstructed from events.
     pub network: Network,
+    /// Index of the event history used to score targets.
+    pub index: CuriosityIndex,
+    /// Time elapsed over all decay runs.
+    decay_clock: Duration,
+    /// Value of the decay clock when each score was last decayed or
+    /// recalculated; scores absent from the map date from the creation of
+    /// the handler.
+    decayed_at: HashMap<Uuid, Duration>,
+    /// Last target lowered by a decay run.
+    decay_cursor: Option<Uuid>,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
 }
 
 impl<S: EventStore> RecalculateCuriosityScoreHandler<S> {
@@ -38,18 +117,65 @@ impl<S: EventStore> RecalculateCuriosityScoreHandler<S> {
     pub fn new(mut store: S) -> Result<Self, S::Error> {
         let events = store.load()?;
         let network = Network::hydrate(&events);
-        Ok(Self { store, network })
+        let index = CuriosityIndex::from_events(&events);
+        Ok(Self {
+            store,
+            network,
+            index,
+            decay_clock: Duration::ZERO,
+            decayed_at: HashMap::new(),
+            decay_cursor: None,
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`](super::NetworkHandlerBase::correlation_id).
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
     }
 
     /// Recomputes curiosity scores for the requested targets.
-    pub fn handle(
+    ///
+    /// # Errors
+    /// Returns [`AppendError::StorageError`] if the store fails. If another
+    /// writer appended to the store in the meantime, the handler catches up
+    /// with its events and scores the targets once more, returning
+    /// [`AppendError::Conflict`] if that happens again.
+    pub fn handle<C: CuriosityStrategy>(
         &mut self,
-        cmd: RecalculateCuriosityScoreCommand,
-    ) -> Result<Vec<Event>, S::Error> {
-        let events = self.store.load()?; // full history for analysis
-        let targets = self.resolve_targets(cmd);
+        cmd: RecalculateCuriosityScoreCommand<C>,
+    ) -> Result<Vec<Event>, AppendError<S::Error>> {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+        self.reload().map_err(AppendError::StorageError)?;
+        let targets = self.resolve_targets(cmd.scope, cmd.target_ids);
         let mut emitted = Vec::new();
-        for id in targets {
+        self.retry_on_conflict(|handler| {
+            handler.recalculate(&targets, &cmd.strategy, &mut emitted)
+        })?;
+        Ok(emitted)
+    }
+
+    /// Scores `targets`, pushing the events persisted to `emitted`.
+    ///
+    /// Targets already scored by an attempt refused because of a conflict
+    /// keep their score when retried, so they emit no event twice.
+    fn recalculate<C: CuriosityStrategy>(
+        &mut self,
+        targets: &[Uuid],
+        strategy: &C,
+        emitted: &mut Vec<Event>,
+    ) -> Result<(), AppendError<S::Error>> {
+        for &id in targets {
             let old = self
                 .network
                 .neurons
@@ -57,25 +183,181 @@ impl<S: EventStore> RecalculateCuriosityScoreHandler<S> {
                 .map(|n| n.curiosity_score)
                 .or_else(|| self.network.synapses.get(&id).map(|s| s.curiosity_score))
                 .unwrap_or_default();
-            let new_score = Self::compute_score(&events, id);
+            let new_score = strategy.score(id, &self.index, &self.network);
             if (new_score - old).abs() > f64::EPSILON {
                 let event = Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
                     target_id: id,
                     old_score: old,
                     new_score,
+                    strategy: strategy.name().to_string(),
                 });
-                self.store.append(&event)?;
-                self.network.apply(&event);
+                self.append(&event)?;
+                self.decayed_at.insert(id, self.decay_clock);
                 emitted.push(event);
             }
         }
+        Ok(())
+    }
+
+    /// Lowers the next batch of non-zero curiosity scores.
+    ///
+    /// A score left undecayed for a time `t` is multiplied by
+    /// `0.5^(t / half_life)`.
+    ///
+    /// # Errors
+    /// Returns [`DecayCuriosityScoresError::InvalidDecay`] if the decay
+    /// parameters are invalid and [`DecayCuriosityScoresError::StorageError`]
+    /// if persisting an event fails. If another writer appended to the store
+    /// during the run, the batch is decayed once more on the reloaded
+    /// network, and [`DecayCuriosityScoresError::Conflict`] is returned if
+    /// that happens again.
+    pub fn decay(
+        &mut self,
+        cmd: DecayCuriosityScoresCommand,
+    ) -> Result<Vec<Event>, DecayCuriosityScoresError> {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+        if !cmd.decay.is_valid() {
+            return Err(DecayCuriosityScoresError::InvalidDecay);
+        }
+        self.reload()
+            .map_err(|_| DecayCuriosityScoresError::StorageError)?;
+        self.decay_clock += cmd.elapsed;
+        // A retried run restarts from the same cursor: the scores it already
+        // lowered are up to date and are left alone.
+        let cursor = self.decay_cursor;
+        let mut emitted = Vec::new();
+        self.retry_on_conflict(|handler| handler.decay_batch(cursor, cmd.decay, &mut emitted))?;
         Ok(emitted)
     }
 
-    fn resolve_targets(&self, cmd: RecalculateCuriosityScoreCommand) -> Vec<Uuid> {
-        match cmd.scope {
-            CuriosityScope::Neuron => cmd.target_ids,
-            CuriosityScope::Synapse => cmd.target_ids,
+    /// Lowers the batch of non-zero scores following `cursor`, pushing the
+    /// events persisted to `emitted`.
+    fn decay_batch(
+        &mut self,
+        cursor: Option<Uuid>,
+        decay: CuriosityDecay,
+        emitted: &mut Vec<Event>,
+    ) -> Result<(), DecayCuriosityScoresError> {
+        let mut scored: Vec<(Uuid, f64)> = self
+            .network
+            .neurons
+            .iter()
+            .map(|(id, n)| (*id, n.curiosity_score))
+            .chain(
+                self.network
+                    .synapses
+                    .iter()
+                    .map(|(id, s)| (*id, s.curiosity_score)),
+            )
+            .filter(|(_, score)| *score != 0.0)
+            .collect();
+        scored.sort_unstable_by_key(|(id, _)| *id);
+        // Resume after the last decayed target, wrapping around.
+        let start = cursor.map_or(0, |cursor| scored.partition_point(|(id, _)| *id <= cursor));
+        scored.rotate_left(start);
+        scored.truncate(decay.batch_size);
+
+        for (id, old) in scored {
+            let since = self.decayed_at.get(&id).copied().unwrap_or_default();
+            let new_score = old * decay.factor(self.decay_clock - since);
+            if (new_score - old).abs() > f64::EPSILON {
+                let event = Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
+                    target_id: id,
+                    old_score: old,
+                    new_score,
+                    strategy: "decay".to_string(),
+                });
+                self.append(&event)?;
+                emitted.push(event);
+            }
+            self.decayed_at.insert(id, self.decay_clock);
+            self.decay_cursor = Some(id);
+        }
+        Ok(())
+    }
+
+    /// Registers a task running a [`DecayCuriosityScoresCommand`] on
+    /// `handler` every `interval`, each run counting as `interval` of
+    /// elapsed time.
+    ///
+    /// A run that fails to persist its events is logged at the `warn` level
+    /// and leaves the remaining scores to the following runs.
+    ///
+    /// # Errors
+    /// Returns [`DecayCuriosityScoresError::InvalidDecay`] if the decay
+    /// parameters are invalid; nothing is scheduled then.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::core::scheduler::{InMemoryScheduler, Scheduler};
+    /// use aei_framework::{CuriosityDecay, FileEventStore, RecalculateCuriosityScoreHandler};
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let path = std::env::temp_dir().join(format!("aei_decay_doc_{}.log", uuid::Uuid::new_v4()));
+    /// let store = FileEventStore::new(path.clone());
+    /// let handler = Arc::new(Mutex::new(RecalculateCuriosityScoreHandler::new(store)?));
+    /// let mut scheduler = InMemoryScheduler::new();
+    /// let decay = CuriosityDecay {
+    ///     half_life: Duration::from_secs(600),
+    ///     batch_size: 256,
+    /// };
+    /// RecalculateCuriosityScoreHandler::schedule_decay(
+    ///     &handler,
+    ///     &mut scheduler,
+    ///     Duration::from_secs(10),
+    ///     decay,
+    /// )
+    /// .unwrap();
+    /// scheduler.tick();
+    /// # let _ = std::fs::remove_file(path);
+    /// # Ok(()) }
+    /// ```
+    pub fn schedule_decay<T: Scheduler + ?Sized>(
+        handler: &Arc<Mutex<Self>>,
+        scheduler: &mut T,
+        interval: Duration,
+        decay: CuriosityDecay,
+    ) -> Result<(), DecayCuriosityScoresError>
+    where
+        S: Send + 'static,
+    {
+        if !decay.is_valid() {
+            return Err(DecayCuriosityScoresError::InvalidDecay);
+        }
+        let handler = Arc::clone(handler);
+        scheduler.schedule(
+            interval,
+            Box::new(move || {
+                if let Ok(mut handler) = handler.lock() {
+                    if let Err(error) = handler.decay(DecayCuriosityScoresCommand {
+                        elapsed: interval,
+                        decay,
+                    }) {
+                        log::warn!("curiosity decay run failed: {error:?}");
+                    }
+                }
+            }),
+        );
+        Ok(())
+    }
+
+    /// Persists `event` and applies it, unless another writer appended to
+    /// the store since the last indexed event.
+    fn append(&mut self, event: &Event) -> Result<(), AppendError<S::Error>> {
+        self.store
+            .append_expected(std::slice::from_ref(event), self.index.len())?;
+        self.network.apply(event);
+        self.index.apply(event);
+        Ok(())
+    }
+
+    fn resolve_targets(&self, scope: CuriosityScope, target_ids: Vec<Uuid>) -> Vec<Uuid> {
+        match scope {
+            CuriosityScope::Neuron => target_ids,
+            CuriosityScope::Synapse => target_ids,
             CuriosityScope::All => self
                 .network
                 .neurons
@@ -85,30 +367,24 @@ impl<S: EventStore> RecalculateCuriosityScoreHandler<S> {
                 .collect(),
         }
     }
+}
 
-    /// Computes a simple curiosity score based on event rarity.
-    fn compute_score(events: &[Event], id: Uuid) -> f64 {
-        let occurrences = events.iter().filter(|e| Self::touches(e, id)).count();
-        1.0 / (1.0 + occurrences as f64)
-    }
+impl<S: EventStore> Reload for RecalculateCuriosityScoreHandler<S> {
+    type Error = S::Error;
 
-    fn touches(event: &Event, id: Uuid) -> bool {
-        match event {
-            Event::RandomNeuronAdded(e) => e.neuron_id == id,
-            Event::RandomNeuronRemoved(e) => e.neuron_id == id,
-            Event::NeuronAdded(e) => e.neuron_id == id,
-            Event::NeuronRemoved(e) => e.neuron_id == id,
-            Event::SynapseCreated {
-                id: sid, from, to, ..
-            } => *sid == id || *from == id || *to == id,
-            Event::SynapseRemoved { id: sid } => *sid == id,
-            Event::RandomSynapseAdded(e) => e.synapse_id == id || e.from == id || e.to == id,
-            Event::RandomSynapseRemoved(e) => e.synapse_id == id,
-            Event::SynapseWeightMutated(e) => e.synapse_id == id,
-            Event::SynapseWeightSet(e) => e.synapse_id == id,
-            Event::NeuronActivationMutated(e) => e.neuron_id == id,
-            Event::CuriosityScoreUpdated(e) => e.target_id == id,
+    /// Applies the events appended to the store since the last indexed one.
+    /// Scores recalculated by other writers restart their decay from now.
+    fn reload(&mut self) -> Result<(), S::Error> {
+        for event in self.store.load_from(self.index.len())? {
+            if let Event::CuriosityScoreUpdated(e) = &event {
+                if e.strategy != "decay" {
+                    self.decayed_at.insert(e.target_id, self.decay_clock);
+                }
+            }
+            self.network.apply(&event);
+            self.index.apply(&event);
         }
+        Ok(())
     }
 }
 
@@ -118,17 +394,19 @@ mod tests {
     use crate::domain::{RandomNeuronAdded, RandomNeuronRemoved};
 
     #[test]
-    fn compute_score_decreases_with_occurrences() {
+    fn rarity_decreases_with_occurrences() {
         let id = Uuid::new_v4();
         let events = vec![
             Event::RandomNeuronAdded(RandomNeuronAdded {
                 neuron_id: id,
                 activation: crate::domain::Activation::Identity,
+                role: crate::domain::NeuronRole::Hidden,
+                bias: 0.0,
             }),
             Event::RandomNeuronRemoved(RandomNeuronRemoved { neuron_id: id }),
         ];
-        let score =
-            RecalculateCuriosityScoreHandler::<crate::FileEventStore>::compute_score(&events, id);
+        let index = CuriosityIndex::from_events(&events);
+        let score = Rarity.score(id, &index, &Network::hydrate(&events));
         assert!(score < 1.0);
     }
 }
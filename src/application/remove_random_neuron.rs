@@ -1,11 +1,11 @@
 //! Command and handler for removing a random neuron.
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{Rng, SeedableRng};
 use uuid::Uuid;
 
-use super::NetworkHandlerBase;
+use super::{conflict_error, NetworkHandlerBase};
 use crate::domain::{Event, RandomNeuronRemoved};
-use crate::infrastructure::EventStore;
+use crate::infrastructure::{AppendError, EventStore};
 
 /// Command requesting the removal of a random neuron.
 #[derive(Debug, Clone, Copy)]
@@ -18,8 +18,13 @@ pub enum RemoveRandomNeuronError {
     NoNeuronAvailable,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(RemoveRandomNeuronError);
+
 /// Handles [`RemoveRandomNeuronCommand`], emitting events and updating state.
 pub struct RemoveRandomNeuronHandler<S: EventStore, R: Rng> {
     /// Shared handler state including store, network and RNG.
@@ -34,24 +39,55 @@ impl<S: EventStore, R: Rng> RemoveRandomNeuronHandler<S, R> {
         })
     }
 
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
     /// Handles the command and returns the identifier of the removed neuron.
+    ///
+    /// Only hidden neurons are candidates; neurons with a protected
+    /// [`NeuronRole`](crate::domain::NeuronRole) are never removed.
     pub fn handle(
         &mut self,
-        _cmd: RemoveRandomNeuronCommand,
+        cmd: RemoveRandomNeuronCommand,
     ) -> Result<Uuid, RemoveRandomNeuronError> {
-        let base = &mut self.base;
-        let ids: Vec<Uuid> = base.network.neurons.keys().copied().collect();
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: RemoveRandomNeuronCommand,
+    ) -> Result<Result<Uuid, RemoveRandomNeuronError>, AppendError<S::Error>> {
+        let RemoveRandomNeuronCommand = cmd;
+        let ids: Vec<Uuid> = base
+            .network
+            .neurons
+            .values()
+            .filter(|n| !n.role.is_protected())
+            .map(|n| n.id)
+            .collect();
         if ids.is_empty() {
-            return Err(RemoveRandomNeuronError::NoNeuronAvailable);
+            return Ok(Err(RemoveRandomNeuronError::NoNeuronAvailable));
         }
-        let neuron_id = *ids
-            .choose(&mut base.rng)
+        let neuron_id = base
+            .choose_target(&ids)
             .expect("candidate list is non-empty");
         let event = Event::RandomNeuronRemoved(RandomNeuronRemoved { neuron_id });
-        base.store
-            .append(&event)
-            .map_err(|_| RemoveRandomNeuronError::StorageError)?;
+        base.append(&event)?;
         base.network.apply(&event);
-        Ok(neuron_id)
+        Ok(Ok(neuron_id))
     }
 }
@@ -1,11 +1,11 @@
 //! Command and handler for removing a random synapse.
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{Rng, SeedableRng};
 use uuid::Uuid;
 
-use super::NetworkHandlerBase;
+use super::{conflict_error, NetworkHandlerBase};
 use crate::domain::{Event, RandomSynapseRemoved};
-use crate::infrastructure::EventStore;
+use crate::infrastructure::{AppendError, EventStore};
 
 /// Command requesting the removal of a random synapse.
 ///
@@ -25,8 +25,13 @@ pub enum RemoveRandomSynapseError {
     NoSynapseAvailable,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(RemoveRandomSynapseError);
+
 /// Handles [`RemoveRandomSynapseCommand`], emitting events and updating state.
 pub struct RemoveRandomSynapseHandler<S: EventStore, R: Rng> {
     /// Shared handler state including store, network and RNG.
@@ -44,6 +49,20 @@ impl<S: EventStore, R: Rng> RemoveRandomSynapseHandler<S, R> {
         })
     }
 
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
     /// Handles the command and returns the identifier of the removed synapse.
     ///
     /// # Errors
@@ -51,6 +70,9 @@ impl<S: EventStore, R: Rng> RemoveRandomSynapseHandler<S, R> {
     /// does not contain any synapse and
     /// [`RemoveRandomSynapseError::StorageError`] if persisting the event
     /// fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`RemoveRandomSynapseError::Conflict`] is returned if that happens again.
     ///
     /// # Examples
     /// ```
@@ -68,21 +90,32 @@ impl<S: EventStore, R: Rng> RemoveRandomSynapseHandler<S, R> {
     /// ```
     pub fn handle(
         &mut self,
-        _cmd: RemoveRandomSynapseCommand,
+        cmd: RemoveRandomSynapseCommand,
     ) -> Result<Uuid, RemoveRandomSynapseError> {
-        let base = &mut self.base;
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: RemoveRandomSynapseCommand,
+    ) -> Result<Result<Uuid, RemoveRandomSynapseError>, AppendError<S::Error>> {
+        let RemoveRandomSynapseCommand = cmd;
         let ids: Vec<Uuid> = base.network.synapses.keys().copied().collect();
         if ids.is_empty() {
-            return Err(RemoveRandomSynapseError::NoSynapseAvailable);
+            return Ok(Err(RemoveRandomSynapseError::NoSynapseAvailable));
         }
-        let synapse_id = *ids
-            .choose(&mut base.rng)
+        let synapse_id = base
+            .choose_target(&ids)
             .expect("candidate list is non-empty");
         let event = Event::RandomSynapseRemoved(RandomSynapseRemoved { synapse_id });
-        base.store
-            .append(&event)
-            .map_err(|_| RemoveRandomSynapseError::StorageError)?;
+        base.append(&event)?;
         base.network.apply(&event);
-        Ok(synapse_id)
+        Ok(Ok(synapse_id))
     }
 }
@@ -0,0 +1,135 @@
+//! Command and handler for explicitly setting a neuron's bias.
+//!
+//! This operation emits a [`NeuronBiasSet`](crate::domain::NeuronBiasSet)
+//! event, which is persisted and applied to the [`Network`](crate::domain::Network).
+
+use super::{conflict_error, Reload};
+use crate::domain::{Event, Network, NeuronBiasSet};
+use crate::infrastructure::{Correlation, EventStore};
+use uuid::Uuid;
+
+/// Command requesting to assign a new bias to a neuron.
+#[derive(Debug, Clone)]
+pub struct SetNeuronBiasCommand {
+    /// Identifier of the neuron to update.
+    pub neuron_id: Uuid,
+    /// Desired bias value.
+    pub new_bias: f64,
+}
+
+/// Errors that may occur while setting a neuron's bias.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetNeuronBiasError {
+    /// The specified neuron does not exist in the network.
+    NeuronNotFound,
+    /// Persisting the event failed.
+    StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
+}
+
+conflict_error!(SetNeuronBiasError);
+
+/// Handles [`SetNeuronBiasCommand`] and applies the resulting event.
+pub struct SetNeuronBiasHandler<S: EventStore> {
+    /// Event store used for persistence.
+    pub store: S,
+    /// Current network state reconstructed from events.
+    pub network: Network,
+    /// Number of events in the store that the network reflects.
+    version: u64,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
+}
+
+impl<S: EventStore> SetNeuronBiasHandler<S> {
+    /// Loads events from the store to initialize the handler.
+    pub fn new(mut store: S) -> Result<Self, S::Error> {
+        let events = store.load()?;
+        let network = Network::hydrate(&events);
+        Ok(Self {
+            store,
+            network,
+            version: events.len() as u64,
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`](super::NetworkHandlerBase::correlation_id).
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
+    }
+
+    /// Handles the command by emitting and applying a [`NeuronBiasSet`] event.
+    ///
+    /// # Errors
+    /// Returns [`SetNeuronBiasError::NeuronNotFound`] if the target neuron is
+    /// missing, or [`SetNeuronBiasError::StorageError`] if persisting the event
+    /// fails. If another writer appended to the store in the meantime, the
+    /// command is run once more on the reloaded network, and
+    /// [`SetNeuronBiasError::Conflict`] is returned if that happens again.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{FileEventStore, SetNeuronBiasCommand, SetNeuronBiasHandler};
+    /// use uuid::Uuid;
+    /// use std::path::PathBuf;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let store = FileEventStore::new(PathBuf::from("events.log"));
+    /// let mut handler = SetNeuronBiasHandler::new(store)?;
+    /// let neuron_id = Uuid::new_v4();
+    /// // network must already contain `neuron_id`
+    /// let _ = handler.handle(SetNeuronBiasCommand { neuron_id, new_bias: -0.5 });
+    /// # Ok(()) }
+    /// ```
+    pub fn handle(&mut self, cmd: SetNeuronBiasCommand) -> Result<(), SetNeuronBiasError> {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+        self.retry_on_conflict(|handler| handler.execute(&cmd))
+    }
+
+    /// Emits the event against the current network, unless another writer
+    /// appended to the store since it was loaded.
+    fn execute(&mut self, cmd: &SetNeuronBiasCommand) -> Result<(), SetNeuronBiasError> {
+        let old_bias = self
+            .network
+            .neurons
+            .get(&cmd.neuron_id)
+            .map(|n| n.bias)
+            .ok_or(SetNeuronBiasError::NeuronNotFound)?;
+        let event = Event::NeuronBiasSet(NeuronBiasSet {
+            neuron_id: cmd.neuron_id,
+            old_bias,
+            new_bias: cmd.new_bias,
+        });
+        self.version = self
+            .store
+            .append_expected(std::slice::from_ref(&event), self.version)?;
+        self.network.apply(&event);
+        Ok(())
+    }
+}
+
+impl<S: EventStore> Reload for SetNeuronBiasHandler<S> {
+    type Error = S::Error;
+
+    fn reload(&mut self) -> Result<(), S::Error> {
+        let tail = self.store.load_from(self.version)?;
+        for event in &tail {
+            self.network.apply(event);
+        }
+        self.version += tail.len() as u64;
+        Ok(())
+    }
+}
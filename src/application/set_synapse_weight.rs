@@ -3,8 +3,9 @@
 //! This operation emits a [`SynapseWeightSet`](crate::domain::SynapseWeightSet)
 //! event, which is persisted and applied to the [`Network`](crate::domain::Network).
 
+use super::{conflict_error, Reload};
 use crate::domain::{Event, Network, SynapseWeightSet};
-use crate::infrastructure::EventStore;
+use crate::infrastructure::{Correlation, EventStore};
 use uuid::Uuid;
 
 /// Command requesting to assign a new weight to a synapse.
@@ -23,14 +24,23 @@ pub enum SetSynapseWeightError {
     SynapseNotFound,
     /// Persisting the event failed.
     StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
 }
 
+conflict_error!(SetSynapseWeightError);
+
 /// Handles [`SetSynapseWeightCommand`] and applies the resulting event.
 pub struct SetSynapseWeightHandler<S: EventStore> {
     /// Event store used for persistence.
     pub store: S,
     /// Current network state reconstructed from events.
     pub network: Network,
+    /// Number of events in the store that the network reflects.
+    version: u64,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
 }
 
 impl<S: EventStore> SetSynapseWeightHandler<S> {
@@ -38,7 +48,26 @@ impl<S: EventStore> SetSynapseWeightHandler<S> {
     pub fn new(mut store: S) -> Result<Self, S::Error> {
         let events = store.load()?;
         let network = Network::hydrate(&events);
-        Ok(Self { store, network })
+        Ok(Self {
+            store,
+            network,
+            version: events.len() as u64,
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`](super::NetworkHandlerBase::correlation_id).
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
     }
 
     /// Handles the command by emitting and applying a [`SynapseWeightSet`] event.
@@ -46,7 +75,9 @@ impl<S: EventStore> SetSynapseWeightHandler<S> {
     /// # Errors
     /// Returns [`SetSynapseWeightError::SynapseNotFound`] if the target synapse is
     /// missing, or [`SetSynapseWeightError::StorageError`] if persisting the event
-    /// fails.
+    /// fails. If another writer appended to the store in the meantime, the
+    /// command is run once more on the reloaded network, and
+    /// [`SetSynapseWeightError::Conflict`] is returned if that happens again.
     ///
     /// # Examples
     /// ```
@@ -63,6 +94,14 @@ impl<S: EventStore> SetSynapseWeightHandler<S> {
     /// # Ok(()) }
     /// ```
     pub fn handle(&mut self, cmd: SetSynapseWeightCommand) -> Result<(), SetSynapseWeightError> {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+        self.retry_on_conflict(|handler| handler.execute(&cmd))
+    }
+
+    /// Emits the event against the current network, unless another writer
+    /// appended to the store since it was loaded.
+    fn execute(&mut self, cmd: &SetSynapseWeightCommand) -> Result<(), SetSynapseWeightError> {
         let old_weight = self
             .network
             .synapses
@@ -74,14 +113,27 @@ impl<S: EventStore> SetSynapseWeightHandler<S> {
             old_weight,
             new_weight: cmd.new_weight,
         });
-        self.store
-            .append(&event)
-            .map_err(|_| SetSynapseWeightError::StorageError)?;
+        self.version = self
+            .store
+            .append_expected(std::slice::from_ref(&event), self.version)?;
         self.network.apply(&event);
         Ok(())
     }
 }
 
+impl<S: EventStore> Reload for SetSynapseWeightHandler<S> {
+    type Error = S::Error;
+
+    fn reload(&mut self) -> Result<(), S::Error> {
+        let tail = self.store.load_from(self.version)?;
+        for event in &tail {
+            self.network.apply(event);
+        }
+        self.version += tail.len() as u64;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,7 +149,7 @@ mod tests {
     }
 
     #[test]
-    fn set_synapse_weight_updates_network() {
+    fn set_synapse_weight_updates_network() -> std::io::Result<()> {
         let path = temp_path();
         let mut store = FileEventStore::new(path.clone());
         let n1 = Uuid::new_v4();
@@ -107,23 +159,28 @@ mod tests {
             Event::RandomNeuronAdded(RandomNeuronAdded {
                 neuron_id: n1,
                 activation: crate::domain::Activation::Identity,
+                role: crate::domain::NeuronRole::Hidden,
+                bias: 0.0,
             }),
             Event::RandomNeuronAdded(RandomNeuronAdded {
                 neuron_id: n2,
                 activation: crate::domain::Activation::Identity,
+                role: crate::domain::NeuronRole::Hidden,
+                bias: 0.0,
             }),
             Event::RandomSynapseAdded(RandomSynapseAdded {
                 synapse_id: syn_id,
                 from: n1,
                 to: n2,
                 weight: 1.0,
+                innovation: None,
             }),
         ];
         for e in &events {
-            store.append(e).unwrap();
+            store.append(e)?;
         }
 
-        let mut handler = SetSynapseWeightHandler::new(FileEventStore::new(path)).unwrap();
+        let mut handler = SetSynapseWeightHandler::new(FileEventStore::new(path))?;
         handler
             .handle(SetSynapseWeightCommand {
                 synapse_id: syn_id,
@@ -131,5 +188,6 @@ mod tests {
             })
             .unwrap();
         assert_eq!(handler.network.synapses.get(&syn_id).unwrap().weight, 2.0);
+        Ok(())
     }
 }
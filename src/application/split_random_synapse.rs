@@ -0,0 +1,139 @@
+//! Command and handler for the NEAT "add node" structural mutation.
+//!
+//! A randomly selected enabled synapse `A → B` is disabled and bypassed by a
+//! new hidden neuron `C` connected through `A → C` with weight `1.0` and
+//! `C → B` with the weight of the original synapse. The change is persisted
+//! as a single [`SynapseSplit`] event. With the default identity activation the network
+//! computes exactly the same values right after the split, so topology can
+//! grow without disrupting what was learned.
+
+use rand::{Rng, SeedableRng};
+use uuid::Uuid;
+
+use super::{conflict_error, NetworkHandlerBase};
+use crate::domain::{Activation, Event, SynapseSplit};
+use crate::infrastructure::{AppendError, EventStore};
+
+/// Command requesting the split of a random synapse.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SplitRandomSynapseCommand {
+    /// Activation of the inserted neuron. [`Activation::Identity`], the
+    /// default, preserves the behaviour of the network.
+    pub activation: Activation,
+}
+
+/// Errors that can occur when splitting a synapse.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplitRandomSynapseError {
+    /// The network does not contain any enabled synapse to split.
+    NoSynapseAvailable,
+    /// Persisting the event failed.
+    StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
+}
+
+conflict_error!(SplitRandomSynapseError);
+
+/// Handles [`SplitRandomSynapseCommand`], emitting and applying
+/// [`SynapseSplit`] events.
+pub struct SplitRandomSynapseHandler<S: EventStore, R: Rng> {
+    /// Shared handler state including store, network and RNG.
+    pub base: NetworkHandlerBase<S, R>,
+}
+
+impl<S: EventStore, R: Rng> SplitRandomSynapseHandler<S, R> {
+    /// Loads events from the store to initialize the handler.
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn new(store: S, rng: R) -> Result<Self, S::Error> {
+        Ok(Self {
+            base: NetworkHandlerBase::new(store, rng)?,
+        })
+    }
+
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
+    /// Handles the command and returns the identifier of the inserted neuron.
+    ///
+    /// # Errors
+    /// Returns [`SplitRandomSynapseError::NoSynapseAvailable`] if the network
+    /// has no enabled synapse and [`SplitRandomSynapseError::StorageError`] if
+    /// persisting the event fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`SplitRandomSynapseError::Conflict`] is returned if that happens again.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{FileEventStore, SplitRandomSynapseCommand, SplitRandomSynapseHandler};
+    /// use rand::thread_rng;
+    /// use std::path::PathBuf;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let store = FileEventStore::new(PathBuf::from("events.log"));
+    /// let mut handler = SplitRandomSynapseHandler::new(store, thread_rng())?;
+    /// let _ = handler.handle(SplitRandomSynapseCommand::default());
+    /// # Ok(()) }
+    /// ```
+    pub fn handle(
+        &mut self,
+        cmd: SplitRandomSynapseCommand,
+    ) -> Result<Uuid, SplitRandomSynapseError> {
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: SplitRandomSynapseCommand,
+    ) -> Result<Result<Uuid, SplitRandomSynapseError>, AppendError<S::Error>> {
+        let ids: Vec<Uuid> = base.network.enabled_synapses().map(|s| s.id).collect();
+        let Some(synapse_id) = base.choose_target(&ids) else {
+            return Ok(Err(SplitRandomSynapseError::NoSynapseAvailable));
+        };
+        let synapse = &base.network.synapses[&synapse_id];
+        let (from, to, weight) = (synapse.from, synapse.to, synapse.weight);
+
+        let neuron_id = base.next_id();
+        let innovations = &base.innovations;
+        let (incoming_innovation, outgoing_innovation) = (
+            innovations.innovation(from, neuron_id),
+            innovations.innovation(neuron_id, to),
+        );
+        let event = Event::SynapseSplit(SynapseSplit {
+            synapse_id,
+            from,
+            to,
+            weight,
+            neuron_id,
+            activation: cmd.activation,
+            incoming_synapse_id: base.next_id(),
+            outgoing_synapse_id: base.next_id(),
+            incoming_innovation: Some(incoming_innovation),
+            outgoing_innovation: Some(outgoing_innovation),
+        });
+        base.append(&event)?;
+        base.network.apply(&event);
+        Ok(Ok(neuron_id))
+    }
+}
@@ -0,0 +1,119 @@
+//! Command and handler for enabling or disabling a random synapse.
+//!
+//! The selected synapse flips its `enabled` flag: an enabled synapse emits a
+//! [`SynapseDisabled`] event and a disabled one a [`SynapseEnabled`] event.
+//! The synapse keeps its weight either way.
+
+use rand::{Rng, SeedableRng};
+use uuid::Uuid;
+
+use super::{conflict_error, NetworkHandlerBase};
+use crate::domain::{Event, SynapseDisabled, SynapseEnabled};
+use crate::infrastructure::{AppendError, EventStore};
+
+/// Command requesting to toggle a random synapse.
+#[derive(Debug, Clone, Copy)]
+pub struct ToggleRandomSynapseCommand;
+
+/// Errors that can occur when toggling a synapse.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToggleRandomSynapseError {
+    /// The network does not contain any synapse to toggle.
+    NoSynapseAvailable,
+    /// Persisting the event failed.
+    StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
+}
+
+conflict_error!(ToggleRandomSynapseError);
+
+/// Handles [`ToggleRandomSynapseCommand`], emitting [`SynapseDisabled`] or
+/// [`SynapseEnabled`] events.
+pub struct ToggleRandomSynapseHandler<S: EventStore, R: Rng> {
+    /// Shared handler state including store, network and RNG.
+    pub base: NetworkHandlerBase<S, R>,
+}
+
+impl<S: EventStore, R: Rng> ToggleRandomSynapseHandler<S, R> {
+    /// Loads events from the store to initialize the handler.
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn new(store: S, rng: R) -> Result<Self, S::Error> {
+        Ok(Self {
+            base: NetworkHandlerBase::new(store, rng)?,
+        })
+    }
+
+    /// Loads events from the store and seeds the RNG from the run seed, see
+    /// [`NetworkHandlerBase::with_seed`].
+    ///
+    /// # Errors
+    /// Propagates storage backend errors.
+    pub fn with_seed(store: S, seed: u64) -> Result<Self, S::Error>
+    where
+        R: SeedableRng,
+    {
+        Ok(Self {
+            base: NetworkHandlerBase::with_seed(store, seed)?,
+        })
+    }
+
+    /// Handles the command and returns the identifier of the toggled synapse.
+    ///
+    /// # Errors
+    /// Returns [`ToggleRandomSynapseError::NoSynapseAvailable`] if the network
+    /// does not contain any synapse and
+    /// [`ToggleRandomSynapseError::StorageError`] if persisting the event
+    /// fails.
+    /// If another writer appended to the store in the meantime, the command
+    /// is run once more on the reloaded network, and
+    /// [`ToggleRandomSynapseError::Conflict`] is returned if that happens again.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{FileEventStore, ToggleRandomSynapseCommand, ToggleRandomSynapseHandler};
+    /// use rand::thread_rng;
+    /// use std::path::PathBuf;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let store = FileEventStore::new(PathBuf::from("events.log"));
+    /// let mut handler = ToggleRandomSynapseHandler::new(store, thread_rng())?;
+    /// let _ = handler.handle(ToggleRandomSynapseCommand);
+    /// # Ok(()) }
+    /// ```
+    pub fn handle(
+        &mut self,
+        cmd: ToggleRandomSynapseCommand,
+    ) -> Result<Uuid, ToggleRandomSynapseError> {
+        self.base
+            .run_command(|base| Self::execute(base, cmd))
+            .map_err(Into::into)
+            .and_then(|outcome| outcome)
+    }
+
+    /// Handles the command against `base`, which may be shared with other
+    /// handlers. Failed appends are reported apart from the errors of a
+    /// command that does not apply to the network.
+    pub(crate) fn execute(
+        base: &mut NetworkHandlerBase<S, R>,
+        cmd: ToggleRandomSynapseCommand,
+    ) -> Result<Result<Uuid, ToggleRandomSynapseError>, AppendError<S::Error>> {
+        let ToggleRandomSynapseCommand = cmd;
+        let ids: Vec<Uuid> = base.network.synapses.keys().copied().collect();
+        let Some(synapse_id) = base.choose_target(&ids) else {
+            return Ok(Err(ToggleRandomSynapseError::NoSynapseAvailable));
+        };
+        let enabled = base.network.synapses[&synapse_id].enabled;
+        let event = if enabled {
+            Event::SynapseDisabled(SynapseDisabled { synapse_id })
+        } else {
+            Event::SynapseEnabled(SynapseEnabled { synapse_id })
+        };
+        base.append(&event)?;
+        base.network.apply(&event);
+        Ok(Ok(synapse_id))
+    }
+}
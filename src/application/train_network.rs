@@ -0,0 +1,276 @@
+//! Command and handler for training synapse weights by gradient descent.
+//!
+//! Each epoch computes the gradients of the configured [`Loss`] over all
+//! samples, lets the configured [`Optimizer`] turn them into weight and bias
+//! changes and emits a single [`TrainingStepCompleted`] event holding every
+//! change, so training can be replayed from the event log.
+//!
+//! The optimizer state is rebuilt on load by feeding it the gradients of the
+//! trailing run of steps recorded with the same [`OptimizerConfig`]. A run
+//! interrupted midway therefore resumes exactly where it stopped.
+
+use std::collections::BTreeMap;
+
+use uuid::Uuid;
+
+use super::{conflict_error, Reload};
+use crate::domain::{
+    Event, Loss, Network, NeuronBiasUpdate, Optimizer, OptimizerConfig, PropagationError,
+    SynapseWeightUpdate, TrainingSample, TrainingStepCompleted,
+};
+use crate::infrastructure::{Correlation, EventStore};
+
+/// Command requesting to train the network on a set of samples.
+#[derive(Debug, Clone)]
+pub struct TrainNetworkCommand {
+    /// Samples forming one full batch.
+    pub samples: Vec<TrainingSample>,
+    /// Number of gradient descent steps to perform over the batch.
+    pub epochs: usize,
+    /// Loss minimised by the training.
+    pub loss: Loss,
+    /// Optimizer applying the gradients.
+    pub optimizer: OptimizerConfig,
+}
+
+/// Errors that may occur while training the network.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrainNetworkError {
+    /// The command does not contain any sample.
+    NoSamples,
+    /// A parameter of the loss function lies outside its valid range.
+    InvalidLoss,
+    /// A hyperparameter of the optimizer lies outside its valid range.
+    InvalidOptimizerConfig,
+    /// The forward pass failed, e.g. because the network contains a cycle.
+    Propagation(PropagationError),
+    /// Persisting the event failed.
+    StorageError,
+    /// Another writer kept appending to the store, even after the network
+    /// was reloaded once.
+    Conflict,
+}
+
+conflict_error!(TrainNetworkError);
+
+/// Optimizer configuration together with the optimizer it built.
+type RunningOptimizer = (OptimizerConfig, Box<dyn Optimizer>);
+
+/// Handles [`TrainNetworkCommand`], emitting and applying
+/// [`TrainingStepCompleted`] events.
+pub struct TrainNetworkHandler<S: EventStore> {
+    /// Event store used for persistence.
+    pub store: S,
+    /// Current network state reconstructed from events.
+    pub network: Network,
+    /// Number of training steps recorded in the event log.
+    pub steps: u64,
+    /// Optimizer of the most recent steps together with its rebuilt state.
+    optimizer: Option<RunningOptimizer>,
+    /// Number of events in the store that the network reflects.
+    version: u64,
+    /// Identifiers attached to the envelopes of the events.
+    correlation: Correlation,
+}
+
+impl<S: EventStore> TrainNetworkHandler<S> {
+    /// Loads events from the store to initialize the handler.
+    pub fn new(mut store: S) -> Result<Self, S::Error> {
+        let events = store.load()?;
+        let (steps, optimizer) = Self::history(&events);
+        Ok(Self {
+            store,
+            network: Network::hydrate(&events),
+            steps,
+            optimizer,
+            version: events.len() as u64,
+            correlation: Correlation::default(),
+        })
+    }
+
+    /// Returns the identifier recorded as the correlation of the events of
+    /// the command in progress or last handled, see
+    /// [`NetworkHandlerBase::correlation_id`](super::NetworkHandlerBase::correlation_id).
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        self.correlation.correlation_id
+    }
+
+    /// Records `causation_id` as the cause of the events of the following
+    /// commands.
+    pub fn set_causation(&mut self, causation_id: Option<Uuid>) {
+        self.correlation.causation_id = causation_id;
+    }
+
+    /// Counts the training steps in `events` and rebuilds the optimizer of
+    /// the trailing run of steps recorded with the same configuration.
+    fn history(events: &[Event]) -> (u64, Option<RunningOptimizer>) {
+        let history: Vec<&TrainingStepCompleted> = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::TrainingStepCompleted(step) => Some(step),
+                _ => None,
+            })
+            .collect();
+        let optimizer = history.last().map(|last| {
+            let config = last.optimizer;
+            let run = history
+                .iter()
+                .rev()
+                .take_while(|step| step.optimizer == config)
+                .count();
+            let mut optimizer = config.build();
+            for step in &history[history.len() - run..] {
+                let gradients = step
+                    .updates
+                    .iter()
+                    .map(|u| (u.synapse_id, u.gradient))
+                    .chain(step.bias_updates.iter().map(|u| (u.neuron_id, u.gradient)))
+                    .collect();
+                optimizer.step(&gradients);
+            }
+            (config, optimizer)
+        });
+        (history.len() as u64, optimizer)
+    }
+
+    /// Handles the command and returns the loss measured before each step.
+    ///
+    /// # Errors
+    /// Returns [`TrainNetworkError::NoSamples`] if no sample is provided,
+    /// [`TrainNetworkError::InvalidLoss`] if the loss parameters are invalid,
+    /// [`TrainNetworkError::InvalidOptimizerConfig`] if the optimizer
+    /// hyperparameters are invalid, [`TrainNetworkError::Propagation`] if the
+    /// forward pass fails and [`TrainNetworkError::StorageError`] if
+    /// persisting an event fails. If another writer appended to the store
+    /// during an epoch, the epoch is run once more on the reloaded network,
+    /// and [`TrainNetworkError::Conflict`] is returned if that happens again.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{
+    ///     FileEventStore, Loss, OptimizerConfig, TrainNetworkCommand, TrainNetworkHandler,
+    ///     TrainingSample,
+    /// };
+    /// use std::path::PathBuf;
+    /// use uuid::Uuid;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let store = FileEventStore::new(PathBuf::from("events.log"));
+    /// let mut handler = TrainNetworkHandler::new(store)?;
+    /// let (input, output) = (Uuid::new_v4(), Uuid::new_v4()); // existing neurons
+    /// let sample = TrainingSample {
+    ///     inputs: vec![(input, 1.0)],
+    ///     targets: vec![(output, 0.0)],
+    /// };
+    /// let _ = handler.handle(TrainNetworkCommand {
+    ///     samples: vec![sample],
+    ///     epochs: 10,
+    ///     loss: Loss::MeanSquaredError,
+    ///     optimizer: OptimizerConfig::adam(0.01),
+    /// });
+    /// # Ok(()) }
+    /// ```
+    pub fn handle(&mut self, cmd: TrainNetworkCommand) -> Result<Vec<f64>, TrainNetworkError> {
+        let (correlation_id, causation_id) = self.correlation.next_command();
+        self.store.set_correlation(correlation_id, causation_id);
+        if cmd.samples.is_empty() {
+            return Err(TrainNetworkError::NoSamples);
+        }
+        if !cmd.loss.is_valid() {
+            return Err(TrainNetworkError::InvalidLoss);
+        }
+        if !cmd.optimizer.is_valid() {
+            return Err(TrainNetworkError::InvalidOptimizerConfig);
+        }
+        let mut losses = Vec::with_capacity(cmd.epochs);
+        for _ in 0..cmd.epochs {
+            losses.push(self.retry_on_conflict(|handler| handler.step(&cmd))?);
+        }
+        Ok(losses)
+    }
+
+    /// Performs one gradient descent step over the batch and returns the
+    /// loss measured before it, unless another writer appended to the store
+    /// since the network was loaded.
+    fn step(&mut self, cmd: &TrainNetworkCommand) -> Result<f64, TrainNetworkError> {
+        if self
+            .optimizer
+            .as_ref()
+            .is_none_or(|(config, _)| *config != cmd.optimizer)
+        {
+            self.optimizer = Some((cmd.optimizer, cmd.optimizer.build()));
+        }
+        let (_, optimizer) = self.optimizer.as_mut().expect("optimizer was just set");
+
+        let gradients = self
+            .network
+            .batch_gradients(&cmd.samples, cmd.loss)
+            .map_err(TrainNetworkError::Propagation)?;
+        // Synapse and neuron identifiers never collide, so weights and
+        // biases share one parameter map in the optimizer.
+        let parameters: BTreeMap<Uuid, f64> = gradients
+            .weights
+            .iter()
+            .chain(&gradients.biases)
+            .map(|(id, gradient)| (*id, *gradient))
+            .collect();
+        let deltas = optimizer.step(&parameters);
+        let updates = gradients
+            .weights
+            .iter()
+            .map(|(synapse_id, gradient)| {
+                let old_weight = self.network.synapses[synapse_id].weight;
+                SynapseWeightUpdate {
+                    synapse_id: *synapse_id,
+                    gradient: *gradient,
+                    old_weight,
+                    new_weight: old_weight + deltas[synapse_id],
+                }
+            })
+            .collect();
+        let bias_updates = gradients
+            .biases
+            .iter()
+            .map(|(neuron_id, gradient)| {
+                let old_bias = self.network.neurons[neuron_id].bias;
+                NeuronBiasUpdate {
+                    neuron_id: *neuron_id,
+                    gradient: *gradient,
+                    old_bias,
+                    new_bias: old_bias + deltas[neuron_id],
+                }
+            })
+            .collect();
+        let event = Event::TrainingStepCompleted(TrainingStepCompleted {
+            step: self.steps + 1,
+            loss: gradients.loss,
+            optimizer: cmd.optimizer,
+            updates,
+            bias_updates,
+        });
+        self.version = self
+            .store
+            .append_expected(std::slice::from_ref(&event), self.version)?;
+        self.network.apply(&event);
+        self.steps += 1;
+        Ok(gradients.loss)
+    }
+}
+
+impl<S: EventStore> Reload for TrainNetworkHandler<S> {
+    type Error = S::Error;
+
+    /// Rebuilds the handler from the whole log: the refused step already
+    /// advanced the optimizer, whose state can only be recovered by
+    /// replaying the recorded gradients.
+    fn reload(&mut self) -> Result<(), S::Error> {
+        let events = self.store.load()?;
+        let (steps, optimizer) = Self::history(&events);
+        self.network = Network::hydrate(&events);
+        self.steps = steps;
+        self.optimizer = optimizer;
+        self.version = events.len() as u64;
+        Ok(())
+    }
+}
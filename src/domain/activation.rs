@@ -1,11 +1,37 @@
 //! Activation functions available for neurons.
 //!
 //! Each variant provides a mathematical transformation applied to the input
-//! value during propagation. More functions can be added in the future by
-//! extending this enum.
+//! value during propagation together with its derivative. Parametric variants
+//! carry their parameter, which is serialized with them. [`Activation::all`]
+//! lists one instance of every function and is the registry used when an
+//! activation is picked at random, as described by an
+//! [`ActivationDistribution`].
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+/// `sqrt(2 / π)`, used by the tanh approximation of [`Activation::GELU`].
+const GELU_SCALE: f64 = 0.797_884_560_802_865_4;
+/// Cubic coefficient of the tanh approximation of [`Activation::GELU`].
+const GELU_CUBIC: f64 = 0.044_715;
+
+/// Every activation function, parametric ones with their default parameter.
+const ALL: [Activation; 13] = [
+    Activation::Identity,
+    Activation::Sigmoid,
+    Activation::ReLU,
+    Activation::Tanh,
+    Activation::LeakyReLU { alpha: 0.01 },
+    Activation::ELU { alpha: 1.0 },
+    Activation::GELU,
+    Activation::Softplus,
+    Activation::Swish,
+    Activation::Gaussian,
+    Activation::Sine,
+    Activation::Step,
+    Activation::Abs,
+];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub enum Activation {
     /// Returns the input unchanged.
     #[default]
@@ -16,17 +42,95 @@ pub enum Activation {
     ReLU,
     /// Hyperbolic tangent function.
     Tanh,
+    /// Leaky ReLU: `x` for positive inputs, `alpha · x` otherwise.
+    LeakyReLU {
+        /// Slope applied to negative inputs.
+        alpha: f64,
+    },
+    /// Exponential Linear Unit: `x` for positive inputs,
+    /// `alpha · (e^x - 1)` otherwise.
+    ELU {
+        /// Value approached for large negative inputs, negated.
+        alpha: f64,
+    },
+    /// Gaussian Error Linear Unit, using the usual tanh approximation.
+    GELU,
+    /// Smooth approximation of ReLU: `ln(1 + e^x)`.
+    Softplus,
+    /// Self-gated activation: `x · sigmoid(x)`.
+    Swish,
+    /// Gaussian bump: `e^{-x²}`.
+    Gaussian,
+    /// Sine function.
+    Sine,
+    /// Heaviside step: `1` for positive inputs, `0` otherwise.
+    Step,
+    /// Absolute value.
+    Abs,
 }
 
 impl Activation {
+    /// Returns every activation function.
+    ///
+    /// Parametric functions appear once with their default parameter:
+    /// `alpha = 0.01` for [`Activation::LeakyReLU`] and `alpha = 1.0` for
+    /// [`Activation::ELU`].
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::Activation;
+    ///
+    /// assert!(Activation::all().contains(&Activation::Gaussian));
+    /// ```
+    #[must_use]
+    pub fn all() -> &'static [Activation] {
+        &ALL
+    }
+
+    /// Returns `true` if the parameter of a parametric function is finite.
+    #[must_use]
+    pub fn is_valid(self) -> bool {
+        match self {
+            Activation::LeakyReLU { alpha } | Activation::ELU { alpha } => alpha.is_finite(),
+            _ => true,
+        }
+    }
+
     /// Applies the activation function to the provided value.
     #[must_use]
     pub fn apply(self, x: f64) -> f64 {
         match self {
             Activation::Identity => x,
-            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Sigmoid => sigmoid(x),
             Activation::ReLU => x.max(0.0),
             Activation::Tanh => x.tanh(),
+            Activation::LeakyReLU { alpha } => {
+                if x > 0.0 {
+                    x
+                } else {
+                    alpha * x
+                }
+            }
+            Activation::ELU { alpha } => {
+                if x > 0.0 {
+                    x
+                } else {
+                    alpha * x.exp_m1()
+                }
+            }
+            Activation::GELU => 0.5 * x * (1.0 + gelu_inner(x).tanh()),
+            Activation::Softplus => x.max(0.0) + (-x.abs()).exp().ln_1p(),
+            Activation::Swish => x * sigmoid(x),
+            Activation::Gaussian => (-x * x).exp(),
+            Activation::Sine => x.sin(),
+            Activation::Step => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::Abs => x.abs(),
         }
     }
 
@@ -34,6 +138,11 @@ impl Activation {
     ///
     /// The derivative is expressed in terms of the already activated output in
     /// order to avoid recomputing the forward pass during backpropagation.
+    /// [`Activation::GELU`], [`Activation::Swish`], [`Activation::Gaussian`],
+    /// [`Activation::Sine`] and [`Activation::Abs`] cannot recover their input
+    /// from the output and return `f64::NAN`; use
+    /// [`Activation::derivative_at`] for them. [`Activation::LeakyReLU`]
+    /// assumes a non-negative `alpha`.
     #[must_use]
     pub fn derivative(self, activated: f64) -> f64 {
         match self {
@@ -47,6 +156,226 @@ impl Activation {
                 }
             }
             Activation::Tanh => 1.0 - activated * activated,
+            Activation::LeakyReLU { alpha } => {
+                if activated > 0.0 {
+                    1.0
+                } else {
+                    alpha
+                }
+            }
+            Activation::ELU { alpha } => {
+                if activated > 0.0 {
+                    1.0
+                } else {
+                    activated + alpha
+                }
+            }
+            Activation::Softplus => -(-activated).exp_m1(),
+            Activation::Step => 0.0,
+            Activation::GELU
+            | Activation::Swish
+            | Activation::Gaussian
+            | Activation::Sine
+            | Activation::Abs => f64::NAN,
+        }
+    }
+
+    /// Returns the derivative of the activation function at the input `x`.
+    ///
+    /// The derivative is taken with respect to the value passed to
+    /// [`Activation::apply`], which unlike [`Activation::derivative`] works
+    /// for every function. At points where the function is not
+    /// differentiable the derivative of its left side is returned, except for
+    /// [`Activation::Abs`] which returns `0.0`, and [`Activation::Step`] is
+    /// treated as flat everywhere.
+    #[must_use]
+    pub fn derivative_at(self, x: f64) -> f64 {
+        match self {
+            Activation::Identity => 1.0,
+            Activation::Sigmoid => {
+                let s = sigmoid(x);
+                s * (1.0 - s)
+            }
+            Activation::ReLU => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::Tanh => 1.0 - x.tanh() * x.tanh(),
+            Activation::LeakyReLU { alpha } => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    alpha
+                }
+            }
+            Activation::ELU { alpha } => {
+                if x > 0.0 {
+                    1.0
+                } else {
+                    alpha * x.exp()
+                }
+            }
+            Activation::GELU => {
+                let t = gelu_inner(x).tanh();
+                0.5 * (1.0 + t)
+                    + 0.5 * x * (1.0 - t * t) * GELU_SCALE * (1.0 + 3.0 * GELU_CUBIC * x * x)
+            }
+            Activation::Softplus => sigmoid(x),
+            Activation::Swish => {
+                let s = sigmoid(x);
+                s + x * s * (1.0 - s)
+            }
+            Activation::Gaussian => -2.0 * x * (-x * x).exp(),
+            Activation::Sine => x.cos(),
+            Activation::Step => 0.0,
+            Activation::Abs => {
+                if x > 0.0 {
+                    1.0
+                } else if x < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// Logistic sigmoid.
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Argument of the hyperbolic tangent in the GELU approximation.
+fn gelu_inner(x: f64) -> f64 {
+    GELU_SCALE * (x + GELU_CUBIC * x * x * x)
+}
+
+/// Reasons why an [`ActivationDistribution`] cannot be sampled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActivationDistributionError {
+    /// The distribution does not list any activation.
+    Empty,
+    /// An activation is listed more than once.
+    DuplicateActivation(Activation),
+    /// A parametric activation has a non-finite parameter.
+    InvalidActivation(Activation),
+    /// A weight is negative or not finite.
+    InvalidWeight(Activation),
+    /// All weights are zero.
+    ZeroTotalWeight,
+    /// The weights are finite but add up to infinity.
+    InfiniteTotalWeight,
+}
+
+/// Weighted set of activations that random neuron commands may pick from.
+///
+/// The probability of an activation is its weight divided by the sum of all
+/// weights, so weights need not add up to one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivationDistribution {
+    /// Allowed activations with their relative weights.
+    pub weights: Vec<(Activation, f64)>,
+}
+
+impl Default for ActivationDistribution {
+    /// Uniform distribution over [`Activation::all`].
+    fn default() -> Self {
+        Self::uniform(Activation::all())
+    }
+}
+
+impl ActivationDistribution {
+    /// Gives the same weight to each of `activations`.
+    #[must_use]
+    pub fn uniform(activations: &[Activation]) -> Self {
+        Self {
+            weights: activations.iter().map(|a| (*a, 1.0)).collect(),
+        }
+    }
+
+    /// Uses the given weights.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{Activation, ActivationDistribution};
+    ///
+    /// let distribution =
+    ///     ActivationDistribution::weighted([(Activation::Tanh, 0.7), (Activation::Sigmoid, 0.3)]);
+    /// assert!(distribution.validate().is_ok());
+    /// ```
+    #[must_use]
+    pub fn weighted(weights: impl IntoIterator<Item = (Activation, f64)>) -> Self {
+        Self {
+            weights: weights.into_iter().collect(),
+        }
+    }
+
+    /// Checks that the distribution can be sampled.
+    ///
+    /// # Errors
+    /// Returns the first [`ActivationDistributionError`] found.
+    pub fn validate(&self) -> Result<(), ActivationDistributionError> {
+        if self.weights.is_empty() {
+            return Err(ActivationDistributionError::Empty);
+        }
+        for (i, (activation, weight)) in self.weights.iter().enumerate() {
+            if self.weights[..i].iter().any(|(a, _)| a == activation) {
+                return Err(ActivationDistributionError::DuplicateActivation(
+                    *activation,
+                ));
+            }
+            if !activation.is_valid() {
+                return Err(ActivationDistributionError::InvalidActivation(*activation));
+            }
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(ActivationDistributionError::InvalidWeight(*activation));
+            }
+        }
+        if self.weights.iter().all(|(_, weight)| *weight == 0.0) {
+            return Err(ActivationDistributionError::ZeroTotalWeight);
+        }
+        if !self
+            .weights
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum::<f64>()
+            .is_finite()
+        {
+            return Err(ActivationDistributionError::InfiniteTotalWeight);
+        }
+        Ok(())
+    }
+
+    /// Draws an activation different from `excluded`.
+    ///
+    /// Returns `None` when no other activation has a positive weight. The
+    /// distribution is expected to be valid.
+    pub fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        excluded: Option<Activation>,
+    ) -> Option<Activation> {
+        let candidates: Vec<(Activation, f64)> = self
+            .weights
+            .iter()
+            .copied()
+            .filter(|(activation, weight)| *weight > 0.0 && Some(*activation) != excluded)
+            .collect();
+        let total: f64 = candidates.iter().map(|(_, weight)| weight).sum();
+        if candidates.is_empty() {
+            return None;
+        }
+        let mut threshold = rng.gen_range(0.0..total);
+        for (activation, weight) in &candidates {
+            if threshold < *weight {
+                return Some(*activation);
+            }
+            threshold -= weight;
         }
+        candidates.last().map(|(activation, _)| *activation)
     }
 }
@@ -0,0 +1,117 @@
+//! Genome crossover between two networks.
+//!
+//! Neurons are matched by identifier and synapses by innovation number,
+//! falling back to their `from → to` connection when a synapse has no
+//! innovation number. Matching genes are inherited from either parent at
+//! random, while disjoint and excess genes, those present in only one
+//! parent, come from the fitter one. The child is returned as an event
+//! stream in which every created neuron and synapse is followed by a
+//! [`NeuronInherited`] or [`SynapseInherited`] event naming its origin.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+use uuid::Uuid;
+
+use super::innovation::GeneKey;
+use super::{
+    Event, Network, NeuronAdded, NeuronInherited, Synapse, SynapseDisabled, SynapseInherited,
+};
+
+/// A network taking part in a crossover.
+#[derive(Debug, Clone, Copy)]
+pub struct CrossoverParent<'a> {
+    /// Identifier recorded in the lineage events of the genes it passes on,
+    /// such as its population member identifier.
+    pub id: Uuid,
+    /// Genome of the parent.
+    pub network: &'a Network,
+    /// Fitness of the parent; higher is better.
+    pub fitness: f64,
+}
+
+/// Builds the event stream of a child of `first` and `second`.
+///
+/// The child has the neurons and synapses of the fitter parent, with ties
+/// favouring `first`. For each gene the other parent shares, a fair coin
+/// decides which parent provides its activation and bias, or its weight and
+/// enabled flag. Identifiers and connections always follow the fitter
+/// parent, and curiosity scores start afresh.
+///
+/// # Examples
+/// ```
+/// use aei_framework::{crossover, CrossoverParent, DomainNetwork, Event};
+/// use rand::thread_rng;
+/// use uuid::Uuid;
+///
+/// let (a, b) = (DomainNetwork::default(), DomainNetwork::default());
+/// let child: Vec<Event> = crossover(
+///     CrossoverParent { id: Uuid::new_v4(), network: &a, fitness: 1.0 },
+///     CrossoverParent { id: Uuid::new_v4(), network: &b, fitness: 0.5 },
+///     &mut thread_rng(),
+/// );
+/// assert!(child.is_empty());
+/// ```
+pub fn crossover<R: Rng + ?Sized>(
+    first: CrossoverParent<'_>,
+    second: CrossoverParent<'_>,
+    rng: &mut R,
+) -> Vec<Event> {
+    let (fitter, other) = if second.fitness > first.fitness {
+        (second, first)
+    } else {
+        (first, second)
+    };
+    let mut events = Vec::new();
+
+    for neuron in fitter.network.neurons.values() {
+        let matching = other.network.neurons.get(&neuron.id);
+        let (parent_id, source) = match matching {
+            Some(theirs) if rng.gen_bool(0.5) => (other.id, theirs),
+            _ => (fitter.id, neuron),
+        };
+        events.push(Event::NeuronAdded(NeuronAdded {
+            neuron_id: neuron.id,
+            activation: source.activation,
+            role: neuron.role,
+            bias: source.bias,
+        }));
+        events.push(Event::NeuronInherited(NeuronInherited {
+            neuron_id: neuron.id,
+            parent_id,
+            matching: matching.is_some(),
+        }));
+    }
+
+    let theirs: HashMap<GeneKey, &Synapse> = other
+        .network
+        .synapses
+        .values()
+        .map(|s| (GeneKey::of(s), s))
+        .collect();
+    for synapse in fitter.network.synapses.values() {
+        let matching = theirs.get(&GeneKey::of(synapse)).copied();
+        let (parent_id, source) = match matching {
+            Some(theirs) if rng.gen_bool(0.5) => (other.id, theirs),
+            _ => (fitter.id, synapse),
+        };
+        events.push(Event::SynapseCreated {
+            id: synapse.id,
+            from: synapse.from,
+            to: synapse.to,
+            weight: source.weight,
+            innovation: synapse.innovation,
+        });
+        if !source.enabled {
+            events.push(Event::SynapseDisabled(SynapseDisabled {
+                synapse_id: synapse.id,
+            }));
+        }
+        events.push(Event::SynapseInherited(SynapseInherited {
+            synapse_id: synapse.id,
+            parent_id,
+            matching: matching.is_some(),
+        }));
+    }
+    events
+}
@@ -0,0 +1,319 @@
+//! Strategies computing the curiosity score of neurons and synapses.
+//!
+//! A [`CuriosityStrategy`] maps the event history of a target to a score;
+//! the higher the score, the more novel the target is considered. The name
+//! of the strategy is recorded in every
+//! [`CuriosityScoreUpdated`](crate::domain::CuriosityScoreUpdated) event so
+//! that the log tells how each score was produced.
+//!
+//! Strategies read the history through a [`CuriosityIndex`], maintained
+//! incrementally as events are applied, so that scoring a target does not
+//! require scanning the whole log.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use uuid::Uuid;
+
+use super::{Event, Network};
+
+/// Computes the curiosity score of a neuron or synapse.
+pub trait CuriosityStrategy: fmt::Debug {
+    /// Name recorded alongside the scores produced by the strategy.
+    fn name(&self) -> &str;
+
+    /// Scores `target` given the `index` of the event history and the
+    /// current `network` state.
+    fn score(&self, target: Uuid, index: &CuriosityIndex, network: &Network) -> f64;
+}
+
+/// Scores targets by the rarity of the events touching them:
+/// `1 / (1 + occurrences)`.
+///
+/// # Examples
+/// ```
+/// use aei_framework::{CuriosityIndex, CuriosityStrategy, DomainNetwork, Rarity};
+/// use uuid::Uuid;
+///
+/// let index = CuriosityIndex::default();
+/// let score = Rarity.score(Uuid::new_v4(), &index, &DomainNetwork::default());
+/// assert_eq!(score, 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rarity;
+
+impl CuriosityStrategy for Rarity {
+    fn name(&self) -> &str {
+        "rarity"
+    }
+
+    fn score(&self, target: Uuid, index: &CuriosityIndex, _network: &Network) -> f64 {
+        1.0 / (1.0 + index.occurrences(target) as f64)
+    }
+}
+
+/// Rarity in which older occurrences weigh less.
+///
+/// An occurrence followed by `age` events counts for
+/// `0.5^(age / half_life)`, and the score is `1 / (1 + Σ weights)`. A target
+/// left untouched for long thus becomes curious again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecencyWeightedRarity {
+    /// Number of events after which an occurrence counts half; positive.
+    pub half_life: f64,
+}
+
+impl CuriosityStrategy for RecencyWeightedRarity {
+    fn name(&self) -> &str {
+        "recency_weighted_rarity"
+    }
+
+    fn score(&self, target: Uuid, index: &CuriosityIndex, _network: &Network) -> f64 {
+        let last = index.len().saturating_sub(1);
+        let weight: f64 = index
+            .positions(target)
+            .iter()
+            .map(|position| 0.5f64.powf((last - position) as f64 / self.half_life))
+            .sum();
+        1.0 / (1.0 + weight)
+    }
+}
+
+/// Scores targets by how much their parameter moved over the history.
+///
+/// The variance `v` of the successive weights of a synapse, or biases of a
+/// neuron, gives the score `v / (1 + v)`: parameters still being reshaped
+/// are considered novel, settled ones are not.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WeightVariance;
+
+impl CuriosityStrategy for WeightVariance {
+    fn name(&self) -> &str {
+        "weight_variance"
+    }
+
+    fn score(&self, target: Uuid, index: &CuriosityIndex, _network: &Network) -> f64 {
+        let variance = index.parameter_variance(target).unwrap_or_default();
+        variance / (1.0 + variance)
+    }
+}
+
+/// Scores targets by an error signal supplied by the caller, such as the
+/// error of a predictive model on the activity of each target.
+///
+/// An error `e` gives the score `|e| / (1 + |e|)`.
+///
+/// # Examples
+/// ```
+/// use aei_framework::{CuriosityIndex, CuriosityStrategy, DomainNetwork, PredictionError};
+/// use uuid::Uuid;
+///
+/// let strategy = PredictionError::new(|_| -1.0);
+/// let index = CuriosityIndex::default();
+/// let score = strategy.score(Uuid::new_v4(), &index, &DomainNetwork::default());
+/// assert_eq!(score, 0.5);
+/// ```
+#[derive(Clone, Copy)]
+pub struct PredictionError<F: Fn(Uuid) -> f64> {
+    error: F,
+}
+
+impl<F: Fn(Uuid) -> f64> PredictionError<F> {
+    /// Creates a strategy reading the error of each target from `error`.
+    pub fn new(error: F) -> Self {
+        Self { error }
+    }
+}
+
+impl<F: Fn(Uuid) -> f64> fmt::Debug for PredictionError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PredictionError").finish_non_exhaustive()
+    }
+}
+
+impl<F: Fn(Uuid) -> f64> CuriosityStrategy for PredictionError<F> {
+    fn name(&self) -> &str {
+        "prediction_error"
+    }
+
+    fn score(&self, target: Uuid, _index: &CuriosityIndex, _network: &Network) -> f64 {
+        let error = (self.error)(target).abs();
+        error / (1.0 + error)
+    }
+}
+
+/// Incremental index of the events touching each neuron and synapse.
+///
+/// Applying an event costs time proportional to the number of elements it
+/// touches, and every query answers without going back to the log.
+///
+/// # Examples
+/// ```
+/// use aei_framework::{Activation, CuriosityIndex, Event, NeuronAdded, NeuronRole};
+/// use uuid::Uuid;
+///
+/// let id = Uuid::new_v4();
+/// let mut index = CuriosityIndex::default();
+/// index.apply(&Event::NeuronAdded(NeuronAdded {
+///     neuron_id: id,
+///     activation: Activation::Identity,
+///     role: NeuronRole::Hidden,
+///     bias: 0.5,
+/// }));
+/// assert_eq!(index.occurrences(id), 1);
+/// assert_eq!(index.parameter_variance(id), Some(0.0));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CuriosityIndex {
+    len: u64,
+    targets: HashMap<Uuid, TargetHistory>,
+}
+
+/// Indexed history of a single neuron or synapse.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct TargetHistory {
+    /// Positions in the log of the events touching the target.
+    positions: Vec<u64>,
+    /// Successive weights or biases of the target.
+    parameter: RunningVariance,
+}
+
+/// Welford accumulator of the variance of a sequence of values.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct RunningVariance {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl RunningVariance {
+    fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+}
+
+impl CuriosityIndex {
+    /// Builds the index of a full event history.
+    pub fn from_events(events: &[Event]) -> Self {
+        let mut index = Self::default();
+        for event in events {
+            index.apply(event);
+        }
+        index
+    }
+
+    /// Indexes the next event of the history.
+    pub fn apply(&mut self, event: &Event) {
+        for id in touched(event) {
+            self.targets.entry(id).or_default().positions.push(self.len);
+        }
+        for (id, value) in parameters(event) {
+            self.targets.entry(id).or_default().parameter.push(value);
+        }
+        self.len += 1;
+    }
+
+    /// Number of indexed events.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no event was indexed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of events touching `id`.
+    pub fn occurrences(&self, id: Uuid) -> usize {
+        self.positions(id).len()
+    }
+
+    /// Positions in the history, in increasing order, of the events
+    /// touching `id`.
+    pub fn positions(&self, id: Uuid) -> &[u64] {
+        self.targets
+            .get(&id)
+            .map_or(&[], |target| target.positions.as_slice())
+    }
+
+    /// Variance of the successive weights of synapse `id`, or biases of
+    /// neuron `id`, or `None` if the history never set it.
+    pub fn parameter_variance(&self, id: Uuid) -> Option<f64> {
+        self.targets
+            .get(&id)
+            .and_then(|target| target.parameter.variance())
+    }
+}
+
+/// Weights or biases set by `event`, with the element they belong to.
+fn parameters(event: &Event) -> Vec<(Uuid, f64)> {
+    match event {
+        Event::NeuronAdded(e) => vec![(e.neuron_id, e.bias)],
+        Event::RandomNeuronAdded(e) => vec![(e.neuron_id, e.bias)],
+        Event::NeuronBiasSet(e) => vec![(e.neuron_id, e.new_bias)],
+        Event::NeuronBiasMutated(e) => vec![(e.neuron_id, e.new_bias)],
+        Event::SynapseCreated { id, weight, .. } => vec![(*id, *weight)],
+        Event::RandomSynapseAdded(e) => vec![(e.synapse_id, e.weight)],
+        Event::SynapseWeightSet(e) => vec![(e.synapse_id, e.new_weight)],
+        Event::SynapseWeightMutated(e) => vec![(e.synapse_id, e.new_weight)],
+        Event::SynapseSplit(e) => vec![
+            (e.neuron_id, 0.0),
+            (e.incoming_synapse_id, 1.0),
+            (e.outgoing_synapse_id, e.weight),
+        ],
+        Event::TrainingStepCompleted(e) => e
+            .updates
+            .iter()
+            .map(|u| (u.synapse_id, u.new_weight))
+            .chain(e.bias_updates.iter().map(|u| (u.neuron_id, u.new_bias)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Neurons and synapses involved in `event`, each listed once.
+fn touched(event: &Event) -> Vec<Uuid> {
+    let mut ids = match event {
+        Event::RandomNeuronAdded(e) => vec![e.neuron_id],
+        Event::RandomNeuronRemoved(e) => vec![e.neuron_id],
+        Event::NeuronAdded(e) => vec![e.neuron_id],
+        Event::NeuronRemoved(e) => vec![e.neuron_id],
+        Event::SynapseCreated { id, from, to, .. } => vec![*id, *from, *to],
+        Event::SynapseRemoved { id } => vec![*id],
+        Event::RandomSynapseAdded(e) => vec![e.synapse_id, e.from, e.to],
+        Event::RandomSynapseRemoved(e) => vec![e.synapse_id],
+        Event::SynapseWeightMutated(e) => vec![e.synapse_id],
+        Event::SynapseWeightSet(e) => vec![e.synapse_id],
+        Event::NeuronActivationMutated(e) => vec![e.neuron_id],
+        Event::NeuronBiasSet(e) => vec![e.neuron_id],
+        Event::NeuronBiasMutated(e) => vec![e.neuron_id],
+        // Score bookkeeping, decay included, is not a structural touch.
+        Event::CuriosityScoreUpdated(_) | Event::RunSeeded(_) => Vec::new(),
+        Event::SynapseDisabled(e) => vec![e.synapse_id],
+        Event::SynapseEnabled(e) => vec![e.synapse_id],
+        Event::NeuronInherited(e) => vec![e.neuron_id],
+        Event::SynapseInherited(e) => vec![e.synapse_id],
+        Event::SynapseSplit(e) => vec![
+            e.synapse_id,
+            e.neuron_id,
+            e.incoming_synapse_id,
+            e.outgoing_synapse_id,
+        ],
+        Event::TrainingStepCompleted(e) => e
+            .updates
+            .iter()
+            .map(|u| u.synapse_id)
+            .chain(e.bias_updates.iter().map(|u| u.neuron_id))
+            .collect(),
+    };
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
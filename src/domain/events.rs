@@ -3,7 +3,7 @@
 //! Events are persisted in an append-only log and can be replayed to
 //! reconstruct the state of the system.
 
-use super::Activation;
+use super::{Activation, NeuronRole, OptimizerConfig};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -24,6 +24,9 @@ pub enum Event {
         from: Uuid,
         to: Uuid,
         weight: f64,
+        /// Innovation number of the connection, if one was assigned.
+        #[serde(default)]
+        innovation: Option<u64>,
     },
     /// A synapse was removed from the network.
     SynapseRemoved { id: Uuid },
@@ -37,8 +40,26 @@ pub enum Event {
     SynapseWeightSet(SynapseWeightSet),
     /// The activation function of a neuron was mutated.
     NeuronActivationMutated(NeuronActivationMutated),
+    /// The bias of a neuron was explicitly set.
+    NeuronBiasSet(NeuronBiasSet),
+    /// The bias of a neuron was mutated.
+    NeuronBiasMutated(NeuronBiasMutated),
     /// The curiosity score of a neuron or synapse was updated.
     CuriosityScoreUpdated(CuriosityScoreUpdated),
+    /// A gradient descent step updated the synapse weights.
+    TrainingStepCompleted(TrainingStepCompleted),
+    /// A seeded run started; recorded as the first event of the log.
+    RunSeeded(RunSeeded),
+    /// A synapse was replaced by a new neuron and two synapses.
+    SynapseSplit(SynapseSplit),
+    /// A synapse stopped taking part in propagation.
+    SynapseDisabled(SynapseDisabled),
+    /// A disabled synapse takes part in propagation again.
+    SynapseEnabled(SynapseEnabled),
+    /// A neuron created by crossover was inherited from a parent.
+    NeuronInherited(NeuronInherited),
+    /// A synapse created by crossover was inherited from a parent.
+    SynapseInherited(SynapseInherited),
 }
 
 /// Event emitted when a random neuron is added to the network.
@@ -48,6 +69,12 @@ pub struct RandomNeuronAdded {
     pub neuron_id: Uuid,
     /// Activation assigned to the neuron.
     pub activation: Activation,
+    /// Structural role of the neuron.
+    #[serde(default)]
+    pub role: NeuronRole,
+    /// Bias added to the weighted input sum of the neuron.
+    #[serde(default)]
+    pub bias: f64,
 }
 
 /// Event emitted when a random neuron is removed from the network.
@@ -62,11 +89,16 @@ pub struct RandomNeuronRemoved {
 /// # Examples
 ///
 /// ```
-/// use aei_framework::{Activation, Event, NeuronAdded};
+/// use aei_framework::{Activation, Event, NeuronAdded, NeuronRole};
 /// use uuid::Uuid;
 ///
 /// let id = Uuid::new_v4();
-/// let event = Event::NeuronAdded(NeuronAdded { neuron_id: id, activation: Activation::ReLU });
+/// let event = Event::NeuronAdded(NeuronAdded {
+///     neuron_id: id,
+///     activation: Activation::ReLU,
+///     role: NeuronRole::Hidden,
+///     bias: 0.0,
+/// });
 /// # let _ = event;
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,6 +107,12 @@ pub struct NeuronAdded {
     pub neuron_id: Uuid,
     /// Activation assigned to the neuron.
     pub activation: Activation,
+    /// Structural role of the neuron.
+    #[serde(default)]
+    pub role: NeuronRole,
+    /// Bias added to the weighted input sum of the neuron.
+    #[serde(default)]
+    pub bias: f64,
 }
 
 /// Event emitted when a neuron is removed from the network.
@@ -106,6 +144,76 @@ pub struct RandomSynapseAdded {
     pub to: Uuid,
     /// Weight associated with the synapse.
     pub weight: f64,
+    /// Innovation number of the connection, if one was assigned.
+    #[serde(default)]
+    pub innovation: Option<u64>,
+}
+
+/// Event emitted when a synapse `from → to` is split by inserting a neuron.
+///
+/// Applying it disables the split synapse, adds a hidden neuron with zero bias
+/// and connects `from → neuron` with weight `1.0` and `neuron → to` with the
+/// weight of the split synapse, as a single atomic change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynapseSplit {
+    /// Identifier of the split synapse.
+    pub synapse_id: Uuid,
+    /// Source neuron of the split synapse.
+    pub from: Uuid,
+    /// Target neuron of the split synapse.
+    pub to: Uuid,
+    /// Weight of the split synapse, carried over to the outgoing synapse.
+    pub weight: f64,
+    /// Identifier of the inserted neuron.
+    pub neuron_id: Uuid,
+    /// Activation of the inserted neuron.
+    pub activation: Activation,
+    /// Identifier of the synapse from the source to the inserted neuron.
+    pub incoming_synapse_id: Uuid,
+    /// Identifier of the synapse from the inserted neuron to the target.
+    pub outgoing_synapse_id: Uuid,
+    /// Innovation number of the incoming synapse, if one was assigned.
+    #[serde(default)]
+    pub incoming_innovation: Option<u64>,
+    /// Innovation number of the outgoing synapse, if one was assigned.
+    #[serde(default)]
+    pub outgoing_innovation: Option<u64>,
+}
+
+/// Event emitted when a synapse is disabled, keeping its weight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynapseDisabled {
+    /// Identifier of the disabled synapse.
+    pub synapse_id: Uuid,
+}
+
+/// Event emitted when a disabled synapse is enabled again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynapseEnabled {
+    /// Identifier of the enabled synapse.
+    pub synapse_id: Uuid,
+}
+
+/// Lineage event following the creation of a neuron by crossover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeuronInherited {
+    /// Identifier of the created neuron.
+    pub neuron_id: Uuid,
+    /// Parent whose activation and bias the neuron received.
+    pub parent_id: Uuid,
+    /// Whether both parents carried the neuron.
+    pub matching: bool,
+}
+
+/// Lineage event following the creation of a synapse by crossover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynapseInherited {
+    /// Identifier of the created synapse.
+    pub synapse_id: Uuid,
+    /// Parent whose weight and enabled flag the synapse received.
+    pub parent_id: Uuid,
+    /// Whether both parents carried the gene.
+    pub matching: bool,
 }
 
 /// Event emitted when a random synapse is removed from the network.
@@ -163,6 +271,42 @@ pub struct NeuronActivationMutated {
     pub new_activation: Activation,
 }
 
+/// Event emitted when the bias of a neuron is set explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use aei_framework::{Event, NeuronBiasSet};
+/// use uuid::Uuid;
+///
+/// let event = Event::NeuronBiasSet(NeuronBiasSet {
+///     neuron_id: Uuid::new_v4(),
+///     old_bias: 0.0,
+///     new_bias: -0.5,
+/// });
+/// # let _ = event;
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeuronBiasSet {
+    /// Identifier of the updated neuron.
+    pub neuron_id: Uuid,
+    /// Bias of the neuron before the update.
+    pub old_bias: f64,
+    /// New bias assigned to the neuron.
+    pub new_bias: f64,
+}
+
+/// Event emitted when the bias of a neuron changes due to mutation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeuronBiasMutated {
+    /// Identifier of the mutated neuron.
+    pub neuron_id: Uuid,
+    /// Bias of the neuron before mutation.
+    pub old_bias: f64,
+    /// Newly assigned bias after mutation.
+    pub new_bias: f64,
+}
+
 /// Event emitted when a curiosity score changes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CuriosityScoreUpdated {
@@ -172,4 +316,72 @@ pub struct CuriosityScoreUpdated {
     pub old_score: f64,
     /// Newly computed curiosity score.
     pub new_score: f64,
+    /// Name of the [`CuriosityStrategy`](crate::domain::CuriosityStrategy)
+    /// that computed the score, or `"decay"` for a score lowered by the
+    /// periodic decay. Logs predating strategies only hold `"rarity"` scores.
+    #[serde(default = "rarity")]
+    pub strategy: String,
+}
+
+fn rarity() -> String {
+    "rarity".to_string()
+}
+
+/// Weight change applied to a single synapse during a training step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynapseWeightUpdate {
+    /// Identifier of the updated synapse.
+    pub synapse_id: Uuid,
+    /// Gradient of the loss with respect to the weight.
+    pub gradient: f64,
+    /// Weight of the synapse before the step.
+    pub old_weight: f64,
+    /// Weight of the synapse after the step.
+    pub new_weight: f64,
+}
+
+/// Bias change applied to a single neuron during a training step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NeuronBiasUpdate {
+    /// Identifier of the updated neuron.
+    pub neuron_id: Uuid,
+    /// Gradient of the loss with respect to the bias.
+    pub gradient: f64,
+    /// Bias of the neuron before the step.
+    pub old_bias: f64,
+    /// Bias of the neuron after the step.
+    pub new_bias: f64,
+}
+
+/// Event emitted when a training step updates the synapse weights and neuron
+/// biases.
+///
+/// All weight changes of one step are recorded together so that replaying
+/// the log reproduces the trained network exactly. The optimizer and the
+/// recorded gradients allow its state to be rebuilt when training resumes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingStepCompleted {
+    /// One-based index of the step within the network's training history.
+    pub step: u64,
+    /// Loss over the training samples before the weights were updated.
+    pub loss: f64,
+    /// Optimizer that turned the gradients into weight changes.
+    pub optimizer: OptimizerConfig,
+    /// Weight changes, ordered by synapse identifier.
+    pub updates: Vec<SynapseWeightUpdate>,
+    /// Bias changes, ordered by neuron identifier.
+    #[serde(default)]
+    pub bias_updates: Vec<NeuronBiasUpdate>,
+}
+
+/// Event recording the seed from which every random choice of a run derives.
+///
+/// Handlers created with
+/// [`NetworkHandlerBase::with_seed`](crate::application::NetworkHandlerBase::with_seed)
+/// append it when they open an empty log, and reuse the recorded seed
+/// afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunSeeded {
+    /// Seed of the run.
+    pub seed: u64,
 }
@@ -0,0 +1,163 @@
+//! Historical markings of structural genes.
+//!
+//! Every connection `from → to` created by a structural mutation receives an
+//! innovation number. Within one generation the same connection always gets
+//! the same number, even when it appears in several networks, so synapses of
+//! different networks can be matched gene by gene during crossover.
+//!
+//! An [`InnovationTracker`] is a cheap handle: its clones share the same
+//! counter, which lets several handlers, or the handlers of several networks,
+//! draw from a single sequence.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use uuid::Uuid;
+
+use super::{Event, Synapse};
+
+/// Counter and per-generation registry of innovation numbers.
+#[derive(Debug, Default)]
+struct TrackerState {
+    /// Number assigned to the next new connection.
+    next: u64,
+    /// Connections numbered during the current generation.
+    current: HashMap<(Uuid, Uuid), u64>,
+}
+
+/// Assigns innovation numbers to `from → to` connections.
+///
+/// # Examples
+/// ```
+/// use aei_framework::InnovationTracker;
+/// use uuid::Uuid;
+///
+/// let tracker = InnovationTracker::new();
+/// let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+/// let first = tracker.innovation(a, b);
+/// assert_eq!(tracker.clone().innovation(a, b), first);
+/// assert_ne!(tracker.innovation(b, a), first);
+///
+/// tracker.next_generation();
+/// assert_ne!(tracker.innovation(a, b), first);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InnovationTracker {
+    state: Arc<Mutex<TrackerState>>,
+}
+
+impl InnovationTracker {
+    /// Creates a tracker whose first innovation number is `0`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker numbering after every innovation recorded in
+    /// `events`.
+    ///
+    /// The connections of the generation in progress are not restored, so
+    /// the first connection created afterwards always gets a new number.
+    #[must_use]
+    pub fn from_events(events: &[Event]) -> Self {
+        let tracker = Self::new();
+        tracker.observe_events(events);
+        tracker
+    }
+
+    /// Returns the innovation number of the connection `from → to`.
+    ///
+    /// A connection already numbered during the current generation keeps its
+    /// number; any other connection receives the next free one.
+    pub fn innovation(&self, from: Uuid, to: Uuid) -> u64 {
+        let mut state = self.lock();
+        if let Some(innovation) = state.current.get(&(from, to)) {
+            return *innovation;
+        }
+        let innovation = state.next;
+        state.next += 1;
+        state.current.insert((from, to), innovation);
+        innovation
+    }
+
+    /// Makes sure `innovation` is never assigned to a new connection.
+    pub fn observe(&self, innovation: u64) {
+        let mut state = self.lock();
+        state.next = state.next.max(innovation + 1);
+    }
+
+    /// Makes sure no innovation recorded in `events` is assigned to a new
+    /// connection.
+    ///
+    /// A connection of the current generation whose number is recorded for
+    /// another connection, e.g. because it was numbered for an append that
+    /// another writer beat, is forgotten and gets a new number next time.
+    pub fn observe_events(&self, events: &[Event]) {
+        let mut state = self.lock();
+        for (connection, innovation) in events.iter().flat_map(recorded_innovations) {
+            state.next = state.next.max(innovation + 1);
+            state
+                .current
+                .retain(|numbered, number| *number != innovation || *numbered == connection);
+        }
+    }
+
+    /// Starts a new generation: connections created from now on receive new
+    /// numbers even if they were numbered before.
+    pub fn next_generation(&self) {
+        self.lock().current.clear();
+    }
+
+    /// Returns the number the next new connection will receive.
+    #[must_use]
+    pub fn peek(&self) -> u64 {
+        self.lock().next
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TrackerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Key under which synapses of different networks are matched: their
+/// innovation number or, for synapses without one, their connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum GeneKey {
+    Innovation(u64),
+    Connection(Uuid, Uuid),
+}
+
+impl GeneKey {
+    pub(crate) fn of(synapse: &Synapse) -> Self {
+        match synapse.innovation {
+            Some(innovation) => GeneKey::Innovation(innovation),
+            None => GeneKey::Connection(synapse.from, synapse.to),
+        }
+    }
+}
+
+/// Innovation numbers carried by an event, with their connections.
+fn recorded_innovations(event: &Event) -> Vec<((Uuid, Uuid), u64)> {
+    match event {
+        Event::SynapseCreated {
+            from,
+            to,
+            innovation,
+            ..
+        } => innovation.map(|i| ((*from, *to), i)).into_iter().collect(),
+        Event::RandomSynapseAdded(e) => e
+            .innovation
+            .map(|i| ((e.from, e.to), i))
+            .into_iter()
+            .collect(),
+        Event::SynapseSplit(e) => e
+            .incoming_innovation
+            .map(|i| ((e.from, e.neuron_id), i))
+            .into_iter()
+            .chain(e.outgoing_innovation.map(|i| ((e.neuron_id, e.to), i)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,161 @@
+//! Loss functions comparing network outputs with expected values.
+//!
+//! Each [`Loss`] provides its value and its gradient with respect to the
+//! outputs, so it can drive training as well as evaluation.
+
+use serde::{Deserialize, Serialize};
+
+/// Smallest probability used by [`Loss::BinaryCrossEntropy`] to keep
+/// logarithms finite.
+const PROBABILITY_EPSILON: f64 = 1e-12;
+
+/// Loss functions available for training and evaluation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Loss {
+    /// Mean of the squared errors.
+    #[default]
+    MeanSquaredError,
+    /// Mean of the absolute errors.
+    MeanAbsoluteError,
+    /// Binary cross-entropy of outputs read as probabilities in `[0, 1]`.
+    BinaryCrossEntropy,
+    /// Cross-entropy between the softmax of the outputs, read as logits, and
+    /// the target distribution.
+    SoftmaxCrossEntropy,
+    /// Mean Huber loss: quadratic within `delta` of the target and linear
+    /// beyond.
+    Huber {
+        /// Error magnitude at which the loss becomes linear.
+        delta: f64,
+    },
+}
+
+impl Loss {
+    /// Computes the loss of `outputs` against `targets`.
+    ///
+    /// Returns `0.0` for empty slices.
+    ///
+    /// # Panics
+    /// Panics if `outputs` and `targets` have different lengths.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::Loss;
+    ///
+    /// assert_eq!(Loss::MeanSquaredError.compute(&[1.0, 3.0], &[0.0, 1.0]), 2.5);
+    /// assert_eq!(Loss::MeanAbsoluteError.compute(&[1.0, 3.0], &[0.0, 1.0]), 1.5);
+    /// ```
+    #[must_use]
+    pub fn compute(self, outputs: &[f64], targets: &[f64]) -> f64 {
+        assert_eq!(
+            outputs.len(),
+            targets.len(),
+            "outputs and targets differ in length"
+        );
+        if outputs.is_empty() {
+            return 0.0;
+        }
+        let count = outputs.len() as f64;
+        let pairs = outputs.iter().zip(targets);
+        match self {
+            Loss::MeanSquaredError => pairs.map(|(y, t)| (y - t) * (y - t)).sum::<f64>() / count,
+            Loss::MeanAbsoluteError => pairs.map(|(y, t)| (y - t).abs()).sum::<f64>() / count,
+            Loss::BinaryCrossEntropy => {
+                -pairs
+                    .map(|(y, t)| {
+                        let y = clamp_probability(*y);
+                        t * y.ln() + (1.0 - t) * (1.0 - y).ln()
+                    })
+                    .sum::<f64>()
+                    / count
+            }
+            Loss::SoftmaxCrossEntropy => {
+                let log_probs = log_softmax(outputs);
+                -log_probs
+                    .iter()
+                    .zip(targets)
+                    .map(|(lp, t)| t * lp)
+                    .sum::<f64>()
+            }
+            Loss::Huber { delta } => {
+                pairs
+                    .map(|(y, t)| {
+                        let error = (y - t).abs();
+                        if error <= delta {
+                            0.5 * error * error
+                        } else {
+                            delta * (error - 0.5 * delta)
+                        }
+                    })
+                    .sum::<f64>()
+                    / count
+            }
+        }
+    }
+
+    /// Computes the gradient of the loss with respect to each output.
+    ///
+    /// # Panics
+    /// Panics if `outputs` and `targets` have different lengths.
+    #[must_use]
+    pub fn gradient(self, outputs: &[f64], targets: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            outputs.len(),
+            targets.len(),
+            "outputs and targets differ in length"
+        );
+        let count = outputs.len() as f64;
+        let pairs = outputs.iter().zip(targets);
+        match self {
+            Loss::MeanSquaredError => pairs.map(|(y, t)| 2.0 * (y - t) / count).collect(),
+            Loss::MeanAbsoluteError => pairs
+                .map(|(y, t)| {
+                    let error = y - t;
+                    if error == 0.0 {
+                        0.0
+                    } else {
+                        error.signum() / count
+                    }
+                })
+                .collect(),
+            Loss::BinaryCrossEntropy => pairs
+                .map(|(y, t)| {
+                    let y = clamp_probability(*y);
+                    (y - t) / (y * (1.0 - y)) / count
+                })
+                .collect(),
+            Loss::SoftmaxCrossEntropy => {
+                let total: f64 = targets.iter().sum();
+                log_softmax(outputs)
+                    .iter()
+                    .zip(targets)
+                    .map(|(lp, t)| lp.exp() * total - t)
+                    .collect()
+            }
+            Loss::Huber { delta } => pairs
+                .map(|(y, t)| (y - t).clamp(-delta, delta) / count)
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if the loss parameters are valid.
+    #[must_use]
+    pub fn is_valid(self) -> bool {
+        match self {
+            Loss::Huber { delta } => delta.is_finite() && delta > 0.0,
+            _ => true,
+        }
+    }
+}
+
+/// Restricts a probability to the open interval `(0, 1)`.
+fn clamp_probability(p: f64) -> f64 {
+    p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON)
+}
+
+/// Numerically stable logarithm of the softmax of `logits`.
+fn log_softmax(logits: &[f64]) -> Vec<f64> {
+    let max = logits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let log_sum = logits.iter().map(|x| (x - max).exp()).sum::<f64>().ln() + max;
+    logits.iter().map(|x| x - log_sum).collect()
+}
@@ -65,7 +65,9 @@ pub enum MemoryEvent {
 }
 
 /// Aggregate maintaining a bounded buffer of memory entries.
-#[derive(Debug, Clone)]
+///
+/// The aggregate can be serialized as a snapshot of the events it reflects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdaptiveMemory {
     /// Stored memory entries ordered by insertion time.
     pub entries: Vec<MemoryEntry>,
@@ -0,0 +1,243 @@
+//! Metrics scoring a [`Network`] against a dataset.
+//!
+//! Classification metrics interpret the values of the target neurons as a
+//! class prediction: a single output is thresholded at `0.5`, several outputs
+//! select the index of the largest value. Regression metrics compare the raw
+//! values with the expected ones.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Loss, Network, PropagationError, TrainingSample};
+
+/// Counts of actual classes (rows) against predicted classes (columns).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfusionMatrix {
+    counts: Vec<Vec<usize>>,
+}
+
+impl ConfusionMatrix {
+    /// Creates an empty matrix for `classes` classes.
+    #[must_use]
+    pub fn new(classes: usize) -> Self {
+        Self {
+            counts: vec![vec![0; classes]; classes],
+        }
+    }
+
+    /// Builds a matrix from `(actual, predicted)` class pairs.
+    ///
+    /// # Panics
+    /// Panics if a class is not lower than `classes`.
+    #[must_use]
+    pub fn from_pairs(classes: usize, pairs: impl IntoIterator<Item = (usize, usize)>) -> Self {
+        let mut matrix = Self::new(classes);
+        for (actual, predicted) in pairs {
+            matrix.record(actual, predicted);
+        }
+        matrix
+    }
+
+    /// Records one prediction.
+    ///
+    /// # Panics
+    /// Panics if a class is not lower than [`ConfusionMatrix::classes`].
+    pub fn record(&mut self, actual: usize, predicted: usize) {
+        self.counts[actual][predicted] += 1;
+    }
+
+    /// Number of classes tracked by the matrix.
+    #[must_use]
+    pub fn classes(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Number of samples of class `actual` predicted as `predicted`.
+    #[must_use]
+    pub fn count(&self, actual: usize, predicted: usize) -> usize {
+        self.counts[actual][predicted]
+    }
+
+    /// Total number of recorded predictions.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts.iter().flatten().sum()
+    }
+
+    /// Fraction of correct predictions, or `0.0` when nothing was recorded.
+    #[must_use]
+    pub fn accuracy(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        let correct: usize = (0..self.classes()).map(|c| self.counts[c][c]).sum();
+        correct as f64 / total as f64
+    }
+
+    /// Fraction of predictions of `class` that were correct, or `0.0` when the
+    /// class was never predicted.
+    #[must_use]
+    pub fn precision(&self, class: usize) -> f64 {
+        let predicted: usize = self.counts.iter().map(|row| row[class]).sum();
+        ratio(self.counts[class][class], predicted)
+    }
+
+    /// Fraction of samples of `class` that were found, or `0.0` when the class
+    /// never occurred.
+    #[must_use]
+    pub fn recall(&self, class: usize) -> f64 {
+        let actual: usize = self.counts[class].iter().sum();
+        ratio(self.counts[class][class], actual)
+    }
+}
+
+/// Scores of a network over a dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// Loss averaged over the samples.
+    pub loss: f64,
+    /// Class predictions against expected classes.
+    pub confusion: ConfusionMatrix,
+    /// Coefficient of determination over all target values.
+    pub r_squared: f64,
+}
+
+impl Evaluation {
+    /// Fraction of samples whose predicted class is correct.
+    #[must_use]
+    pub fn accuracy(&self) -> f64 {
+        self.confusion.accuracy()
+    }
+}
+
+/// Returns the class encoded by a vector of values.
+///
+/// A single value is read as the probability of class `1`; otherwise the
+/// index of the largest value is returned, the first one winning ties.
+///
+/// # Panics
+/// Panics if `values` is empty.
+#[must_use]
+pub fn class_of(values: &[f64]) -> usize {
+    assert!(!values.is_empty(), "no value to classify");
+    if let [value] = values {
+        return usize::from(*value >= 0.5);
+    }
+    values
+        .iter()
+        .enumerate()
+        .fold((0, f64::NEG_INFINITY), |best, (i, v)| {
+            if *v > best.1 {
+                (i, *v)
+            } else {
+                best
+            }
+        })
+        .0
+}
+
+/// Fraction of equal entries between `predicted` and `actual` classes, or
+/// `0.0` when both are empty.
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+#[must_use]
+pub fn accuracy(predicted: &[usize], actual: &[usize]) -> f64 {
+    assert_eq!(
+        predicted.len(),
+        actual.len(),
+        "predictions and labels differ in length"
+    );
+    ratio(
+        predicted.iter().zip(actual).filter(|(p, a)| p == a).count(),
+        predicted.len(),
+    )
+}
+
+/// Coefficient of determination `1 - SS_res / SS_tot` of `predicted` against
+/// `actual`.
+///
+/// Returns `1.0` for a perfect fit and `0.0` when `actual` is constant but not
+/// matched exactly, or when the slices are empty.
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+#[must_use]
+pub fn r_squared(predicted: &[f64], actual: &[f64]) -> f64 {
+    assert_eq!(
+        predicted.len(),
+        actual.len(),
+        "predictions and targets differ in length"
+    );
+    if actual.is_empty() {
+        return 0.0;
+    }
+    let mean = actual.iter().sum::<f64>() / actual.len() as f64;
+    let residual: f64 = predicted
+        .iter()
+        .zip(actual)
+        .map(|(p, a)| (a - p) * (a - p))
+        .sum();
+    let total: f64 = actual.iter().map(|a| (a - mean) * (a - mean)).sum();
+    if total == 0.0 {
+        return if residual == 0.0 { 1.0 } else { 0.0 };
+    }
+    1.0 - residual / total
+}
+
+/// Divides two counts, returning `0.0` for an empty denominator.
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+impl Network {
+    /// Scores the network on `samples` with the given `loss`.
+    ///
+    /// Each sample is evaluated with [`Network::propagate`]. Samples are
+    /// classified with [`class_of`]; the number of classes is `2` for a single
+    /// target and the number of targets otherwise, taken from the first
+    /// sample.
+    ///
+    /// # Errors
+    /// Returns the [`PropagationError`] raised by the first failing sample.
+    ///
+    /// # Panics
+    /// Panics if samples do not all have the same number of targets.
+    pub fn evaluate_samples(
+        &mut self,
+        samples: &[TrainingSample],
+        loss: Loss,
+    ) -> Result<Evaluation, PropagationError> {
+        let classes = match samples.first().map_or(0, |s| s.targets.len()) {
+            1 => 2,
+            n => n,
+        };
+        let mut confusion = ConfusionMatrix::new(classes);
+        let mut total_loss = 0.0;
+        let (mut predicted, mut actual) = (Vec::new(), Vec::new());
+        for sample in samples {
+            let outputs: Vec<_> = sample.targets.iter().map(|(id, _)| *id).collect();
+            let targets: Vec<f64> = sample.targets.iter().map(|(_, t)| *t).collect();
+            let values = self.propagate(&sample.inputs, &outputs)?;
+            total_loss += loss.compute(&values, &targets);
+            if classes > 0 {
+                confusion.record(class_of(&targets), class_of(&values));
+            }
+            predicted.extend(values);
+            actual.extend(targets);
+        }
+        Ok(Evaluation {
+            loss: if samples.is_empty() {
+                0.0
+            } else {
+                total_loss / samples.len() as f64
+            },
+            confusion,
+            r_squared: r_squared(&predicted, &actual),
+        })
+    }
+}
@@ -1,22 +1,51 @@
 //! Domain aggregates modelling the state of the network.
 
 mod activation;
+mod crossover;
+mod curiosity;
 mod events;
+mod innovation;
+mod loss;
 mod memory;
+pub mod metrics;
 mod network;
 mod neuron;
+mod optimizer;
+mod population;
+mod propagation;
 mod synapse;
+mod target_selection;
+mod training;
 
-pub use activation::Activation;
+pub use activation::{Activation, ActivationDistribution, ActivationDistributionError};
+pub use crossover::{crossover, CrossoverParent};
+pub use curiosity::{
+    CuriosityIndex, CuriosityStrategy, PredictionError, Rarity, RecencyWeightedRarity,
+    WeightVariance,
+};
 pub use events::{
-    CuriosityScoreUpdated, Event, NeuronActivationMutated, NeuronAdded, NeuronRemoved,
-    RandomNeuronAdded, RandomNeuronRemoved, RandomSynapseAdded, RandomSynapseRemoved,
-    SynapseWeightMutated, SynapseWeightSet,
+    CuriosityScoreUpdated, Event, NeuronActivationMutated, NeuronAdded, NeuronBiasMutated,
+    NeuronBiasSet, NeuronBiasUpdate, NeuronInherited, NeuronRemoved, RandomNeuronAdded,
+    RandomNeuronRemoved, RandomSynapseAdded, RandomSynapseRemoved, RunSeeded, SynapseDisabled,
+    SynapseEnabled, SynapseInherited, SynapseSplit, SynapseWeightMutated, SynapseWeightSet,
+    SynapseWeightUpdate, TrainingStepCompleted,
 };
+pub use innovation::InnovationTracker;
+pub use loss::Loss;
 pub use memory::{
     AdaptiveMemory, MemoryEntry, MemoryEntryAdded, MemoryEntryRemoved, MemoryEvent, MemoryPruned,
     MemoryScoreUpdated,
 };
+pub use metrics::{ConfusionMatrix, Evaluation};
 pub use network::Network;
-pub use neuron::Neuron;
+pub use neuron::{Neuron, NeuronRole};
+pub use optimizer::{Adam, Optimizer, OptimizerConfig, RmsProp, Sgd};
+pub use population::{
+    CompatibilityCoefficients, CompatibilityThresholdAdjusted, FitnessEvaluated,
+    GenerationCompleted, Member, MemberAdded, MemberRetired, Population, PopulationEvent,
+    Selection, SpeciationConfig, Species, SpeciesAssigned, SpeciesCreated, SpeciesExtinct,
+};
+pub use propagation::PropagationError;
 pub use synapse::Synapse;
+pub use target_selection::{TargetSelection, TargetSelectionError};
+pub use training::{Gradients, TrainingSample};
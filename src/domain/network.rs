@@ -3,23 +3,27 @@
 //! The [`Network`] aggregate stores neurons and synapses and evolves solely
 //! through the application of [`Event`]s.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use super::events::{
-    CuriosityScoreUpdated, Event, NeuronActivationMutated, NeuronAdded, NeuronRemoved,
-    RandomNeuronAdded, RandomNeuronRemoved, RandomSynapseAdded, RandomSynapseRemoved,
-    SynapseWeightMutated, SynapseWeightSet,
+    CuriosityScoreUpdated, Event, NeuronActivationMutated, NeuronAdded, NeuronBiasMutated,
+    NeuronBiasSet, NeuronRemoved, RandomNeuronAdded, RandomNeuronRemoved, RandomSynapseAdded,
+    RandomSynapseRemoved, SynapseDisabled, SynapseEnabled, SynapseSplit, SynapseWeightMutated,
+    SynapseWeightSet, TrainingStepCompleted,
 };
-use super::{Neuron, Synapse};
+use super::{Neuron, NeuronRole, Synapse};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Aggregate root containing all neurons and synapses.
-#[derive(Debug, Default, Clone)]
+///
+/// The aggregate can be serialized as a snapshot of the events it reflects.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Network {
-    /// Neurons indexed by their [`Uuid`].
-    pub neurons: HashMap<Uuid, Neuron>,
-    /// Synapses indexed by their [`Uuid`].
-    pub synapses: HashMap<Uuid, Synapse>,
+    /// Neurons indexed and ordered by their [`Uuid`].
+    pub neurons: BTreeMap<Uuid, Neuron>,
+    /// Synapses indexed and ordered by their [`Uuid`].
+    pub synapses: BTreeMap<Uuid, Synapse>,
 }
 
 impl Network {
@@ -53,10 +57,13 @@ impl Network {
                 from,
                 to,
                 weight,
+                innovation,
             } => {
                 if self.neurons.contains_key(from) && self.neurons.contains_key(to) {
-                    self.synapses
-                        .insert(*id, Synapse::with_id(*id, *from, *to, *weight));
+                    self.synapses.insert(
+                        *id,
+                        Synapse::with_id(*id, *from, *to, *weight).with_innovation(*innovation),
+                    );
                 }
             }
             Event::SynapseRemoved { id } => {
@@ -77,9 +84,28 @@ impl Network {
             Event::NeuronActivationMutated(e) => {
                 self.apply_neuron_activation_mutated(e);
             }
+            Event::NeuronBiasSet(e) => {
+                self.apply_neuron_bias_set(e);
+            }
+            Event::NeuronBiasMutated(e) => {
+                self.apply_neuron_bias_mutated(e);
+            }
             Event::CuriosityScoreUpdated(e) => {
                 self.apply_curiosity_score_updated(e);
             }
+            Event::RunSeeded(_) | Event::NeuronInherited(_) | Event::SynapseInherited(_) => {}
+            Event::SynapseSplit(e) => {
+                self.apply_synapse_split(e);
+            }
+            Event::SynapseDisabled(SynapseDisabled { synapse_id }) => {
+                self.set_synapse_enabled(*synapse_id, false);
+            }
+            Event::SynapseEnabled(SynapseEnabled { synapse_id }) => {
+                self.set_synapse_enabled(*synapse_id, true);
+            }
+            Event::TrainingStepCompleted(e) => {
+                self.apply_training_step_completed(e);
+            }
         }
     }
 
@@ -87,7 +113,9 @@ impl Network {
     fn apply_random_neuron_added(&mut self, event: &RandomNeuronAdded) {
         self.neurons.insert(
             event.neuron_id,
-            Neuron::with_id(event.neuron_id, event.activation),
+            Neuron::with_id(event.neuron_id, event.activation)
+                .with_role(event.role)
+                .with_bias(event.bias),
         );
     }
 
@@ -102,7 +130,9 @@ impl Network {
     fn apply_neuron_added(&mut self, event: &NeuronAdded) {
         self.neurons.insert(
             event.neuron_id,
-            Neuron::with_id(event.neuron_id, event.activation),
+            Neuron::with_id(event.neuron_id, event.activation)
+                .with_role(event.role)
+                .with_bias(event.bias),
         );
     }
 
@@ -125,7 +155,8 @@ impl Network {
         {
             self.synapses.insert(
                 event.synapse_id,
-                Synapse::with_id(event.synapse_id, event.from, event.to, event.weight),
+                Synapse::with_id(event.synapse_id, event.from, event.to, event.weight)
+                    .with_innovation(event.innovation),
             );
         }
     }
@@ -135,6 +166,41 @@ impl Network {
         self.synapses.remove(&event.synapse_id);
     }
 
+    /// Applies a [`SynapseSplit`] event to the network state.
+    fn apply_synapse_split(&mut self, event: &SynapseSplit) {
+        match self.synapses.get_mut(&event.synapse_id) {
+            Some(synapse) => synapse.enabled = false,
+            None => return,
+        }
+        self.neurons.insert(
+            event.neuron_id,
+            Neuron::with_id(event.neuron_id, event.activation),
+        );
+        self.synapses.insert(
+            event.incoming_synapse_id,
+            Synapse::with_id(event.incoming_synapse_id, event.from, event.neuron_id, 1.0)
+                .with_innovation(event.incoming_innovation),
+        );
+        self.synapses.insert(
+            event.outgoing_synapse_id,
+            Synapse::with_id(
+                event.outgoing_synapse_id,
+                event.neuron_id,
+                event.to,
+                event.weight,
+            )
+            .with_innovation(event.outgoing_innovation),
+        );
+    }
+
+    /// Applies a [`SynapseDisabled`] or [`SynapseEnabled`] event to the
+    /// network state.
+    fn set_synapse_enabled(&mut self, synapse_id: Uuid, enabled: bool) {
+        if let Some(synapse) = self.synapses.get_mut(&synapse_id) {
+            synapse.enabled = enabled;
+        }
+    }
+
     /// Applies a [`SynapseWeightMutated`] event to the network state.
     fn apply_synapse_weight_mutated(&mut self, event: &SynapseWeightMutated) {
         if let Some(synapse) = self.synapses.get_mut(&event.synapse_id) {
@@ -156,6 +222,20 @@ impl Network {
         }
     }
 
+    /// Applies a [`NeuronBiasSet`] event to the network state.
+    fn apply_neuron_bias_set(&mut self, event: &NeuronBiasSet) {
+        if let Some(neuron) = self.neurons.get_mut(&event.neuron_id) {
+            neuron.bias = event.new_bias;
+        }
+    }
+
+    /// Applies a [`NeuronBiasMutated`] event to the network state.
+    fn apply_neuron_bias_mutated(&mut self, event: &NeuronBiasMutated) {
+        if let Some(neuron) = self.neurons.get_mut(&event.neuron_id) {
+            neuron.bias = event.new_bias;
+        }
+    }
+
     /// Applies a [`CuriosityScoreUpdated`] event to the network state.
     fn apply_curiosity_score_updated(&mut self, event: &CuriosityScoreUpdated) {
         if let Some(neuron) = self.neurons.get_mut(&event.target_id) {
@@ -165,15 +245,40 @@ impl Network {
         }
     }
 
+    /// Applies a [`TrainingStepCompleted`] event to the network state.
+    fn apply_training_step_completed(&mut self, event: &TrainingStepCompleted) {
+        for update in &event.updates {
+            if let Some(synapse) = self.synapses.get_mut(&update.synapse_id) {
+                synapse.weight = update.new_weight;
+            }
+        }
+        for update in &event.bias_updates {
+            if let Some(neuron) = self.neurons.get_mut(&update.neuron_id) {
+                neuron.bias = update.new_bias;
+            }
+        }
+    }
+
     /// Convenience method to list all neurons.
     #[must_use]
     pub fn neurons(&self) -> Vec<&Neuron> {
         self.neurons.values().collect()
     }
 
+    /// Lists all neurons with the given role.
+    #[must_use]
+    pub fn neurons_by_role(&self, role: NeuronRole) -> Vec<&Neuron> {
+        self.neurons.values().filter(|n| n.role == role).collect()
+    }
+
     /// Convenience method to list all synapses.
     #[must_use]
     pub fn synapses(&self) -> Vec<&Synapse> {
         self.synapses.values().collect()
     }
+
+    /// Iterates over the enabled synapses by ascending identifier.
+    pub fn enabled_synapses(&self) -> impl Iterator<Item = &Synapse> {
+        self.synapses.values().filter(|s| s.enabled)
+    }
 }
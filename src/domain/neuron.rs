@@ -4,10 +4,36 @@ use super::Activation;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Structural role of a neuron within the network.
+///
+/// Roles let handlers tell interface neurons apart from internal ones
+/// without inspecting the topology.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum NeuronRole {
+    /// Receives external input values during propagation.
+    Input,
+    /// Internal neuron free to be mutated or removed.
+    #[default]
+    Hidden,
+    /// Exposes a value read by callers after propagation.
+    Output,
+    /// Emits a constant value of `1.0` during propagation.
+    Bias,
+}
+
+impl NeuronRole {
+    /// Returns `true` for roles that random structural mutations must leave
+    /// alone, i.e. every role except [`NeuronRole::Hidden`].
+    #[must_use]
+    pub fn is_protected(self) -> bool {
+        !matches!(self, NeuronRole::Hidden)
+    }
+}
+
 /// Represents a neuron within the network.
 ///
-/// Each neuron has a unique identifier, an activation function and a
-/// floating-point value representing its current state.
+/// Each neuron has a unique identifier, an activation function, a role, a bias
+/// and a floating-point value representing its current state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Neuron {
     /// Globally unique identifier of the neuron.
@@ -16,32 +42,49 @@ pub struct Neuron {
     pub value: f64,
     /// Activation function used by this neuron.
     pub activation: Activation,
+    /// Structural role of the neuron.
+    #[serde(default)]
+    pub role: NeuronRole,
+    /// Bias added to the weighted input sum before activation.
+    #[serde(default)]
+    pub bias: f64,
     /// Current curiosity score guiding exploration.
     pub curiosity_score: f64,
 }
 
 impl Neuron {
-    /// Creates a new neuron with the provided activation function and a fresh
-    /// random [`Uuid`].
+    /// Creates a new hidden neuron with the provided activation function and a
+    /// fresh random [`Uuid`].
     pub fn new(activation: Activation) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            value: 0.0,
-            activation,
-            curiosity_score: 0.0,
-        }
+        Self::with_id(Uuid::new_v4(), activation)
     }
 
-    /// Creates a neuron using the supplied [`Uuid`].
+    /// Creates a hidden neuron without bias using the supplied [`Uuid`].
     pub fn with_id(id: Uuid, activation: Activation) -> Self {
         Self {
             id,
             value: 0.0,
             activation,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
             curiosity_score: 0.0,
         }
     }
 
+    /// Returns the neuron with its role replaced by `role`.
+    #[must_use]
+    pub fn with_role(mut self, role: NeuronRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Returns the neuron with its bias replaced by `bias`.
+    #[must_use]
+    pub fn with_bias(mut self, bias: f64) -> Self {
+        self.bias = bias;
+        self
+    }
+
     /// Updates the curiosity score of the neuron.
     pub fn update_curiosity_score(&mut self, score: f64) {
         self.curiosity_score = score;
@@ -0,0 +1,243 @@
+//! Optimizers turning weight gradients into weight updates.
+//!
+//! An [`Optimizer`] may keep per-synapse state such as moment estimates. The
+//! state only depends on the sequence of gradients it received, so it can be
+//! rebuilt deterministically by feeding the gradients recorded in
+//! [`TrainingStepCompleted`](super::TrainingStepCompleted) events again.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Computes weight updates from gradients.
+pub trait Optimizer {
+    /// Consumes the gradients of one training step and returns, for each
+    /// synapse, the value to add to its weight.
+    fn step(&mut self, gradients: &BTreeMap<Uuid, f64>) -> BTreeMap<Uuid, f64>;
+}
+
+/// Serializable description of an optimizer and its hyperparameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OptimizerConfig {
+    /// Stochastic gradient descent with optional momentum.
+    Sgd {
+        /// Step size applied to the gradients.
+        learning_rate: f64,
+        /// Fraction of the previous update carried over, in `[0, 1)`.
+        momentum: f64,
+    },
+    /// RMSProp, scaling gradients by a running average of their squares.
+    RmsProp {
+        /// Step size applied to the scaled gradients.
+        learning_rate: f64,
+        /// Decay rate of the squared gradient average, in `[0, 1)`.
+        decay: f64,
+        /// Small positive constant avoiding divisions by zero.
+        epsilon: f64,
+    },
+    /// Adam, using bias-corrected first and second moment estimates.
+    Adam {
+        /// Step size applied to the scaled moments.
+        learning_rate: f64,
+        /// Decay rate of the first moment estimate, in `[0, 1)`.
+        beta1: f64,
+        /// Decay rate of the second moment estimate, in `[0, 1)`.
+        beta2: f64,
+        /// Small positive constant avoiding divisions by zero.
+        epsilon: f64,
+    },
+}
+
+impl OptimizerConfig {
+    /// Plain gradient descent without momentum.
+    #[must_use]
+    pub fn sgd(learning_rate: f64) -> Self {
+        OptimizerConfig::Sgd {
+            learning_rate,
+            momentum: 0.0,
+        }
+    }
+
+    /// RMSProp with the customary decay of `0.9` and epsilon of `1e-8`.
+    #[must_use]
+    pub fn rms_prop(learning_rate: f64) -> Self {
+        OptimizerConfig::RmsProp {
+            learning_rate,
+            decay: 0.9,
+            epsilon: 1e-8,
+        }
+    }
+
+    /// Adam with the customary betas of `0.9`/`0.999` and epsilon of `1e-8`.
+    #[must_use]
+    pub fn adam(learning_rate: f64) -> Self {
+        OptimizerConfig::Adam {
+            learning_rate,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+        }
+    }
+
+    /// Returns `true` if every hyperparameter lies in its valid range.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        let rate = |x: f64| (0.0..1.0).contains(&x);
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+        match *self {
+            OptimizerConfig::Sgd {
+                learning_rate,
+                momentum,
+            } => positive(learning_rate) && rate(momentum),
+            OptimizerConfig::RmsProp {
+                learning_rate,
+                decay,
+                epsilon,
+            } => positive(learning_rate) && rate(decay) && positive(epsilon),
+            OptimizerConfig::Adam {
+                learning_rate,
+                beta1,
+                beta2,
+                epsilon,
+            } => positive(learning_rate) && rate(beta1) && rate(beta2) && positive(epsilon),
+        }
+    }
+
+    /// Creates a fresh optimizer without any accumulated state.
+    #[must_use]
+    pub fn build(&self) -> Box<dyn Optimizer> {
+        match *self {
+            OptimizerConfig::Sgd {
+                learning_rate,
+                momentum,
+            } => Box::new(Sgd::new(learning_rate, momentum)),
+            OptimizerConfig::RmsProp {
+                learning_rate,
+                decay,
+                epsilon,
+            } => Box::new(RmsProp::new(learning_rate, decay, epsilon)),
+            OptimizerConfig::Adam {
+                learning_rate,
+                beta1,
+                beta2,
+                epsilon,
+            } => Box::new(Adam::new(learning_rate, beta1, beta2, epsilon)),
+        }
+    }
+}
+
+/// Stochastic gradient descent with momentum.
+#[derive(Debug, Clone, Default)]
+pub struct Sgd {
+    learning_rate: f64,
+    momentum: f64,
+    velocity: HashMap<Uuid, f64>,
+}
+
+impl Sgd {
+    /// Creates the optimizer; a `momentum` of `0.0` yields plain gradient descent.
+    #[must_use]
+    pub fn new(learning_rate: f64, momentum: f64) -> Self {
+        Self {
+            learning_rate,
+            momentum,
+            velocity: HashMap::new(),
+        }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn step(&mut self, gradients: &BTreeMap<Uuid, f64>) -> BTreeMap<Uuid, f64> {
+        gradients
+            .iter()
+            .map(|(id, gradient)| {
+                let velocity = self.velocity.entry(*id).or_default();
+                *velocity = self.momentum * *velocity - self.learning_rate * gradient;
+                (*id, *velocity)
+            })
+            .collect()
+    }
+}
+
+/// RMSProp optimizer.
+#[derive(Debug, Clone, Default)]
+pub struct RmsProp {
+    learning_rate: f64,
+    decay: f64,
+    epsilon: f64,
+    mean_square: HashMap<Uuid, f64>,
+}
+
+impl RmsProp {
+    /// Creates the optimizer with the given hyperparameters.
+    #[must_use]
+    pub fn new(learning_rate: f64, decay: f64, epsilon: f64) -> Self {
+        Self {
+            learning_rate,
+            decay,
+            epsilon,
+            mean_square: HashMap::new(),
+        }
+    }
+}
+
+impl Optimizer for RmsProp {
+    fn step(&mut self, gradients: &BTreeMap<Uuid, f64>) -> BTreeMap<Uuid, f64> {
+        gradients
+            .iter()
+            .map(|(id, gradient)| {
+                let mean_square = self.mean_square.entry(*id).or_default();
+                *mean_square = self.decay * *mean_square + (1.0 - self.decay) * gradient * gradient;
+                let delta = -self.learning_rate * gradient / (mean_square.sqrt() + self.epsilon);
+                (*id, delta)
+            })
+            .collect()
+    }
+}
+
+/// Adam optimizer.
+#[derive(Debug, Clone, Default)]
+pub struct Adam {
+    learning_rate: f64,
+    beta1: f64,
+    beta2: f64,
+    epsilon: f64,
+    steps: i32,
+    moments: HashMap<Uuid, (f64, f64)>,
+}
+
+impl Adam {
+    /// Creates the optimizer with the given hyperparameters.
+    #[must_use]
+    pub fn new(learning_rate: f64, beta1: f64, beta2: f64, epsilon: f64) -> Self {
+        Self {
+            learning_rate,
+            beta1,
+            beta2,
+            epsilon,
+            steps: 0,
+            moments: HashMap::new(),
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, gradients: &BTreeMap<Uuid, f64>) -> BTreeMap<Uuid, f64> {
+        self.steps += 1;
+        let first_correction = 1.0 - self.beta1.powi(self.steps);
+        let second_correction = 1.0 - self.beta2.powi(self.steps);
+        gradients
+            .iter()
+            .map(|(id, gradient)| {
+                let (first, second) = self.moments.entry(*id).or_default();
+                *first = self.beta1 * *first + (1.0 - self.beta1) * gradient;
+                *second = self.beta2 * *second + (1.0 - self.beta2) * gradient * gradient;
+                let first_hat = *first / first_correction;
+                let second_hat = *second / second_correction;
+                let delta = -self.learning_rate * first_hat / (second_hat.sqrt() + self.epsilon);
+                (*id, delta)
+            })
+            .collect()
+    }
+}
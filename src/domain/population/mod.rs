@@ -0,0 +1,231 @@
+//! Population aggregate tracking the networks of an evolutionary run.
+//!
+//! Each member of a [`Population`] is a [`Network`](super::Network) with its
+//! own event stream, identified by the member's [`Uuid`]. The population log
+//! only records membership, fitness, species and generation boundaries; the
+//! genomes themselves live in the member streams.
+
+mod selection;
+mod speciation;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use selection::Selection;
+pub use speciation::{CompatibilityCoefficients, SpeciationConfig, Species};
+
+/// A network taking part in the population.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    /// Identifier of the member and of its event stream.
+    pub id: Uuid,
+    /// Members the genome was derived from; empty for founders.
+    pub parents: Vec<Uuid>,
+    /// Generation in which the member was added.
+    pub born: u64,
+    /// Fitness measured during the latest evaluation, if any.
+    pub fitness: Option<f64>,
+    /// Species the member was assigned to by the latest speciation, if any.
+    pub species: Option<u64>,
+}
+
+/// Event emitted when a network joins the population.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberAdded {
+    /// Identifier of the new member and of its event stream.
+    pub member_id: Uuid,
+    /// Members the genome was derived from; empty for founders.
+    pub parents: Vec<Uuid>,
+    /// Generation the member belongs to.
+    pub generation: u64,
+}
+
+/// Event emitted when the fitness of a member is measured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FitnessEvaluated {
+    /// Identifier of the evaluated member.
+    pub member_id: Uuid,
+    /// Generation during which the member was evaluated.
+    pub generation: u64,
+    /// Measured fitness; higher is better.
+    pub fitness: f64,
+}
+
+/// Event emitted when a member leaves the population.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberRetired {
+    /// Identifier of the retired member.
+    pub member_id: Uuid,
+    /// Generation at the end of which the member was retired.
+    pub generation: u64,
+}
+
+/// Event marking the end of a generation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationCompleted {
+    /// Index of the completed generation, starting at zero.
+    pub generation: u64,
+    /// Fittest member of the generation.
+    pub best_member_id: Uuid,
+    /// Fitness of the fittest member.
+    pub best_fitness: f64,
+    /// Mean fitness over all evaluated members.
+    pub mean_fitness: f64,
+}
+
+/// Event emitted when a member founds a new species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesCreated {
+    /// Identifier of the new species.
+    pub species_id: u64,
+    /// Founding member, representing the species from now on.
+    pub representative_id: Uuid,
+    /// Generation in which the species appeared.
+    pub generation: u64,
+}
+
+/// Event emitted when speciation places a member in a species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesAssigned {
+    /// Identifier of the assigned member.
+    pub member_id: Uuid,
+    /// Species the member belongs to.
+    pub species_id: u64,
+    /// Generation during which the member was assigned.
+    pub generation: u64,
+    /// Compatibility distance to the representative of the species.
+    pub distance: f64,
+}
+
+/// Event emitted when speciation leaves a species without members.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeciesExtinct {
+    /// Identifier of the extinct species.
+    pub species_id: u64,
+    /// Generation during which the species died out.
+    pub generation: u64,
+}
+
+/// Event emitted when the compatibility threshold moves towards the target
+/// number of species.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatibilityThresholdAdjusted {
+    /// Generation during which the threshold was adjusted.
+    pub generation: u64,
+    /// Threshold used by the speciation that triggered the adjustment.
+    pub old_threshold: f64,
+    /// Threshold used by the next speciation.
+    pub new_threshold: f64,
+}
+
+/// Domain events for the [`Population`] aggregate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PopulationEvent {
+    /// A network joined the population.
+    MemberAdded(MemberAdded),
+    /// The fitness of a member was measured.
+    FitnessEvaluated(FitnessEvaluated),
+    /// A member left the population.
+    MemberRetired(MemberRetired),
+    /// A generation ended and the next one started.
+    GenerationCompleted(GenerationCompleted),
+    /// A member founded a new species.
+    SpeciesCreated(SpeciesCreated),
+    /// A member was placed in a species.
+    SpeciesAssigned(SpeciesAssigned),
+    /// A species lost all its members.
+    SpeciesExtinct(SpeciesExtinct),
+    /// The compatibility threshold was adjusted.
+    CompatibilityThresholdAdjusted(CompatibilityThresholdAdjusted),
+}
+
+/// Aggregate holding the current members of an evolutionary run.
+#[derive(Debug, Clone, Default)]
+pub struct Population {
+    /// Index of the generation in progress.
+    pub generation: u64,
+    /// Current members indexed and ordered by their [`Uuid`].
+    pub members: BTreeMap<Uuid, Member>,
+    /// Living species indexed and ordered by identifier.
+    pub species: BTreeMap<u64, Species>,
+    /// Compatibility threshold recorded by the latest adjustment, if any.
+    pub compatibility_threshold: Option<f64>,
+    /// Identifier the next new species will receive.
+    pub next_species_id: u64,
+}
+
+impl Population {
+    /// Rebuilds a population by replaying past events.
+    #[must_use]
+    pub fn hydrate(events: &[PopulationEvent]) -> Self {
+        let mut population = Self::default();
+        for event in events {
+            population.apply(event);
+        }
+        population
+    }
+
+    /// Applies a domain event to mutate the internal state.
+    pub fn apply(&mut self, event: &PopulationEvent) {
+        match event {
+            PopulationEvent::MemberAdded(e) => self.apply_member_added(e),
+            PopulationEvent::FitnessEvaluated(e) => self.apply_fitness_evaluated(e),
+            PopulationEvent::MemberRetired(e) => {
+                self.members.remove(&e.member_id);
+            }
+            PopulationEvent::GenerationCompleted(e) => self.generation = e.generation + 1,
+            PopulationEvent::SpeciesCreated(e) => self.apply_species_created(e),
+            PopulationEvent::SpeciesAssigned(e) => {
+                if let Some(member) = self.members.get_mut(&e.member_id) {
+                    member.species = Some(e.species_id);
+                }
+            }
+            PopulationEvent::SpeciesExtinct(e) => {
+                self.species.remove(&e.species_id);
+            }
+            PopulationEvent::CompatibilityThresholdAdjusted(e) => {
+                self.compatibility_threshold = Some(e.new_threshold);
+            }
+        }
+    }
+
+    /// Returns the current members of a species.
+    pub fn species_members(&self, species_id: u64) -> impl Iterator<Item = &Member> {
+        self.members
+            .values()
+            .filter(move |m| m.species == Some(species_id))
+    }
+
+    fn apply_member_added(&mut self, event: &MemberAdded) {
+        self.members.insert(
+            event.member_id,
+            Member {
+                id: event.member_id,
+                parents: event.parents.clone(),
+                born: event.generation,
+                fitness: None,
+                species: None,
+            },
+        );
+    }
+
+    fn apply_species_created(&mut self, event: &SpeciesCreated) {
+        self.species.insert(
+            event.species_id,
+            Species {
+                id: event.species_id,
+                representative_id: event.representative_id,
+                created: event.generation,
+            },
+        );
+        self.next_species_id = self.next_species_id.max(event.species_id + 1);
+    }
+
+    fn apply_fitness_evaluated(&mut self, event: &FitnessEvaluated) {
+        if let Some(member) = self.members.get_mut(&event.member_id) {
+            member.fitness = Some(event.fitness);
+        }
+    }
+}
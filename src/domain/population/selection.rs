@@ -0,0 +1,106 @@
+//! Parent selection schemes.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Strategy choosing the parents of the next generation.
+///
+/// Every scheme favours fitter members; higher fitness is better.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Selection {
+    /// Draws `size` members at random, with replacement, and keeps the
+    /// fittest of them.
+    Tournament {
+        /// Number of members competing in each tournament.
+        size: usize,
+    },
+    /// Picks members with a probability proportional to their fitness,
+    /// shifted so that the least fit member has weight zero. A uniform
+    /// choice is made when every member has the same fitness.
+    Roulette,
+    /// Picks uniformly among the fittest `fraction` of the members, always
+    /// keeping at least one.
+    Truncation {
+        /// Share of the members eligible as parents, in `(0, 1]`.
+        fraction: f64,
+    },
+}
+
+impl Default for Selection {
+    /// Binary tournament.
+    fn default() -> Self {
+        Selection::Tournament { size: 2 }
+    }
+}
+
+impl Selection {
+    /// Returns `true` if the parameters of the scheme are in range.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Selection::Tournament { size } => size > 0,
+            Selection::Roulette => true,
+            Selection::Truncation { fraction } => fraction > 0.0 && fraction <= 1.0,
+        }
+    }
+
+    /// Selects a parent among `ranked`, which lists members with their
+    /// fitness from the fittest to the least fit.
+    ///
+    /// Returns `None` if `ranked` is empty. Fitness values are expected to be
+    /// finite.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::Selection;
+    /// use rand::thread_rng;
+    /// use uuid::Uuid;
+    ///
+    /// let (best, worst) = (Uuid::new_v4(), Uuid::new_v4());
+    /// let ranked = [(best, 2.0), (worst, 1.0)];
+    /// let selection = Selection::Truncation { fraction: 0.5 };
+    /// assert_eq!(selection.select(&ranked, &mut thread_rng()), Some(best));
+    /// ```
+    pub fn select<R: Rng + ?Sized>(&self, ranked: &[(Uuid, f64)], rng: &mut R) -> Option<Uuid> {
+        if ranked.is_empty() {
+            return None;
+        }
+        let index = match *self {
+            Selection::Tournament { size } => (0..size.max(1))
+                .map(|_| rng.gen_range(0..ranked.len()))
+                .min()
+                .expect("tournament has at least one entrant"),
+            Selection::Roulette => {
+                let worst = ranked[ranked.len() - 1].1;
+                let total: f64 = ranked.iter().map(|(_, fitness)| fitness - worst).sum();
+                if total > 0.0 {
+                    let mut threshold = rng.gen_range(0.0..total);
+                    ranked
+                        .iter()
+                        .position(|(_, fitness)| {
+                            threshold -= fitness - worst;
+                            threshold < 0.0
+                        })
+                        // Rounding may leave the threshold just above the
+                        // sum; fall back to the last slot with a non-zero
+                        // width, as the least fit member has none.
+                        .unwrap_or_else(|| {
+                            ranked
+                                .iter()
+                                .rposition(|(_, fitness)| *fitness > worst)
+                                .expect("a positive total has a positive weight")
+                        })
+                } else {
+                    rng.gen_range(0..ranked.len())
+                }
+            }
+            Selection::Truncation { fraction } => {
+                let eligible =
+                    ((ranked.len() as f64 * fraction).ceil() as usize).clamp(1, ranked.len());
+                rng.gen_range(0..eligible)
+            }
+        };
+        Some(ranked[index].0)
+    }
+}
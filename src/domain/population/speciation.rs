@@ -0,0 +1,169 @@
+//! Compatibility distance and speciation parameters.
+//!
+//! Genomes are compared gene by gene, synapses being matched by innovation
+//! number as in [`crossover`](crate::domain::crossover). The distance between
+//! two networks is
+//!
+//! ```text
+//! δ = excess · E / N + disjoint · D / N + weight · W
+//! ```
+//!
+//! where `E` counts the excess genes, numbered beyond the highest innovation
+//! number of the other genome, `D` the other unmatched genes, `W` the mean
+//! absolute weight difference of the matching genes and `N` the number of
+//! genes of the larger genome.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::innovation::GeneKey;
+use crate::domain::Network;
+
+/// Weights of the terms of the compatibility distance.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityCoefficients {
+    /// Weight of the excess genes.
+    pub excess: f64,
+    /// Weight of the disjoint genes.
+    pub disjoint: f64,
+    /// Weight of the mean weight difference of matching genes.
+    pub weight: f64,
+}
+
+impl Default for CompatibilityCoefficients {
+    /// The coefficients of the original NEAT experiments: `1.0`, `1.0` and
+    /// `0.4`.
+    fn default() -> Self {
+        Self {
+            excess: 1.0,
+            disjoint: 1.0,
+            weight: 0.4,
+        }
+    }
+}
+
+impl CompatibilityCoefficients {
+    /// Returns `true` if every coefficient is finite and non-negative.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        [self.excess, self.disjoint, self.weight]
+            .iter()
+            .all(|c| c.is_finite() && *c >= 0.0)
+    }
+
+    /// Returns the compatibility distance between two genomes.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{CompatibilityCoefficients, DomainNetwork};
+    ///
+    /// let empty = DomainNetwork::default();
+    /// let distance = CompatibilityCoefficients::default().distance(&empty, &empty);
+    /// assert_eq!(distance, 0.0);
+    /// ```
+    #[must_use]
+    pub fn distance(&self, a: &Network, b: &Network) -> f64 {
+        let genes = |net: &Network| -> HashMap<GeneKey, f64> {
+            net.synapses
+                .values()
+                .map(|s| (GeneKey::of(s), s.weight))
+                .collect()
+        };
+        let highest = |net: &Network| net.synapses.values().filter_map(|s| s.innovation).max();
+        let (genes_a, genes_b) = (genes(a), genes(b));
+        let (highest_a, highest_b) = (highest(a), highest(b));
+
+        let (mut excess, mut disjoint) = (0usize, 0usize);
+        let mut unmatched = |key: &GeneKey, other_highest: Option<u64>| match key {
+            GeneKey::Innovation(n) if other_highest.is_none_or(|h| *n > h) => excess += 1,
+            _ => disjoint += 1,
+        };
+        let mut differences = Vec::new();
+        for (key, weight) in &genes_a {
+            match genes_b.get(key) {
+                Some(other) => differences.push((weight - other).abs()),
+                None => unmatched(key, highest_b),
+            }
+        }
+        for key in genes_b.keys().filter(|key| !genes_a.contains_key(key)) {
+            unmatched(key, highest_a);
+        }
+
+        let size = genes_a.len().max(genes_b.len()).max(1) as f64;
+        let mean_difference = if differences.is_empty() {
+            0.0
+        } else {
+            differences.iter().sum::<f64>() / differences.len() as f64
+        };
+        self.excess * excess as f64 / size
+            + self.disjoint * disjoint as f64 / size
+            + self.weight * mean_difference
+    }
+}
+
+/// Parameters of the speciation step.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpeciationConfig {
+    /// Coefficients of the compatibility distance.
+    pub coefficients: CompatibilityCoefficients,
+    /// Distance below which a genome joins a species, used until the
+    /// population records an adjusted threshold.
+    pub threshold: f64,
+    /// Number of species the threshold is adjusted towards after each
+    /// speciation; `None` keeps the threshold fixed.
+    pub target_species: Option<usize>,
+    /// Amount by which the threshold moves at each adjustment. The threshold
+    /// never drops below this value.
+    pub threshold_step: f64,
+}
+
+impl Default for SpeciationConfig {
+    /// Default coefficients, a threshold of `3.0` and no adjustment.
+    fn default() -> Self {
+        Self {
+            coefficients: CompatibilityCoefficients::default(),
+            threshold: 3.0,
+            target_species: None,
+            threshold_step: 0.3,
+        }
+    }
+}
+
+impl SpeciationConfig {
+    /// Returns `true` if every parameter lies in its valid range.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.coefficients.is_valid()
+            && self.threshold.is_finite()
+            && self.threshold > 0.0
+            && self.threshold_step.is_finite()
+            && self.threshold_step > 0.0
+            && self.target_species != Some(0)
+    }
+
+    /// Returns the threshold following `threshold` once `species` species
+    /// were formed.
+    #[must_use]
+    pub fn adjust(&self, threshold: f64, species: usize) -> f64 {
+        match self.target_species {
+            Some(target) if species < target => {
+                (threshold - self.threshold_step).max(self.threshold_step)
+            }
+            Some(target) if species > target => threshold + self.threshold_step,
+            _ => threshold,
+        }
+    }
+}
+
+/// Group of compatible members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    /// Identifier of the species, assigned in creation order.
+    pub id: u64,
+    /// Member whose genome newcomers are compared with.
+    pub representative_id: Uuid,
+    /// Generation in which the species appeared.
+    pub created: u64,
+}
@@ -0,0 +1,332 @@
+//! Forward and recurrent propagation through a [`Network`].
+//!
+//! Values flow from input neurons along weighted synapses. Neurons are
+//! evaluated in topological order so that each one sees the final values of
+//! all its predecessors before applying its activation function.
+//!
+//! [`Network::propagate`] requires an acyclic network. [`Network::step`]
+//! accepts any topology: synapses closing a cycle are treated as recurrent
+//! and carry the value their source neuron held at the previous tick.
+//!
+//! Disabled synapses are ignored entirely, including when looking for cycles.
+
+use std::collections::{BTreeSet, HashMap};
+
+use uuid::Uuid;
+
+use super::{Network, NeuronRole};
+
+/// Errors that can occur while propagating values through a [`Network`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropagationError {
+    /// The identifier does not refer to a neuron of the network.
+    UnknownNeuron(Uuid),
+    /// The synapses form a cycle, so no topological order exists.
+    CycleDetected,
+}
+
+/// Visit state of a neuron during the depth-first search for recurrent synapses.
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+impl Network {
+    /// Returns all neuron identifiers in topological order.
+    ///
+    /// Neurons without pending predecessors are visited by ascending
+    /// identifier, which makes the order deterministic.
+    ///
+    /// # Errors
+    /// Returns [`PropagationError::CycleDetected`] if the synapses contain a
+    /// cycle.
+    pub fn topological_order(&self) -> Result<Vec<Uuid>, PropagationError> {
+        self.order_without(&BTreeSet::new())
+    }
+
+    /// Returns the identifiers of the synapses that close a cycle.
+    ///
+    /// The synapses are found by a depth-first search that starts from input
+    /// and bias neurons, then from the remaining neurons by ascending
+    /// identifier, following outgoing synapses by ascending identifier. Every
+    /// synapse reaching a neuron that is still on the search path, including
+    /// self-loops, is recurrent. Removing these synapses leaves an acyclic
+    /// network, and the result is empty when the network is already acyclic.
+    #[must_use]
+    pub fn recurrent_synapses(&self) -> BTreeSet<Uuid> {
+        let mut successors: HashMap<Uuid, Vec<(Uuid, Uuid)>> = HashMap::new();
+        for synapse in self.enabled_synapses() {
+            successors
+                .entry(synapse.from)
+                .or_default()
+                .push((synapse.id, synapse.to));
+        }
+
+        let mut roots: Vec<_> = self.neurons.values().collect();
+        roots.sort_by_key(|n| {
+            (
+                !matches!(n.role, NeuronRole::Input | NeuronRole::Bias),
+                n.id,
+            )
+        });
+
+        let mut visits: HashMap<Uuid, Visit> = HashMap::new();
+        let mut recurrent = BTreeSet::new();
+        for root in roots {
+            if visits.contains_key(&root.id) {
+                continue;
+            }
+            visits.insert(root.id, Visit::InProgress);
+            let mut path = vec![(root.id, 0)];
+            while let Some((node, next)) = path.last_mut() {
+                let node = *node;
+                match successors.get(&node).and_then(|edges| edges.get(*next)) {
+                    Some(&(synapse_id, to)) => {
+                        *next += 1;
+                        match visits.get(&to) {
+                            Some(Visit::InProgress) => {
+                                recurrent.insert(synapse_id);
+                            }
+                            Some(Visit::Done) => {}
+                            None => {
+                                visits.insert(to, Visit::InProgress);
+                                path.push((to, 0));
+                            }
+                        }
+                    }
+                    None => {
+                        visits.insert(node, Visit::Done);
+                        path.pop();
+                    }
+                }
+            }
+        }
+        recurrent
+    }
+
+    /// Runs a forward pass and returns the values of the requested outputs.
+    ///
+    /// All neuron values are reset before the pass. Input neurons take the
+    /// supplied values as-is and [`NeuronRole::Bias`] neurons emit `1.0`
+    /// unless an input value is supplied for them; every other neuron stores
+    /// `activation(Σ weight · value + bias)` over its enabled incoming
+    /// synapses. The resulting values remain available on each
+    /// [`Neuron`](super::Neuron).
+    ///
+    /// # Arguments
+    /// * `inputs` - Pairs of input neuron identifier and value.
+    /// * `outputs` - Identifiers of the neurons whose values are returned, in
+    ///   the same order.
+    ///
+    /// # Errors
+    /// Returns [`PropagationError::UnknownNeuron`] if an input or output
+    /// identifier is not part of the network and
+    /// [`PropagationError::CycleDetected`] if the network is not acyclic.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{Activation, DomainNetwork, Event, NeuronAdded, NeuronRole};
+    /// use uuid::Uuid;
+    ///
+    /// let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    /// let mut net = DomainNetwork::hydrate(&[
+    ///     Event::NeuronAdded(NeuronAdded {
+    ///         neuron_id: input,
+    ///         activation: Activation::Identity,
+    ///         role: NeuronRole::Input,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::NeuronAdded(NeuronAdded {
+    ///         neuron_id: output,
+    ///         activation: Activation::ReLU,
+    ///         role: NeuronRole::Output,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::SynapseCreated { id: Uuid::new_v4(), from: input, to: output, weight: 2.0, innovation: None },
+    /// ]);
+    /// let values = net.propagate(&[(input, 1.5)], &[output]).unwrap();
+    /// assert_eq!(values, vec![3.0]);
+    /// ```
+    pub fn propagate(
+        &mut self,
+        inputs: &[(Uuid, f64)],
+        outputs: &[Uuid],
+    ) -> Result<Vec<f64>, PropagationError> {
+        self.check_known(inputs, outputs)?;
+        let order = self.topological_order()?;
+
+        for neuron in self.neurons.values_mut() {
+            neuron.value = 0.0;
+        }
+        self.evaluate(&order, inputs, &BTreeSet::new());
+        Ok(outputs.iter().map(|id| self.neurons[id].value).collect())
+    }
+
+    /// Advances the network by one time step and returns the requested outputs.
+    ///
+    /// Unlike [`Network::propagate`], cycles are allowed. Synapses reported
+    /// by [`Network::recurrent_synapses`] read the value their source neuron
+    /// held at the end of the previous step, while all other synapses read
+    /// values computed during the current step. Input and bias neurons behave
+    /// as in [`Network::propagate`].
+    ///
+    /// Neuron values are kept between calls and form the recurrent state; it
+    /// is runtime state only and is not recorded in the event log. Call
+    /// [`Network::reset_state`] to start a new sequence.
+    ///
+    /// # Errors
+    /// Returns [`PropagationError::UnknownNeuron`] if an input or output
+    /// identifier is not part of the network.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{Activation, DomainNetwork, Event, NeuronAdded, NeuronRole};
+    /// use uuid::Uuid;
+    ///
+    /// let (input, sum) = (Uuid::new_v4(), Uuid::new_v4());
+    /// let mut net = DomainNetwork::hydrate(&[
+    ///     Event::NeuronAdded(NeuronAdded {
+    ///         neuron_id: input,
+    ///         activation: Activation::Identity,
+    ///         role: NeuronRole::Input,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::NeuronAdded(NeuronAdded {
+    ///         neuron_id: sum,
+    ///         activation: Activation::Identity,
+    ///         role: NeuronRole::Output,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::SynapseCreated { id: Uuid::new_v4(), from: input, to: sum, weight: 1.0, innovation: None },
+    ///     Event::SynapseCreated { id: Uuid::new_v4(), from: sum, to: sum, weight: 1.0, innovation: None },
+    /// ]);
+    /// assert_eq!(net.step(&[(input, 1.0)], &[sum]).unwrap(), vec![1.0]);
+    /// assert_eq!(net.step(&[(input, 1.0)], &[sum]).unwrap(), vec![2.0]);
+    /// net.reset_state();
+    /// assert_eq!(net.step(&[(input, 1.0)], &[sum]).unwrap(), vec![1.0]);
+    /// ```
+    pub fn step(
+        &mut self,
+        inputs: &[(Uuid, f64)],
+        outputs: &[Uuid],
+    ) -> Result<Vec<f64>, PropagationError> {
+        self.check_known(inputs, outputs)?;
+        let recurrent = self.recurrent_synapses();
+        let order = self
+            .order_without(&recurrent)
+            .expect("network is acyclic without recurrent synapses");
+
+        self.evaluate(&order, inputs, &recurrent);
+        Ok(outputs.iter().map(|id| self.neurons[id].value).collect())
+    }
+
+    /// Clears the recurrent state by setting every neuron value to `0.0`.
+    pub fn reset_state(&mut self) {
+        for neuron in self.neurons.values_mut() {
+            neuron.value = 0.0;
+        }
+    }
+
+    /// Ensures every input and output identifier refers to a neuron.
+    fn check_known(
+        &self,
+        inputs: &[(Uuid, f64)],
+        outputs: &[Uuid],
+    ) -> Result<(), PropagationError> {
+        match inputs
+            .iter()
+            .map(|(id, _)| id)
+            .chain(outputs)
+            .find(|id| !self.neurons.contains_key(id))
+        {
+            Some(id) => Err(PropagationError::UnknownNeuron(*id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Topological order of the network once the `skipped` synapses are ignored.
+    fn order_without(&self, skipped: &BTreeSet<Uuid>) -> Result<Vec<Uuid>, PropagationError> {
+        let mut in_degree: HashMap<Uuid, usize> = self.neurons.keys().map(|id| (*id, 0)).collect();
+        let mut successors: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for synapse in self.enabled_synapses() {
+            if skipped.contains(&synapse.id) {
+                continue;
+            }
+            *in_degree.entry(synapse.to).or_default() += 1;
+            successors.entry(synapse.from).or_default().push(synapse.to);
+        }
+
+        let mut ready: BTreeSet<Uuid> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut order = Vec::with_capacity(self.neurons.len());
+        while let Some(id) = ready.pop_first() {
+            order.push(id);
+            for next in successors.get(&id).into_iter().flatten() {
+                let degree = in_degree.get_mut(next).expect("successor is a neuron");
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert(*next);
+                }
+            }
+        }
+
+        if order.len() == self.neurons.len() {
+            Ok(order)
+        } else {
+            Err(PropagationError::CycleDetected)
+        }
+    }
+
+    /// Computes neuron values in `order`.
+    ///
+    /// Synapses listed in `recurrent` read the value their source held before
+    /// this evaluation started; all other synapses read current values.
+    fn evaluate(&mut self, order: &[Uuid], inputs: &[(Uuid, f64)], recurrent: &BTreeSet<Uuid>) {
+        let previous: HashMap<Uuid, f64> = if recurrent.is_empty() {
+            HashMap::new()
+        } else {
+            self.neurons.iter().map(|(id, n)| (*id, n.value)).collect()
+        };
+        let mut incoming: HashMap<Uuid, Vec<(Uuid, f64, bool)>> = HashMap::new();
+        for synapse in self.enabled_synapses() {
+            incoming.entry(synapse.to).or_default().push((
+                synapse.from,
+                synapse.weight,
+                recurrent.contains(&synapse.id),
+            ));
+        }
+        let inputs: HashMap<Uuid, f64> = inputs.iter().copied().collect();
+
+        for id in order {
+            let value = match inputs.get(id) {
+                Some(value) => *value,
+                None if self.neurons[id].role == NeuronRole::Bias => 1.0,
+                None => {
+                    let sum: f64 = incoming
+                        .get(id)
+                        .into_iter()
+                        .flatten()
+                        .map(|(from, weight, is_recurrent)| {
+                            let source = if *is_recurrent {
+                                previous[from]
+                            } else {
+                                self.neurons[from].value
+                            };
+                            weight * source
+                        })
+                        .sum();
+                    let neuron = &self.neurons[id];
+                    neuron.activation.apply(sum + neuron.bias)
+                }
+            };
+            self.neurons
+                .get_mut(id)
+                .expect("ordered neuron exists")
+                .value = value;
+        }
+    }
+}
@@ -16,6 +16,15 @@ pub struct Synapse {
     pub to: Uuid,
     /// Weight applied during propagation.
     pub weight: f64,
+    /// Whether the synapse takes part in propagation. Disabled synapses keep
+    /// their weight and can be enabled again.
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    /// Innovation number shared by the synapses that connect the same
+    /// neurons, see [`InnovationTracker`](super::InnovationTracker). `None`
+    /// for synapses created without a tracker.
+    #[serde(default)]
+    pub innovation: Option<u64>,
     /// Curiosity score evaluating exploratory potential.
     pub curiosity_score: f64,
 }
@@ -28,6 +37,8 @@ impl Synapse {
             from,
             to,
             weight,
+            enabled: true,
+            innovation: None,
             curiosity_score: 0.0,
         }
     }
@@ -39,12 +50,27 @@ impl Synapse {
             from,
             to,
             weight,
+            enabled: true,
+            innovation: None,
             curiosity_score: 0.0,
         }
     }
 
+    /// Returns the synapse with its innovation number replaced by `innovation`.
+    #[must_use]
+    pub fn with_innovation(mut self, innovation: Option<u64>) -> Self {
+        self.innovation = innovation;
+        self
+    }
+
     /// Updates the curiosity score of the synapse.
     pub fn update_curiosity_score(&mut self, score: f64) {
         self.curiosity_score = score;
     }
 }
+
+/// Default of [`Synapse::enabled`] for synapses serialized before the flag
+/// existed.
+fn enabled_by_default() -> bool {
+    true
+}
@@ -0,0 +1,101 @@
+//! Choice of the neuron or synapse a random mutation applies to.
+//!
+//! Targets are drawn uniformly by default. [`TargetSelection::Curiosity`]
+//! instead draws them from the softmax of their curiosity scores, so that
+//! exploration concentrates on the elements the
+//! [curiosity score](crate::domain::CuriosityScoreUpdated) marks as novel.
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Strategy choosing the target of a random mutation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum TargetSelection {
+    /// Every candidate is equally likely.
+    #[default]
+    Uniform,
+    /// A candidate with curiosity score `s` is drawn with a probability
+    /// proportional to `exp(s / temperature)`. Low temperatures concentrate
+    /// on the highest scores and high ones approach a uniform choice; a
+    /// negative temperature favours the lowest scores instead.
+    Curiosity {
+        /// Softmax temperature; finite and non-zero.
+        temperature: f64,
+    },
+}
+
+/// Reasons why a [`TargetSelection`] cannot be used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetSelectionError {
+    /// The softmax temperature is zero or not finite.
+    InvalidTemperature,
+}
+
+impl TargetSelection {
+    /// Checks the parameters of the strategy.
+    ///
+    /// # Errors
+    /// Returns [`TargetSelectionError::InvalidTemperature`] if the
+    /// temperature is zero or not finite.
+    pub fn validate(&self) -> Result<(), TargetSelectionError> {
+        match *self {
+            TargetSelection::Curiosity { temperature }
+                if !temperature.is_finite() || temperature == 0.0 =>
+            {
+                Err(TargetSelectionError::InvalidTemperature)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Draws a target among `candidates`, given with their curiosity score.
+    ///
+    /// Returns `None` if `candidates` is empty. The strategy is expected to
+    /// be valid; non-finite scores count as the least favoured finite score,
+    /// and the choice is uniform if no score is finite.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::TargetSelection;
+    /// use rand::thread_rng;
+    /// use uuid::Uuid;
+    ///
+    /// let (curious, dull) = (Uuid::new_v4(), Uuid::new_v4());
+    /// let selection = TargetSelection::Curiosity { temperature: 0.01 };
+    /// let target = selection.choose(&[(curious, 1.0), (dull, 0.0)], &mut thread_rng());
+    /// assert_eq!(target, Some(curious));
+    /// ```
+    pub fn choose<R: Rng + ?Sized>(&self, candidates: &[(Uuid, f64)], rng: &mut R) -> Option<Uuid> {
+        let temperature = match *self {
+            TargetSelection::Uniform => return candidates.choose(rng).map(|(id, _)| *id),
+            TargetSelection::Curiosity { temperature } => temperature,
+        };
+        let logits: Vec<f64> = candidates
+            .iter()
+            .map(|(_, score)| score / temperature)
+            .collect();
+        // Non-finite scores, such as the NaN of a diverged network, are
+        // treated as the least favoured finite one.
+        let finite = logits.iter().copied().filter(|logit| logit.is_finite());
+        let min = finite.clone().fold(f64::INFINITY, f64::min);
+        let max = finite.fold(f64::NEG_INFINITY, f64::max);
+        if !max.is_finite() {
+            return candidates.choose(rng).map(|(id, _)| *id);
+        }
+        let logits: Vec<f64> = logits
+            .into_iter()
+            .map(|logit| if logit.is_finite() { logit } else { min })
+            .collect();
+        let weights: Vec<f64> = logits.iter().map(|logit| (logit - max).exp()).collect();
+        let total: f64 = weights.iter().sum();
+        let mut threshold = rng.gen_range(0.0..total);
+        for ((id, _), weight) in candidates.iter().zip(&weights) {
+            if threshold < *weight {
+                return Some(*id);
+            }
+            threshold -= weight;
+        }
+        candidates.last().map(|(id, _)| *id)
+    }
+}
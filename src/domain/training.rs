@@ -0,0 +1,167 @@
+//! Gradient computation for training a [`Network`].
+//!
+//! Gradients are obtained by backpropagating a [`Loss`] between the values of
+//! the target neurons and the expected values. The network must be acyclic,
+//! as required by [`Network::propagate`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Loss, Network, NeuronRole, PropagationError};
+
+/// Input values paired with the expected values of some neurons.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainingSample {
+    /// Pairs of input neuron identifier and value.
+    pub inputs: Vec<(Uuid, f64)>,
+    /// Pairs of neuron identifier and expected value.
+    pub targets: Vec<(Uuid, f64)>,
+}
+
+/// Loss, weight and bias gradients computed over one or more samples.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gradients {
+    /// Loss of the network before any update.
+    pub loss: f64,
+    /// Gradient of the loss for every enabled synapse weight, keyed by
+    /// synapse id.
+    pub weights: BTreeMap<Uuid, f64>,
+    /// Gradient of the loss for every neuron bias, keyed by neuron id.
+    pub biases: BTreeMap<Uuid, f64>,
+}
+
+impl Network {
+    /// Computes the loss, weight and bias gradients for a single sample.
+    ///
+    /// The loss is computed over the sample targets in their order. Input
+    /// neurons and [`NeuronRole::Bias`] neurons hold fixed values, so no
+    /// gradient flows through them. Every synapse and every neuron receives
+    /// an entry, which is `0.0` when it does not influence any target.
+    ///
+    /// # Errors
+    /// Returns the [`PropagationError`] raised by the forward pass.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::{
+    ///     Activation, DomainNetwork, Event, Loss, NeuronAdded, NeuronRole, TrainingSample,
+    /// };
+    /// use uuid::Uuid;
+    ///
+    /// let (input, output, synapse) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    /// let mut net = DomainNetwork::hydrate(&[
+    ///     Event::NeuronAdded(NeuronAdded {
+    ///         neuron_id: input,
+    ///         activation: Activation::Identity,
+    ///         role: NeuronRole::Input,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::NeuronAdded(NeuronAdded {
+    ///         neuron_id: output,
+    ///         activation: Activation::Identity,
+    ///         role: NeuronRole::Output,
+    ///         bias: 0.0,
+    ///     }),
+    ///     Event::SynapseCreated { id: synapse, from: input, to: output, weight: 1.0, innovation: None },
+    /// ]);
+    /// let sample = TrainingSample {
+    ///     inputs: vec![(input, 2.0)],
+    ///     targets: vec![(output, 1.0)],
+    /// };
+    /// let gradients = net.backpropagate(&sample, Loss::MeanSquaredError).unwrap();
+    /// assert_eq!(gradients.loss, 1.0);
+    /// assert_eq!(gradients.weights[&synapse], 4.0);
+    /// ```
+    pub fn backpropagate(
+        &mut self,
+        sample: &TrainingSample,
+        loss: Loss,
+    ) -> Result<Gradients, PropagationError> {
+        let outputs: Vec<Uuid> = sample.targets.iter().map(|(id, _)| *id).collect();
+        let targets: Vec<f64> = sample.targets.iter().map(|(_, t)| *t).collect();
+        let values = self.propagate(&sample.inputs, &outputs)?;
+        let order = self.topological_order()?;
+
+        let mut upstream: HashMap<Uuid, f64> = HashMap::new();
+        for (id, gradient) in outputs.iter().zip(loss.gradient(&values, &targets)) {
+            *upstream.entry(*id).or_default() += gradient;
+        }
+        let loss = loss.compute(&values, &targets);
+
+        let mut outgoing: HashMap<Uuid, Vec<(Uuid, Uuid, f64)>> = HashMap::new();
+        let mut weighted_inputs: HashMap<Uuid, f64> = HashMap::new();
+        for synapse in self.enabled_synapses() {
+            outgoing.entry(synapse.from).or_default().push((
+                synapse.id,
+                synapse.to,
+                synapse.weight,
+            ));
+            *weighted_inputs.entry(synapse.to).or_default() +=
+                synapse.weight * self.neurons[&synapse.from].value;
+        }
+        let clamped: HashSet<Uuid> = sample.inputs.iter().map(|(id, _)| *id).collect();
+
+        let mut deltas: HashMap<Uuid, f64> = HashMap::new();
+        let mut weights = BTreeMap::new();
+        let mut biases = BTreeMap::new();
+        for id in order.iter().rev() {
+            let neuron = &self.neurons[id];
+            let mut grad_value = upstream.get(id).copied().unwrap_or_default();
+            for (synapse_id, to, weight) in outgoing.get(id).into_iter().flatten() {
+                let delta = deltas[to];
+                grad_value += weight * delta;
+                weights.insert(*synapse_id, delta * neuron.value);
+            }
+            let delta = if clamped.contains(id) || neuron.role == NeuronRole::Bias {
+                0.0
+            } else {
+                let input = weighted_inputs.get(id).copied().unwrap_or_default() + neuron.bias;
+                grad_value * neuron.activation.derivative_at(input)
+            };
+            deltas.insert(*id, delta);
+            biases.insert(*id, delta);
+        }
+
+        Ok(Gradients {
+            loss,
+            weights,
+            biases,
+        })
+    }
+
+    /// Computes the loss, weight and bias gradients averaged over `samples`.
+    ///
+    /// Returns zero gradients for every enabled synapse and neuron when
+    /// `samples` is empty.
+    ///
+    /// # Errors
+    /// Returns the [`PropagationError`] raised by the first failing sample.
+    pub fn batch_gradients(
+        &mut self,
+        samples: &[TrainingSample],
+        loss: Loss,
+    ) -> Result<Gradients, PropagationError> {
+        let mut total = Gradients {
+            loss: 0.0,
+            weights: self.enabled_synapses().map(|s| (s.id, 0.0)).collect(),
+            biases: self.neurons.keys().map(|id| (*id, 0.0)).collect(),
+        };
+        if samples.is_empty() {
+            return Ok(total);
+        }
+        let count = samples.len() as f64;
+        for sample in samples {
+            let gradients = self.backpropagate(sample, loss)?;
+            total.loss += gradients.loss / count;
+            for (id, gradient) in gradients.weights {
+                *total.weights.entry(id).or_default() += gradient / count;
+            }
+            for (id, gradient) in gradients.biases {
+                *total.biases.entry(id).or_default() += gradient / count;
+            }
+        }
+        Ok(total)
+    }
+}
@@ -0,0 +1,71 @@
+//! Metadata wrapped around persisted events.
+//!
+//! An [`EventEnvelope`] records where an event sits in its log, when it was
+//! written and which command produced it. Logs written before envelopes
+//! existed hold bare events, read back as envelopes of schema version `0`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Schema version of the envelopes written by this release.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+/// A persisted event together with its metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<T> {
+    /// Zero-based position of the event in its log.
+    pub sequence: u64,
+    /// Time at which the event was written, unknown for bare events.
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Version of the envelope schema; `0` for bare events.
+    pub schema_version: u32,
+    /// Identifier shared by the events produced by one command.
+    #[serde(default)]
+    pub correlation_id: Option<Uuid>,
+    /// Identifier of the command or event that caused this one.
+    #[serde(default)]
+    pub causation_id: Option<Uuid>,
+    /// The wrapped event.
+    pub event: T,
+}
+
+impl<T> EventEnvelope<T> {
+    /// Wraps an event read from a bare line at position `sequence`.
+    pub fn bare(sequence: u64, event: T) -> Self {
+        Self {
+            sequence,
+            timestamp: None,
+            schema_version: 0,
+            correlation_id: None,
+            causation_id: None,
+            event,
+        }
+    }
+
+    /// Returns `true` if the event was stored without an envelope.
+    pub fn is_bare(&self) -> bool {
+        self.schema_version == 0
+    }
+}
+
+/// Identifiers a handler attaches to the envelopes of its events.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Correlation {
+    /// Identifier of the command in progress or last handled.
+    pub(crate) correlation_id: Option<Uuid>,
+    /// Identifier recorded as the cause of the following commands.
+    pub(crate) causation_id: Option<Uuid>,
+}
+
+impl Correlation {
+    /// Starts a new command and returns the correlation and causation
+    /// identifiers of its events.
+    ///
+    /// The identifier is not drawn from the handler's RNG, so that
+    /// correlating events does not change the course of seeded runs.
+    pub(crate) fn next_command(&mut self) -> (Option<Uuid>, Option<Uuid>) {
+        self.correlation_id = Some(Uuid::new_v4());
+        (self.correlation_id, self.causation_id)
+    }
+}
@@ -1,13 +1,21 @@
 //! Append-only event storage.
 //!
 //! [`FileEventStore`] persists domain events as JSON Lines using
-//! [`JsonlEventStore`].
+//! [`JsonlEventStore`], and each stream of a
+//! [`DirectoryEventStore`](super::DirectoryEventStore) is an
+//! [`EventStream`].
+//!
+//! Writers sharing a store guard against each other with
+//! [`EventStore::append_expected`], which only appends if the store still
+//! holds the number of events the writer last saw.
 
 use std::io;
 
+use uuid::Uuid;
+
 use crate::domain::Event;
 
-use super::JsonlEventStore;
+use super::{EventEnvelope, EventStream, JsonlEventStore};
 
 /// Storage backend for domain events.
 pub trait EventStore {
@@ -17,6 +25,82 @@ pub trait EventStore {
     fn append(&mut self, event: &Event) -> Result<(), Self::Error>;
     /// Load all events in chronological order.
     fn load(&mut self) -> Result<Vec<Event>, Self::Error>;
+    /// Load the events following the first `version` ones, typically those
+    /// appended after a [`Snapshot`](super::Snapshot) was taken.
+    ///
+    /// The default implementation loads every event and drops the first
+    /// ones; stores able to skip them more cheaply should override it.
+    fn load_from(&mut self, version: u64) -> Result<Vec<Event>, Self::Error> {
+        Ok(self.load()?.into_iter().skip(version as usize).collect())
+    }
+    /// Returns the number of events in the store, the version expected by
+    /// [`EventStore::append_expected`].
+    ///
+    /// The default implementation loads every event; stores able to count
+    /// them more cheaply should override it.
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.load()?.len() as u64)
+    }
+    /// Persists `events` only if the store holds exactly `expected_version`
+    /// events, and returns the new version.
+    ///
+    /// The default implementation checks the version before appending each
+    /// event in turn, which is not atomic: stores shared by several writers
+    /// should override it to check and append under one lock.
+    ///
+    /// # Errors
+    /// Returns [`AppendError::Conflict`] if another writer appended since the
+    /// version was read, in which case nothing is written, and
+    /// [`AppendError::StorageError`] if the store fails.
+    fn append_expected(
+        &mut self,
+        events: &[Event],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        let actual = self.version().map_err(AppendError::StorageError)?;
+        if actual != expected_version {
+            return Err(AppendError::Conflict {
+                expected: expected_version,
+                actual,
+            });
+        }
+        for event in events {
+            self.append(event).map_err(AppendError::StorageError)?;
+        }
+        Ok(actual + events.len() as u64)
+    }
+    /// Load all events in chronological order, with their envelopes.
+    ///
+    /// Stores that do not record envelopes return bare ones.
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<Event>>, Self::Error> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .enumerate()
+            .map(|(sequence, event)| EventEnvelope::bare(sequence as u64, event))
+            .collect())
+    }
+    /// Sets the correlation and causation identifiers recorded in the
+    /// envelopes of the following appends. Handler bases call it before
+    /// each command.
+    ///
+    /// Stores that do not record envelopes ignore them.
+    fn set_correlation(&mut self, _correlation_id: Option<Uuid>, _causation_id: Option<Uuid>) {}
+}
+
+/// Reasons why [`EventStore::append_expected`] refused or failed to append.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppendError<E> {
+    /// The store holds `actual` events instead of the `expected` ones:
+    /// another writer appended in the meantime.
+    Conflict {
+        /// Version the writer based its events on.
+        expected: u64,
+        /// Version found in the store.
+        actual: u64,
+    },
+    /// The store failed to read or write.
+    StorageError(E),
 }
 
 /// JSON-lines file based implementation of [`EventStore`].
@@ -32,4 +116,64 @@ impl EventStore for JsonlEventStore<Event> {
     fn load(&mut self) -> Result<Vec<Event>, Self::Error> {
         JsonlEventStore::load(self)
     }
+
+    fn load_from(&mut self, version: u64) -> Result<Vec<Event>, Self::Error> {
+        JsonlEventStore::load_from(self, version)
+    }
+
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        JsonlEventStore::version(self)
+    }
+
+    fn append_expected(
+        &mut self,
+        events: &[Event],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        JsonlEventStore::append_expected(self, events, expected_version)
+    }
+
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<Event>>, Self::Error> {
+        JsonlEventStore::load_envelopes(self)
+    }
+
+    fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        JsonlEventStore::set_correlation(self, correlation_id, causation_id);
+    }
+}
+
+impl EventStore for EventStream<Event> {
+    type Error = io::Error;
+
+    fn append(&mut self, event: &Event) -> Result<(), Self::Error> {
+        EventStream::append(self, event)
+    }
+
+    fn load(&mut self) -> Result<Vec<Event>, Self::Error> {
+        EventStream::load(self)
+    }
+
+    fn load_from(&mut self, version: u64) -> Result<Vec<Event>, Self::Error> {
+        EventStream::load_from(self, version)
+    }
+
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        EventStream::version(self)
+    }
+
+    fn append_expected(
+        &mut self,
+        events: &[Event],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        EventStream::append_expected(self, events, expected_version)
+    }
+
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<Event>>, Self::Error> {
+        EventStream::load_envelopes(self)
+    }
+
+    fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        EventStream::set_correlation(self, correlation_id, causation_id);
+    }
 }
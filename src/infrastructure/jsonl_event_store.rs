@@ -2,13 +2,34 @@
 //!
 //! `JsonlEventStore` persists each event as a single line of JSON.
 //! It accepts any event type that implements [`Serialize`] and [`DeserializeOwned`].
+//!
+//! Events are wrapped in an [`EventEnvelope`] by default. A store created
+//! with [`JsonlEventStore::bare`] writes them bare instead, which keeps the
+//! logs of seeded runs byte-identical. Both kinds of lines can be mixed in
+//! one log and are read back by either store.
+//!
+//! Appends take an exclusive lock on the file, so writers sharing a log
+//! never interleave their lines and [`JsonlEventStore::append_expected`]
+//! checks the version and appends in one step.
 
 use std::fs::{File, OpenOptions};
 use std::io::{self, BufRead, BufReader, Write};
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
-use serde::{de::DeserializeOwned, Serialize};
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{AppendError, EventEnvelope, EVENT_SCHEMA_VERSION};
+
+/// A line of the log: an event in its envelope, or a bare event.
+#[derive(Deserialize)]
+#[serde(untagged, bound = "T: DeserializeOwned")]
+enum Line<T> {
+    Envelope(EventEnvelope<T>),
+    Bare(T),
+}
 
 /// Append-only storage backed by a JSON Lines file.
 ///
@@ -37,21 +58,73 @@ use serde::{de::DeserializeOwned, Serialize};
 #[derive(Debug)]
 pub struct JsonlEventStore<T> {
     path: PathBuf,
+    envelopes: bool,
+    /// Length in bytes of the log and number of events it held when they
+    /// were last counted, so they are only counted again once another
+    /// writer has appended.
+    counted: Option<(u64, u64)>,
+    correlation_id: Option<Uuid>,
+    causation_id: Option<Uuid>,
     _marker: PhantomData<T>,
 }
 
 impl<T> JsonlEventStore<T> {
-    /// Creates a new store writing to the specified path.
+    /// Creates a new store writing to the specified path, wrapping every
+    /// appended event in an [`EventEnvelope`].
+    ///
+    /// Sequence numbers continue from the events already in the log,
+    /// including those appended by other writers.
     ///
     /// # Arguments
     ///
     /// * `path` - Location of the JSON Lines file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aei_framework::infrastructure::JsonlEventStore;
+    /// use uuid::Uuid;
+    ///
+    /// # let path = std::env::temp_dir().join(format!("aei_envelope_doc_{}.log", Uuid::new_v4()));
+    /// let mut store = JsonlEventStore::<u32>::new(path.clone());
+    /// let command = Uuid::new_v4();
+    /// store.set_correlation(Some(command), None);
+    /// store.append(&7).unwrap();
+    /// let envelopes = store.load_envelopes().unwrap();
+    /// assert_eq!(envelopes[0].correlation_id, Some(command));
+    /// assert!(envelopes[0].timestamp.is_some());
+    /// # let _ = std::fs::remove_file(path);
+    /// ```
     pub fn new(path: PathBuf) -> Self {
         Self {
             path,
+            envelopes: true,
+            counted: None,
+            correlation_id: None,
+            causation_id: None,
             _marker: PhantomData,
         }
     }
+
+    /// Creates a store writing bare events, without envelopes.
+    ///
+    /// Envelopes record the time of each append and the identifiers of its
+    /// command, which differ from run to run; bare logs of seeded runs stay
+    /// byte-identical.
+    pub fn bare(path: PathBuf) -> Self {
+        Self {
+            envelopes: false,
+            ..Self::new(path)
+        }
+    }
+
+    /// Sets the correlation and causation identifiers recorded in the
+    /// envelopes of the following appends, typically before handling a
+    /// command. Bare stores ignore them.
+    pub fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        self.correlation_id = correlation_id;
+        self.causation_id = causation_id;
+    }
 }
 
 impl<T> JsonlEventStore<T>
@@ -82,12 +155,152 @@ where
     /// # std::fs::remove_file(path).unwrap();
     /// ```
     pub fn append(&mut self, event: &T) -> Result<(), io::Error> {
+        match self.write(std::slice::from_ref(event), None, None) {
+            Ok(_) => Ok(()),
+            Err(AppendError::StorageError(error)) => Err(error),
+            Err(AppendError::Conflict { .. }) => unreachable!("no version expected"),
+        }
+    }
+
+    /// Persists `events` only if the log holds exactly `expected_version`
+    /// events, and returns the new version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AppendError::Conflict`] if another writer appended since the
+    /// version was read, in which case nothing is written, and
+    /// [`AppendError::StorageError`] if the log cannot be read or written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aei_framework::infrastructure::{AppendError, JsonlEventStore};
+    /// # use uuid::Uuid;
+    ///
+    /// # let path = std::env::temp_dir().join(format!("aei_expected_doc_{}.log", Uuid::new_v4()));
+    /// let mut store = JsonlEventStore::<u32>::new(path.clone());
+    /// let version = store.version().unwrap();
+    /// JsonlEventStore::<u32>::new(path.clone()).append(&1).unwrap();
+    /// assert!(matches!(
+    ///     store.append_expected(&[2], version),
+    ///     Err(AppendError::Conflict { expected: 0, actual: 1 })
+    /// ));
+    /// assert_eq!(store.append_expected(&[2], 1).unwrap(), 2);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn append_expected(
+        &mut self,
+        events: &[T],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<io::Error>> {
+        self.write(events, Some(expected_version), None)
+    }
+
+    /// Returns the number of events in the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the file cannot be read.
+    pub fn version(&mut self) -> Result<u64, io::Error> {
+        if !self.path.exists() {
+            return Ok(0);
+        }
+        let file = File::open(&self.path)?;
+        file.lock_shared()?;
+        self.count(&file)
+    }
+
+    /// Appends `events` numbered from `first_sequence` instead of their
+    /// position in the log, for logs holding one stream of a larger store.
+    pub(crate) fn append_sequenced(
+        &mut self,
+        events: &[T],
+        first_sequence: u64,
+    ) -> Result<u64, io::Error> {
+        match self.write(events, None, Some(first_sequence)) {
+            Ok(version) => Ok(version),
+            Err(AppendError::StorageError(error)) => Err(error),
+            Err(AppendError::Conflict { .. }) => unreachable!("no version expected"),
+        }
+    }
+
+    /// Appends `events` under an exclusive lock, after checking the version
+    /// of the log if one is expected. Envelopes are numbered from
+    /// `first_sequence`, or from the position of the events in the log.
+    fn write(
+        &mut self,
+        events: &[T],
+        expected_version: Option<u64>,
+        first_sequence: Option<u64>,
+    ) -> Result<u64, AppendError<io::Error>> {
         let mut file = OpenOptions::new()
             .create(true)
+            .read(true)
             .append(true)
-            .open(&self.path)?;
-        let json = serde_json::to_string(event).map_err(io::Error::other)?;
-        writeln!(file, "{json}")
+            .open(&self.path)
+            .map_err(AppendError::StorageError)?;
+        file.lock().map_err(AppendError::StorageError)?;
+        let version = self.count(&file).map_err(AppendError::StorageError)?;
+        if let Some(expected) = expected_version {
+            if expected != version {
+                return Err(AppendError::Conflict {
+                    expected,
+                    actual: version,
+                });
+            }
+        }
+        let mut lines = String::new();
+        for (sequence, event) in (first_sequence.unwrap_or(version)..).zip(events) {
+            lines.push_str(
+                &self
+                    .serialize(sequence, event)
+                    .map_err(AppendError::StorageError)?,
+            );
+            lines.push('\n');
+        }
+        file.write_all(lines.as_bytes())
+            .map_err(AppendError::StorageError)?;
+        let length = file.metadata().map_err(AppendError::StorageError)?.len();
+        let version = version + events.len() as u64;
+        self.counted = Some((length, version));
+        Ok(version)
+    }
+
+    /// Serializes `event`, wrapped in an envelope at `sequence` if the store
+    /// records envelopes.
+    fn serialize(&self, sequence: u64, event: &T) -> Result<String, io::Error> {
+        if self.envelopes {
+            serde_json::to_string(&EventEnvelope {
+                sequence,
+                timestamp: Some(Utc::now()),
+                schema_version: EVENT_SCHEMA_VERSION,
+                correlation_id: self.correlation_id,
+                causation_id: self.causation_id,
+                event,
+            })
+        } else {
+            serde_json::to_string(event)
+        }
+        .map_err(io::Error::other)
+    }
+
+    /// Counts the events of the locked `file`, reusing the last count if
+    /// the file has not grown since.
+    fn count(&mut self, file: &File) -> Result<u64, io::Error> {
+        let length = file.metadata()?.len();
+        if let Some((counted_length, count)) = self.counted {
+            if counted_length == length {
+                return Ok(count);
+            }
+        }
+        let mut count = 0;
+        for line in BufReader::new(file).lines() {
+            if !line?.trim().is_empty() {
+                count += 1;
+            }
+        }
+        self.counted = Some((length, count));
+        Ok(count)
     }
 
     /// Load all events in chronological order.
@@ -117,20 +330,71 @@ where
     /// # std::fs::remove_file(path).unwrap();
     /// ```
     pub fn load(&mut self) -> Result<Vec<T>, io::Error> {
-        let mut events = Vec::new();
+        self.load_from(0)
+    }
+
+    /// Load the events following the first `version` ones, in chronological
+    /// order. The skipped lines are not deserialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the file cannot be read or an event fails to
+    /// deserialize.
+    pub fn load_from(&mut self, version: u64) -> Result<Vec<T>, io::Error> {
+        Ok(self
+            .envelopes_from(version)?
+            .into_iter()
+            .map(|envelope| envelope.event)
+            .collect())
+    }
+
+    /// Load all events in chronological order, with their envelopes.
+    ///
+    /// Bare lines are returned as [`EventEnvelope::bare`] envelopes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the file cannot be read or an event fails to
+    /// deserialize.
+    pub fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<T>>, io::Error> {
+        self.envelopes_from(0)
+    }
+
+    /// Reads the envelopes following the first `version` ones.
+    fn envelopes_from(&self, version: u64) -> Result<Vec<EventEnvelope<T>>, io::Error> {
+        self.lines()?
+            .iter()
+            .enumerate()
+            .skip(version as usize)
+            .map(|(sequence, line)| {
+                match serde_json::from_str(line) {
+                    Ok(Line::Envelope(envelope)) => Ok(envelope),
+                    Ok(Line::Bare(event)) => Ok(EventEnvelope::bare(sequence as u64, event)),
+                    // Parse the line again as a bare event to report why the
+                    // event does not deserialize.
+                    Err(_) => serde_json::from_str(line)
+                        .map(|event| EventEnvelope::bare(sequence as u64, event)),
+                }
+                .map_err(io::Error::other)
+            })
+            .collect()
+    }
+
+    /// Reads the non-empty lines of the log.
+    fn lines(&self) -> Result<Vec<String>, io::Error> {
         if !self.path.exists() {
-            return Ok(events);
+            return Ok(Vec::new());
         }
         let file = File::open(&self.path)?;
+        file.lock_shared()?;
         let reader = BufReader::new(file);
+        let mut lines = Vec::new();
         for line in reader.lines() {
             let line = line?;
-            if line.trim().is_empty() {
-                continue;
+            if !line.trim().is_empty() {
+                lines.push(line);
             }
-            let event: T = serde_json::from_str(&line).map_err(io::Error::other)?;
-            events.push(event);
         }
-        Ok(events)
+        Ok(lines)
     }
 }
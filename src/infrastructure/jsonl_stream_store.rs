@@ -0,0 +1,377 @@
+//! Generic JSON-lines store holding one event stream per aggregate.
+//!
+//! `JsonlStreamStore` keeps a directory with one segment per stream, named
+//! after the stream identifier. Each segment is a JSON Lines log of
+//! [`EventEnvelope`]s whose sequence numbers are shared by the whole
+//! directory, so the events of every stream can be merged back in the order
+//! they were appended.
+//!
+//! [`JsonlStreamStore::stream`] returns an [`EventStream`], the store of a
+//! single stream, on which handlers are built like on any other event store.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+use super::{AppendError, EventEnvelope, JsonlEventStore};
+
+/// File holding the sequence number of the next event of the directory.
+const SEQUENCE_FILE: &str = "sequence";
+/// File locked while appending, so sequence numbers follow the order in
+/// which events reach their segments.
+const LOCK_FILE: &str = ".lock";
+/// Extension of the segment files.
+const SEGMENT_EXTENSION: &str = "log";
+
+/// Event storage backed by a directory of JSON Lines segments, one per
+/// stream.
+///
+/// # Examples
+///
+/// ```
+/// use aei_framework::infrastructure::JsonlStreamStore;
+/// use uuid::Uuid;
+///
+/// # let dir = std::env::temp_dir().join(format!("aei_streams_doc_{}", Uuid::new_v4()));
+/// let mut streams = JsonlStreamStore::<u32>::new(dir.clone());
+/// let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+/// streams.append(a, &[1, 2]).unwrap();
+/// streams.append(b, &[3]).unwrap();
+/// streams.append(a, &[4]).unwrap();
+///
+/// assert_eq!(streams.load(a).unwrap(), vec![1, 2, 4]);
+/// let all: Vec<u32> = streams.load_all().unwrap().into_iter().map(|(_, e)| e.event).collect();
+/// assert_eq!(all, vec![1, 2, 3, 4]);
+/// # std::fs::remove_dir_all(dir).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct JsonlStreamStore<T> {
+    dir: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T> JsonlStreamStore<T> {
+    /// Creates a store keeping its segments in `dir`, created on the first
+    /// append.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the store of the stream `stream_id`, which handlers accept in
+    /// place of a single-log store.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aei_framework::{AddRandomNeuronCommand, AddRandomNeuronHandler, DirectoryEventStore};
+    /// use rand::thread_rng;
+    /// use uuid::Uuid;
+    ///
+    /// # let dir = std::env::temp_dir().join(format!("aei_stream_doc_{}", Uuid::new_v4()));
+    /// let streams = DirectoryEventStore::new(dir.clone());
+    /// let network_id = Uuid::new_v4();
+    /// let mut handler = AddRandomNeuronHandler::new(streams.stream(network_id), thread_rng()).unwrap();
+    /// handler.handle(AddRandomNeuronCommand::default()).unwrap();
+    /// # std::fs::remove_dir_all(dir).unwrap();
+    /// ```
+    pub fn stream(&self, stream_id: Uuid) -> EventStream<T> {
+        EventStream {
+            streams: Self::new(self.dir.clone()),
+            stream_id,
+            segment: self.segment(stream_id),
+        }
+    }
+
+    /// Store of the segment of `stream_id`.
+    fn segment(&self, stream_id: Uuid) -> JsonlEventStore<T> {
+        JsonlEventStore::new(self.dir.join(format!("{stream_id}.{SEGMENT_EXTENSION}")))
+    }
+
+    /// Locks the directory, exclusively to append and shared to read.
+    fn lock(&self, exclusive: bool) -> Result<File, io::Error> {
+        fs::create_dir_all(&self.dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))?;
+        if exclusive {
+            file.lock()?;
+        } else {
+            file.lock_shared()?;
+        }
+        Ok(file)
+    }
+}
+
+impl<T> JsonlStreamStore<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Persists `events` at the end of the stream `stream_id`, which is
+    /// created if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if an event cannot be serialized or written.
+    pub fn append(&mut self, stream_id: Uuid, events: &[T]) -> Result<(), io::Error> {
+        let mut segment = self.segment(stream_id);
+        match self.write(&mut segment, events, None) {
+            Ok(_) => Ok(()),
+            Err(AppendError::StorageError(error)) => Err(error),
+            Err(AppendError::Conflict { .. }) => unreachable!("no version expected"),
+        }
+    }
+
+    /// Persists `events` only if the stream `stream_id` holds exactly
+    /// `expected_version` events, and returns its new version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AppendError::Conflict`] if another writer appended to the
+    /// stream since the version was read, in which case nothing is written,
+    /// and [`AppendError::StorageError`] if the stream cannot be read or
+    /// written.
+    pub fn append_expected(
+        &mut self,
+        stream_id: Uuid,
+        events: &[T],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<io::Error>> {
+        let mut segment = self.segment(stream_id);
+        self.write(&mut segment, events, Some(expected_version))
+    }
+
+    /// Loads the events of the stream `stream_id` in chronological order;
+    /// an unknown stream is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the segment cannot be read or an event fails
+    /// to deserialize.
+    pub fn load(&mut self, stream_id: Uuid) -> Result<Vec<T>, io::Error> {
+        self.segment(stream_id).load()
+    }
+
+    /// Loads the events of the stream `stream_id` with their envelopes.
+    ///
+    /// # Errors
+    ///
+    /// See [`JsonlStreamStore::load`].
+    pub fn load_envelopes(&mut self, stream_id: Uuid) -> Result<Vec<EventEnvelope<T>>, io::Error> {
+        self.segment(stream_id).load_envelopes()
+    }
+
+    /// Returns the identifiers of the streams holding a segment, in
+    /// ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the directory cannot be read.
+    pub fn list_streams(&mut self) -> Result<Vec<Uuid>, io::Error> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut streams = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == SEGMENT_EXTENSION) {
+                if let Some(id) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| Uuid::parse_str(stem).ok())
+                {
+                    streams.push(id);
+                }
+            }
+        }
+        streams.sort();
+        Ok(streams)
+    }
+
+    /// Loads the events of every stream, tagged with their stream
+    /// identifier, in the order they were appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if a segment cannot be read or an event fails to
+    /// deserialize.
+    pub fn load_all(&mut self) -> Result<Vec<(Uuid, EventEnvelope<T>)>, io::Error> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let _lock = self.lock(false)?;
+        let mut events = Vec::new();
+        for stream_id in self.list_streams()? {
+            events.extend(
+                self.load_envelopes(stream_id)?
+                    .into_iter()
+                    .map(|envelope| (stream_id, envelope)),
+            );
+        }
+        events.sort_by_key(|(_, envelope)| envelope.sequence);
+        Ok(events)
+    }
+
+    /// Appends `events` to `segment` under the directory lock, numbering
+    /// them from the directory's next sequence number.
+    fn write(
+        &self,
+        segment: &mut JsonlEventStore<T>,
+        events: &[T],
+        expected_version: Option<u64>,
+    ) -> Result<u64, AppendError<io::Error>> {
+        let _lock = self.lock(true).map_err(AppendError::StorageError)?;
+        let version = segment.version().map_err(AppendError::StorageError)?;
+        if let Some(expected) = expected_version {
+            if expected != version {
+                return Err(AppendError::Conflict {
+                    expected,
+                    actual: version,
+                });
+            }
+        }
+        if events.is_empty() {
+            return Ok(version);
+        }
+        // The counter moves first: a crash in between leaves a gap in the
+        // sequence numbers rather than numbers used twice.
+        let next = self.next_sequence().map_err(AppendError::StorageError)?;
+        self.set_next_sequence(next + events.len() as u64)
+            .map_err(AppendError::StorageError)?;
+        segment
+            .append_sequenced(events, next)
+            .map_err(AppendError::StorageError)
+    }
+
+    /// Reads the sequence number of the next event of the directory.
+    fn next_sequence(&self) -> Result<u64, io::Error> {
+        let path = self.dir.join(SEQUENCE_FILE);
+        if !path.exists() {
+            return Ok(0);
+        }
+        fs::read_to_string(path)?
+            .trim()
+            .parse()
+            .map_err(io::Error::other)
+    }
+
+    /// Replaces the sequence number of the next event of the directory.
+    fn set_next_sequence(&self, sequence: u64) -> Result<(), io::Error> {
+        let path = self.dir.join(SEQUENCE_FILE);
+        let mut temporary = path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, sequence.to_string())?;
+        fs::rename(&temporary, path)
+    }
+}
+
+/// Store of a single stream of a [`JsonlStreamStore`].
+///
+/// Appends go through the directory so that they are numbered globally;
+/// reads only touch the segment of the stream.
+#[derive(Debug)]
+pub struct EventStream<T> {
+    streams: JsonlStreamStore<T>,
+    stream_id: Uuid,
+    segment: JsonlEventStore<T>,
+}
+
+impl<T> EventStream<T> {
+    /// Returns the identifier of the stream.
+    pub fn stream_id(&self) -> Uuid {
+        self.stream_id
+    }
+
+    /// Sets the correlation and causation identifiers recorded in the
+    /// envelopes of the following appends, see
+    /// [`JsonlEventStore::set_correlation`].
+    pub fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        self.segment.set_correlation(correlation_id, causation_id);
+    }
+}
+
+impl<T> EventStream<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Persists an event at the end of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the event cannot be serialized or written.
+    pub fn append(&mut self, event: &T) -> Result<(), io::Error> {
+        match self.append_all(std::slice::from_ref(event), None) {
+            Ok(_) => Ok(()),
+            Err(AppendError::StorageError(error)) => Err(error),
+            Err(AppendError::Conflict { .. }) => unreachable!("no version expected"),
+        }
+    }
+
+    /// Persists `events` only if the stream holds exactly `expected_version`
+    /// events, and returns the new version.
+    ///
+    /// # Errors
+    ///
+    /// See [`JsonlStreamStore::append_expected`].
+    pub fn append_expected(
+        &mut self,
+        events: &[T],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<io::Error>> {
+        self.append_all(events, Some(expected_version))
+    }
+
+    /// Returns the number of events in the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the segment cannot be read.
+    pub fn version(&mut self) -> Result<u64, io::Error> {
+        self.segment.version()
+    }
+
+    /// Loads the events of the stream in chronological order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`io::Error`] if the segment cannot be read or an event fails
+    /// to deserialize.
+    pub fn load(&mut self) -> Result<Vec<T>, io::Error> {
+        self.segment.load()
+    }
+
+    /// Loads the events following the first `version` ones.
+    ///
+    /// # Errors
+    ///
+    /// See [`EventStream::load`].
+    pub fn load_from(&mut self, version: u64) -> Result<Vec<T>, io::Error> {
+        self.segment.load_from(version)
+    }
+
+    /// Loads the events of the stream with their envelopes.
+    ///
+    /// # Errors
+    ///
+    /// See [`EventStream::load`].
+    pub fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<T>>, io::Error> {
+        self.segment.load_envelopes()
+    }
+
+    fn append_all(
+        &mut self,
+        events: &[T],
+        expected_version: Option<u64>,
+    ) -> Result<u64, AppendError<io::Error>> {
+        self.streams
+            .write(&mut self.segment, events, expected_version)
+    }
+}
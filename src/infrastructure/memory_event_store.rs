@@ -1,12 +1,17 @@
 //! Append-only store for [`MemoryEvent`](crate::domain::MemoryEvent).
 //!
-//! [`FileMemoryEventStore`] is a type alias over [`JsonlEventStore`].
+//! [`FileMemoryEventStore`] is a type alias over [`JsonlEventStore`], and
+//! each stream of a
+//! [`DirectoryMemoryEventStore`](super::DirectoryMemoryEventStore) is an
+//! [`EventStream`].
 
 use std::io;
 
+use uuid::Uuid;
+
 use crate::domain::MemoryEvent;
 
-use super::JsonlEventStore;
+use super::{AppendError, EventEnvelope, EventStream, JsonlEventStore};
 
 /// Storage backend dedicated to memory events.
 pub trait MemoryEventStore {
@@ -16,6 +21,66 @@ pub trait MemoryEventStore {
     fn append(&mut self, event: &MemoryEvent) -> Result<(), Self::Error>;
     /// Load all stored events in chronological order.
     fn load(&mut self) -> Result<Vec<MemoryEvent>, Self::Error>;
+    /// Load the events following the first `version` ones, typically those
+    /// appended after a [`Snapshot`](super::Snapshot) was taken.
+    ///
+    /// The default implementation loads every event and drops the first
+    /// ones; stores able to skip them more cheaply should override it.
+    fn load_from(&mut self, version: u64) -> Result<Vec<MemoryEvent>, Self::Error> {
+        Ok(self.load()?.into_iter().skip(version as usize).collect())
+    }
+    /// Returns the number of events in the store, the version expected by
+    /// [`MemoryEventStore::append_expected`].
+    ///
+    /// The default implementation loads every event; stores able to count
+    /// them more cheaply should override it.
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.load()?.len() as u64)
+    }
+    /// Persists `events` only if the store holds exactly `expected_version`
+    /// events, and returns the new version.
+    ///
+    /// The default implementation is not atomic, see
+    /// [`EventStore::append_expected`](super::EventStore::append_expected).
+    ///
+    /// # Errors
+    /// Returns [`AppendError::Conflict`] if another writer appended since the
+    /// version was read, in which case nothing is written, and
+    /// [`AppendError::StorageError`] if the store fails.
+    fn append_expected(
+        &mut self,
+        events: &[MemoryEvent],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        let actual = self.version().map_err(AppendError::StorageError)?;
+        if actual != expected_version {
+            return Err(AppendError::Conflict {
+                expected: expected_version,
+                actual,
+            });
+        }
+        for event in events {
+            self.append(event).map_err(AppendError::StorageError)?;
+        }
+        Ok(actual + events.len() as u64)
+    }
+    /// Load all events in chronological order, with their envelopes.
+    ///
+    /// Stores that do not record envelopes return bare ones.
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<MemoryEvent>>, Self::Error> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .enumerate()
+            .map(|(sequence, event)| EventEnvelope::bare(sequence as u64, event))
+            .collect())
+    }
+    /// Sets the correlation and causation identifiers recorded in the
+    /// envelopes of the following appends. Handler bases call it before
+    /// each command.
+    ///
+    /// Stores that do not record envelopes ignore them.
+    fn set_correlation(&mut self, _correlation_id: Option<Uuid>, _causation_id: Option<Uuid>) {}
 }
 
 /// JSON-lines file based implementation of [`MemoryEventStore`].
@@ -31,4 +96,64 @@ impl MemoryEventStore for JsonlEventStore<MemoryEvent> {
     fn load(&mut self) -> Result<Vec<MemoryEvent>, Self::Error> {
         JsonlEventStore::load(self)
     }
+
+    fn load_from(&mut self, version: u64) -> Result<Vec<MemoryEvent>, Self::Error> {
+        JsonlEventStore::load_from(self, version)
+    }
+
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        JsonlEventStore::version(self)
+    }
+
+    fn append_expected(
+        &mut self,
+        events: &[MemoryEvent],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        JsonlEventStore::append_expected(self, events, expected_version)
+    }
+
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<MemoryEvent>>, Self::Error> {
+        JsonlEventStore::load_envelopes(self)
+    }
+
+    fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        JsonlEventStore::set_correlation(self, correlation_id, causation_id);
+    }
+}
+
+impl MemoryEventStore for EventStream<MemoryEvent> {
+    type Error = io::Error;
+
+    fn append(&mut self, event: &MemoryEvent) -> Result<(), Self::Error> {
+        EventStream::append(self, event)
+    }
+
+    fn load(&mut self) -> Result<Vec<MemoryEvent>, Self::Error> {
+        EventStream::load(self)
+    }
+
+    fn load_from(&mut self, version: u64) -> Result<Vec<MemoryEvent>, Self::Error> {
+        EventStream::load_from(self, version)
+    }
+
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        EventStream::version(self)
+    }
+
+    fn append_expected(
+        &mut self,
+        events: &[MemoryEvent],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        EventStream::append_expected(self, events, expected_version)
+    }
+
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<MemoryEvent>>, Self::Error> {
+        EventStream::load_envelopes(self)
+    }
+
+    fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        EventStream::set_correlation(self, correlation_id, causation_id);
+    }
 }
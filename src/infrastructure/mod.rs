@@ -1,10 +1,24 @@
 //! Infrastructure components such as persistence adapters.
 
+mod envelope;
 mod event_store;
 mod jsonl_event_store;
+mod jsonl_stream_store;
 mod memory_event_store;
+mod population_event_store;
 pub mod projection;
+mod snapshot_store;
+mod stream_event_store;
 
-pub use event_store::{EventStore, FileEventStore};
+pub(crate) use envelope::Correlation;
+pub use envelope::{EventEnvelope, EVENT_SCHEMA_VERSION};
+pub use event_store::{AppendError, EventStore, FileEventStore};
 pub use jsonl_event_store::JsonlEventStore;
+pub use jsonl_stream_store::{EventStream, JsonlStreamStore};
 pub use memory_event_store::{FileMemoryEventStore, MemoryEventStore};
+pub use population_event_store::{FilePopulationEventStore, PopulationEventStore};
+pub use snapshot_store::{
+    FileSnapshotStore, NetworkSnapshot, Snapshot, SnapshotPolicy, SnapshotStore,
+};
+pub(crate) use snapshot_store::{Snapshotting, Versions};
+pub use stream_event_store::{DirectoryEventStore, DirectoryMemoryEventStore, StreamEventStore};
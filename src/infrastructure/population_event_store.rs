@@ -0,0 +1,61 @@
+//! Append-only store for [`PopulationEvent`](crate::domain::PopulationEvent).
+//!
+//! [`FilePopulationEventStore`] is a type alias over [`JsonlEventStore`].
+
+use std::io;
+
+use uuid::Uuid;
+
+use crate::domain::PopulationEvent;
+
+use super::{EventEnvelope, JsonlEventStore};
+
+/// Storage backend dedicated to population events.
+pub trait PopulationEventStore {
+    /// Error type returned by the store.
+    type Error;
+    /// Persist an event to the underlying storage.
+    fn append(&mut self, event: &PopulationEvent) -> Result<(), Self::Error>;
+    /// Load all stored events in chronological order.
+    fn load(&mut self) -> Result<Vec<PopulationEvent>, Self::Error>;
+    /// Load all events in chronological order, with their envelopes.
+    ///
+    /// Stores that do not record envelopes return bare ones.
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<PopulationEvent>>, Self::Error> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .enumerate()
+            .map(|(sequence, event)| EventEnvelope::bare(sequence as u64, event))
+            .collect())
+    }
+    /// Sets the correlation and causation identifiers recorded in the
+    /// envelopes of the following appends, see
+    /// [`EventStore::set_correlation`](super::EventStore::set_correlation).
+    ///
+    /// Stores that do not record envelopes ignore them.
+    fn set_correlation(&mut self, _correlation_id: Option<Uuid>, _causation_id: Option<Uuid>) {}
+}
+
+/// JSON-lines file based implementation of [`PopulationEventStore`].
+pub type FilePopulationEventStore = JsonlEventStore<PopulationEvent>;
+
+impl PopulationEventStore for JsonlEventStore<PopulationEvent> {
+    type Error = io::Error;
+
+    fn append(&mut self, event: &PopulationEvent) -> Result<(), Self::Error> {
+        JsonlEventStore::append(self, event)
+    }
+
+    fn load(&mut self) -> Result<Vec<PopulationEvent>, Self::Error> {
+        JsonlEventStore::load(self)
+    }
+
+    fn load_envelopes(&mut self) -> Result<Vec<EventEnvelope<PopulationEvent>>, Self::Error> {
+        JsonlEventStore::load_envelopes(self)
+    }
+
+    fn set_correlation(&mut self, correlation_id: Option<Uuid>, causation_id: Option<Uuid>) {
+        JsonlEventStore::set_correlation(self, correlation_id, causation_id);
+    }
+}
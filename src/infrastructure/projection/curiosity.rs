@@ -23,6 +23,18 @@ impl CuriosityScoreProjection {
         proj
     }
 
+    /// Builds the projection from a snapshot of its scores, taken with
+    /// [`CuriosityScoreProjection::scores`], and the events appended after
+    /// it.
+    #[must_use]
+    pub fn from_snapshot(scores: HashMap<Uuid, f64>, tail: &[Event]) -> Self {
+        let mut projection = Self { scores };
+        for event in tail {
+            projection.apply(event);
+        }
+        projection
+    }
+
     /// Applies a new event to update a score.
     pub fn apply(&mut self, event: &Event) {
         if let Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
@@ -40,4 +52,11 @@ impl CuriosityScoreProjection {
     pub fn get(&self, id: Uuid) -> Option<f64> {
         self.scores.get(&id).copied()
     }
+
+    /// Returns every score recorded so far, including those of removed
+    /// targets.
+    #[must_use]
+    pub fn scores(&self) -> &HashMap<Uuid, f64> {
+        &self.scores
+    }
 }
@@ -19,6 +19,17 @@ impl MemoryProjection {
         }
     }
 
+    /// Builds the projection from a snapshot of the memory and the events
+    /// appended after it.
+    #[must_use]
+    pub fn from_snapshot(memory: AdaptiveMemory, tail: &[MemoryEvent]) -> Self {
+        let mut projection = Self { memory };
+        for event in tail {
+            projection.apply(event);
+        }
+        projection
+    }
+
     /// Applies a new memory event to update the projection.
     pub fn apply(&mut self, event: &MemoryEvent) {
         self.memory.apply(event);
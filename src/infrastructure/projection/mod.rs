@@ -3,7 +3,9 @@
 mod curiosity;
 mod memory_projection;
 mod network;
+mod population;
 
 pub use curiosity::CuriosityScoreProjection;
 pub use memory_projection::MemoryProjection;
 pub use network::NetworkProjection;
+pub use population::PopulationProjection;
@@ -2,7 +2,7 @@
 
 use uuid::Uuid;
 
-use crate::domain::{Event, Network, Neuron, Synapse};
+use crate::domain::{Event, Network, Neuron, NeuronRole, Synapse};
 
 /// In-memory projection of the [`Network`] aggregate.
 #[derive(Debug, Default)]
@@ -19,6 +19,17 @@ impl NetworkProjection {
         }
     }
 
+    /// Builds the projection from a snapshot of the network and the events
+    /// appended after it.
+    #[must_use]
+    pub fn from_snapshot(network: Network, tail: &[Event]) -> Self {
+        let mut projection = Self { network };
+        for event in tail {
+            projection.apply(event);
+        }
+        projection
+    }
+
     /// Applies a new event to update the projection.
     pub fn apply(&mut self, event: &Event) {
         self.network.apply(event);
@@ -35,6 +46,12 @@ impl NetworkProjection {
         self.network.neurons()
     }
 
+    /// Returns all neurons with the given role.
+    #[must_use]
+    pub fn neurons_by_role(&self, role: NeuronRole) -> Vec<&Neuron> {
+        self.network.neurons_by_role(role)
+    }
+
     /// Returns all synapses contained in the projection.
     #[must_use]
     pub fn synapses(&self) -> Vec<&Synapse> {
@@ -50,4 +67,9 @@ impl NetworkProjection {
     pub fn activation(&self, id: Uuid) -> Option<crate::domain::Activation> {
         self.network.neurons.get(&id).map(|n| n.activation)
     }
+
+    /// Fetches the bias of a neuron by its identifier.
+    pub fn bias(&self, id: Uuid) -> Option<f64> {
+        self.network.neurons.get(&id).map(|n| n.bias)
+    }
 }
@@ -0,0 +1,74 @@
+//! Read model reflecting the current population and its species.
+
+use uuid::Uuid;
+
+use crate::domain::{Member, Population, PopulationEvent, Species};
+
+/// In-memory projection of the [`Population`] aggregate.
+#[derive(Debug, Default)]
+pub struct PopulationProjection {
+    population: Population,
+}
+
+impl PopulationProjection {
+    /// Builds the projection by replaying events.
+    #[must_use]
+    pub fn from_events(events: &[PopulationEvent]) -> Self {
+        Self {
+            population: Population::hydrate(events),
+        }
+    }
+
+    /// Builds the projection from a snapshot of the population and the
+    /// events appended after it.
+    #[must_use]
+    pub fn from_snapshot(population: Population, tail: &[PopulationEvent]) -> Self {
+        let mut projection = Self { population };
+        for event in tail {
+            projection.apply(event);
+        }
+        projection
+    }
+
+    /// Applies a new population event to update the projection.
+    pub fn apply(&mut self, event: &PopulationEvent) {
+        self.population.apply(event);
+    }
+
+    /// Returns the index of the generation in progress.
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.population.generation
+    }
+
+    /// Returns the current members ordered by identifier.
+    #[must_use]
+    pub fn members(&self) -> Vec<&Member> {
+        self.population.members.values().collect()
+    }
+
+    /// Returns the current member with the specified identifier, if any.
+    #[must_use]
+    pub fn member(&self, id: Uuid) -> Option<&Member> {
+        self.population.members.get(&id)
+    }
+
+    /// Returns the living species ordered by identifier.
+    #[must_use]
+    pub fn species(&self) -> Vec<&Species> {
+        self.population.species.values().collect()
+    }
+
+    /// Returns the current members of a species, ordered by identifier.
+    #[must_use]
+    pub fn species_members(&self, species_id: u64) -> Vec<&Member> {
+        self.population.species_members(species_id).collect()
+    }
+
+    /// Returns the species of a current member, if it was assigned one.
+    #[must_use]
+    pub fn member_species(&self, id: Uuid) -> Option<&Species> {
+        let species_id = self.member(id)?.species?;
+        self.population.species.get(&species_id)
+    }
+}
@@ -0,0 +1,277 @@
+//! Snapshots of aggregates, used to skip most of the replay on hydration.
+//!
+//! A [`Snapshot`] records the state of an aggregate together with the number
+//! of events it reflects, which is also the sequence number of the first
+//! event left to replay. Handlers built from a snapshot only load the events
+//! appended after it, and take new snapshots following a
+//! [`SnapshotPolicy`].
+//!
+//! [`FileSnapshotStore`] keeps the latest snapshot of one log as a JSON
+//! file.
+//!
+//! Handlers share the restoring and saving logic through the crate-private
+//! `Versions` and `Snapshotting` helpers, generic over the snapshotted state.
+
+use std::fs;
+use std::io;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::domain::{AdaptiveMemory, Event, InnovationTracker, MemoryEvent, Network};
+
+/// State of an aggregate after the first `version` events of its log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<T> {
+    /// Number of events reflected by `state`.
+    pub version: u64,
+    /// The aggregate state.
+    pub state: T,
+}
+
+/// State of a network handler: the network and the innovation counter,
+/// which also covers the synapses removed before the snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    /// The network aggregate.
+    pub network: Network,
+    /// Innovation number the next new connection receives.
+    pub next_innovation: u64,
+}
+
+impl NetworkSnapshot {
+    /// Captures `network` and the counter of `innovations`.
+    pub(crate) fn capture(network: &Network, innovations: &InnovationTracker) -> Self {
+        Self {
+            network: network.clone(),
+            next_innovation: innovations.peek(),
+        }
+    }
+
+    /// Returns a new tracker numbering after the recorded counter.
+    pub(crate) fn innovations(&self) -> InnovationTracker {
+        let innovations = InnovationTracker::new();
+        if let Some(last) = self.next_innovation.checked_sub(1) {
+            innovations.observe(last);
+        }
+        innovations
+    }
+}
+
+/// When handlers take snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotPolicy {
+    /// Only when asked to.
+    #[default]
+    OnDemand,
+    /// Whenever the given number of events, at least one, has been appended
+    /// since the last snapshot.
+    EveryEvents(u64),
+}
+
+impl SnapshotPolicy {
+    /// Returns `true` if a handler reflecting `version` events should replace
+    /// a snapshot taken at `snapshot_version`.
+    ///
+    /// # Examples
+    /// ```
+    /// use aei_framework::SnapshotPolicy;
+    ///
+    /// let policy = SnapshotPolicy::EveryEvents(100);
+    /// assert!(!policy.is_due(200, 299));
+    /// assert!(policy.is_due(200, 300));
+    /// assert!(!SnapshotPolicy::OnDemand.is_due(0, 1_000));
+    /// ```
+    #[must_use]
+    pub fn is_due(self, snapshot_version: u64, version: u64) -> bool {
+        match self {
+            SnapshotPolicy::OnDemand => false,
+            SnapshotPolicy::EveryEvents(events) => {
+                events > 0 && version >= snapshot_version.saturating_add(events)
+            }
+        }
+    }
+}
+
+/// Storage backend for the snapshots of one log.
+pub trait SnapshotStore<T> {
+    /// The error type produced by this snapshot store.
+    type Error;
+    /// Persists `snapshot`, replacing the previous one.
+    fn save(&mut self, snapshot: &Snapshot<T>) -> Result<(), Self::Error>;
+    /// Loads the latest snapshot, if any was taken.
+    fn load_latest(&mut self) -> Result<Option<Snapshot<T>>, Self::Error>;
+}
+
+/// Snapshot store keeping the latest snapshot in a JSON file.
+///
+/// Snapshots are written to a temporary file first and then moved in place,
+/// so a crash never leaves a truncated snapshot behind.
+///
+/// # Examples
+///
+/// ```
+/// use aei_framework::infrastructure::{FileSnapshotStore, Snapshot, SnapshotStore};
+/// # use uuid::Uuid;
+///
+/// # let path = std::env::temp_dir().join(format!("aei_snapshot_doc_{}.json", Uuid::new_v4()));
+/// let mut snapshots = FileSnapshotStore::<Vec<u32>>::new(path.clone());
+/// assert_eq!(snapshots.load_latest().unwrap(), None);
+/// let snapshot = Snapshot { version: 3, state: vec![1, 2, 3] };
+/// snapshots.save(&snapshot).unwrap();
+/// assert_eq!(snapshots.load_latest().unwrap(), Some(snapshot));
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FileSnapshotStore<T> {
+    path: PathBuf,
+    _marker: PhantomData<T>,
+}
+
+impl<T> FileSnapshotStore<T> {
+    /// Creates a store keeping its snapshot at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> SnapshotStore<T> for FileSnapshotStore<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    type Error = io::Error;
+
+    fn save(&mut self, snapshot: &Snapshot<T>) -> Result<(), Self::Error> {
+        let json = serde_json::to_string(snapshot).map_err(io::Error::other)?;
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, &self.path)
+    }
+
+    fn load_latest(&mut self) -> Result<Option<Snapshot<T>>, Self::Error> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let json = fs::read_to_string(&self.path)?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(io::Error::other)
+    }
+}
+
+/// Snapshotted state brought up to date with the events appended after its
+/// snapshot.
+pub(crate) trait Replay<E> {
+    /// Applies `events` in order.
+    fn replay(&mut self, events: &[E]);
+}
+
+impl Replay<Event> for NetworkSnapshot {
+    fn replay(&mut self, events: &[Event]) {
+        for event in events {
+            self.network.apply(event);
+        }
+        let innovations = self.innovations();
+        innovations.observe_events(events);
+        self.next_innovation = innovations.peek();
+    }
+}
+
+impl Replay<MemoryEvent> for AdaptiveMemory {
+    fn replay(&mut self, events: &[MemoryEvent]) {
+        for event in events {
+            self.apply(event);
+        }
+    }
+}
+
+/// Position of a handler in its log and in its snapshots.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Versions {
+    /// Number of events in the store that the handler reflects.
+    pub(crate) current: u64,
+    /// Version of the latest snapshot taken or loaded.
+    pub(crate) snapshot: u64,
+}
+
+impl Versions {
+    /// Versions of a handler that replayed `current` events from scratch.
+    pub(crate) fn new(current: u64) -> Self {
+        Self {
+            current,
+            snapshot: 0,
+        }
+    }
+
+    /// Restores the state of `snapshot` and replays the events that
+    /// `load_from` returns after its version.
+    pub(crate) fn restore<T, E, Err>(
+        snapshot: Snapshot<T>,
+        load_from: impl FnOnce(u64) -> Result<Vec<E>, Err>,
+    ) -> Result<(T, Self), Err>
+    where
+        T: Replay<E>,
+    {
+        let Snapshot { version, mut state } = snapshot;
+        let tail = load_from(version)?;
+        state.replay(&tail);
+        Ok((
+            state,
+            Self {
+                current: version + tail.len() as u64,
+                snapshot: version,
+            },
+        ))
+    }
+}
+
+/// Handler whose state can be saved as a [`Snapshot`].
+pub(crate) trait Snapshotting {
+    /// State recorded in the snapshots.
+    type State;
+
+    /// Returns the versions of the handler.
+    fn versions(&self) -> Versions;
+
+    /// Returns the versions of the handler for update.
+    fn versions_mut(&mut self) -> &mut Versions;
+
+    /// Captures the current state.
+    fn state(&self) -> Self::State;
+
+    /// Captures the current state at the current version.
+    fn capture(&self) -> Snapshot<Self::State> {
+        Snapshot {
+            version: self.versions().current,
+            state: self.state(),
+        }
+    }
+
+    /// Saves a snapshot of the current state to `snapshots`.
+    fn save<P: SnapshotStore<Self::State>>(&mut self, snapshots: &mut P) -> Result<(), P::Error> {
+        let snapshot = self.capture();
+        snapshots.save(&snapshot)?;
+        self.versions_mut().snapshot = snapshot.version;
+        Ok(())
+    }
+
+    /// Saves a snapshot to `snapshots` if `policy` says one is due, and
+    /// returns whether it did.
+    fn save_if_due<P: SnapshotStore<Self::State>>(
+        &mut self,
+        snapshots: &mut P,
+        policy: SnapshotPolicy,
+    ) -> Result<bool, P::Error> {
+        let versions = self.versions();
+        if !policy.is_due(versions.snapshot, versions.current) {
+            return Ok(false);
+        }
+        self.save(snapshots)?;
+        Ok(true)
+    }
+}
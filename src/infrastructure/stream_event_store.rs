@@ -0,0 +1,59 @@
+//! Event storage partitioned into streams, one per aggregate.
+//!
+//! A [`StreamEventStore`] hosts the logs of several networks or memories,
+//! each keyed by the identifier of its aggregate. [`DirectoryEventStore`]
+//! and [`DirectoryMemoryEventStore`] keep them as one JSON Lines segment per
+//! stream using [`JsonlStreamStore`].
+
+use std::io;
+
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{Event, MemoryEvent};
+
+use super::{EventEnvelope, JsonlStreamStore};
+
+/// Storage backend for events partitioned into streams.
+pub trait StreamEventStore<T> {
+    /// The error type produced by this event store.
+    type Error;
+    /// Persist `events` at the end of the stream `stream_id`.
+    fn append(&mut self, stream_id: Uuid, events: &[T]) -> Result<(), Self::Error>;
+    /// Load the events of the stream `stream_id` in chronological order.
+    fn load(&mut self, stream_id: Uuid) -> Result<Vec<T>, Self::Error>;
+    /// List the identifiers of the stored streams.
+    fn list_streams(&mut self) -> Result<Vec<Uuid>, Self::Error>;
+    /// Load the events of every stream, tagged with their stream identifier,
+    /// in the order they were appended across all streams.
+    fn load_all(&mut self) -> Result<Vec<(Uuid, EventEnvelope<T>)>, Self::Error>;
+}
+
+/// Directory based store of network event streams.
+pub type DirectoryEventStore = JsonlStreamStore<Event>;
+
+/// Directory based store of memory event streams.
+pub type DirectoryMemoryEventStore = JsonlStreamStore<MemoryEvent>;
+
+impl<T> StreamEventStore<T> for JsonlStreamStore<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    type Error = io::Error;
+
+    fn append(&mut self, stream_id: Uuid, events: &[T]) -> Result<(), Self::Error> {
+        JsonlStreamStore::append(self, stream_id, events)
+    }
+
+    fn load(&mut self, stream_id: Uuid) -> Result<Vec<T>, Self::Error> {
+        JsonlStreamStore::load(self, stream_id)
+    }
+
+    fn list_streams(&mut self) -> Result<Vec<Uuid>, Self::Error> {
+        JsonlStreamStore::list_streams(self)
+    }
+
+    fn load_all(&mut self) -> Result<Vec<(Uuid, EventEnvelope<T>)>, Self::Error> {
+        JsonlStreamStore::load_all(self)
+    }
+}
@@ -14,22 +14,41 @@ pub use application::memory::{
 };
 pub use application::{
     AddRandomNeuronCommand, AddRandomNeuronError, AddRandomNeuronHandler, AddRandomSynapseCommand,
-    AddRandomSynapseError, AddRandomSynapseHandler, Command, CommandHandler, CuriosityScope,
-    MutateNeuronActivationError, MutateRandomNeuronActivationCommand,
-    MutateRandomNeuronActivationHandler, MutateRandomSynapseWeightCommand,
-    MutateRandomSynapseWeightError, MutateRandomSynapseWeightHandler, NetworkHandlerBase, Query,
-    QueryHandler, QueryResult, RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
-    RemoveRandomNeuronCommand, RemoveRandomNeuronError, RemoveRandomNeuronHandler,
-    RemoveRandomSynapseCommand, RemoveRandomSynapseError, RemoveRandomSynapseHandler,
+    AddRandomSynapseError, AddRandomSynapseHandler, AppliedMutation, Command, CommandHandler,
+    CuriosityDecay, CuriosityScope, DecayCuriosityScoresCommand, DecayCuriosityScoresError,
+    EvolvePopulationCommand, MutateNeuronActivationError, MutateRandomNeuronActivationCommand,
+    MutateRandomNeuronActivationHandler, MutateRandomNeuronBiasCommand,
+    MutateRandomNeuronBiasError, MutateRandomNeuronBiasHandler, MutateRandomSynapseWeightCommand,
+    MutateRandomSynapseWeightError, MutateRandomSynapseWeightHandler, Mutation, MutationKind,
+    MutationOperator, MutationPolicy, MutationPolicyError, MutationReport, NetworkHandlerBase,
+    PopulationError, PopulationHandler, PopulationQuery, PopulationQueryHandler,
+    PopulationQueryResult, Query, QueryHandler, QueryResult, RecalculateCuriosityScoreCommand,
+    RecalculateCuriosityScoreHandler, RemoveRandomNeuronCommand, RemoveRandomNeuronError,
+    RemoveRandomNeuronHandler, RemoveRandomSynapseCommand, RemoveRandomSynapseError,
+    RemoveRandomSynapseHandler, SetNeuronBiasCommand, SetNeuronBiasError, SetNeuronBiasHandler,
     SetSynapseWeightCommand, SetSynapseWeightError, SetSynapseWeightHandler,
+    SplitRandomSynapseCommand, SplitRandomSynapseError, SplitRandomSynapseHandler,
+    ToggleRandomSynapseCommand, ToggleRandomSynapseError, ToggleRandomSynapseHandler,
+    TrainNetworkCommand, TrainNetworkError, TrainNetworkHandler,
 };
 pub use domain::{
-    Activation, AdaptiveMemory, CuriosityScoreUpdated, Event, MemoryEntry, MemoryEntryAdded,
-    MemoryEntryRemoved, MemoryEvent, MemoryPruned, MemoryScoreUpdated, Network as DomainNetwork,
-    Neuron, NeuronActivationMutated, NeuronAdded, NeuronRemoved, RandomNeuronAdded,
-    RandomNeuronRemoved, RandomSynapseAdded, RandomSynapseRemoved, Synapse, SynapseWeightMutated,
-    SynapseWeightSet,
+    crossover, Activation, ActivationDistribution, ActivationDistributionError, AdaptiveMemory,
+    CompatibilityCoefficients, CompatibilityThresholdAdjusted, ConfusionMatrix, CrossoverParent,
+    CuriosityIndex, CuriosityScoreUpdated, CuriosityStrategy, Evaluation, Event, FitnessEvaluated,
+    GenerationCompleted, Gradients, InnovationTracker, Loss, Member, MemberAdded, MemberRetired,
+    MemoryEntry, MemoryEntryAdded, MemoryEntryRemoved, MemoryEvent, MemoryPruned,
+    MemoryScoreUpdated, Network as DomainNetwork, Neuron, NeuronActivationMutated, NeuronAdded,
+    NeuronBiasMutated, NeuronBiasSet, NeuronBiasUpdate, NeuronInherited, NeuronRemoved, NeuronRole,
+    Optimizer, OptimizerConfig, Population, PopulationEvent, PredictionError, PropagationError,
+    RandomNeuronAdded, RandomNeuronRemoved, RandomSynapseAdded, RandomSynapseRemoved, Rarity,
+    RecencyWeightedRarity, RunSeeded, Selection, SpeciationConfig, Species, SpeciesAssigned,
+    SpeciesCreated, SpeciesExtinct, Synapse, SynapseDisabled, SynapseEnabled, SynapseInherited,
+    SynapseSplit, SynapseWeightMutated, SynapseWeightSet, SynapseWeightUpdate, TargetSelection,
+    TargetSelectionError, TrainingSample, TrainingStepCompleted, WeightVariance,
 };
 pub use infrastructure::{
-    EventStore, FileEventStore, FileMemoryEventStore, JsonlEventStore, MemoryEventStore,
+    AppendError, DirectoryEventStore, DirectoryMemoryEventStore, EventEnvelope, EventStore,
+    EventStream, FileEventStore, FileMemoryEventStore, FilePopulationEventStore, FileSnapshotStore,
+    JsonlEventStore, MemoryEventStore, NetworkSnapshot, PopulationEventStore, Snapshot,
+    SnapshotPolicy, SnapshotStore, StreamEventStore,
 };
@@ -25,3 +25,90 @@ fn test_activation_derivatives() {
     let expected_tanh = 1.0 - tanh_out * tanh_out;
     assert!((Activation::Tanh.derivative(tanh_out) - expected_tanh).abs() < 1e-8);
 }
+
+#[test]
+fn derivatives_match_finite_differences() {
+    let eps = 1e-6;
+    for activation in Activation::all() {
+        for x in [-2.3, -0.7, -0.1, 0.2, 0.9, 1.7] {
+            let numeric = (activation.apply(x + eps) - activation.apply(x - eps)) / (2.0 * eps);
+            let analytic = activation.derivative_at(x);
+            assert!(
+                (numeric - analytic).abs() < 1e-6,
+                "{activation:?} at {x}: numeric {numeric} vs analytic {analytic}"
+            );
+        }
+    }
+}
+
+#[test]
+fn output_derivatives_match_input_derivatives() {
+    for activation in [
+        Activation::LeakyReLU { alpha: 0.1 },
+        Activation::ELU { alpha: 2.0 },
+        Activation::Softplus,
+        Activation::Step,
+    ] {
+        for x in [-2.3, -0.7, 0.2, 1.7] {
+            let from_output = activation.derivative(activation.apply(x));
+            assert!(
+                (from_output - activation.derivative_at(x)).abs() < 1e-9,
+                "{activation:?} at {x}"
+            );
+        }
+    }
+    assert!(Activation::Gaussian.derivative(0.5).is_nan());
+}
+
+#[test]
+fn extended_activations_compute_expected_values() {
+    let leaky = Activation::LeakyReLU { alpha: 0.1 };
+    assert_eq!(leaky.apply(2.0), 2.0);
+    assert_eq!(leaky.apply(-2.0), -0.2);
+    assert_eq!(leaky.derivative_at(-2.0), 0.1);
+    let elu = Activation::ELU { alpha: 2.0 };
+    assert_eq!(elu.apply(0.0), 0.0);
+    assert!((elu.apply(-50.0) + 2.0).abs() < 1e-12);
+    assert!((Activation::GELU.apply(1.0) - 0.841_192).abs() < 1e-4);
+    assert!((Activation::Softplus.apply(0.0) - 2f64.ln()).abs() < 1e-12);
+    assert_eq!(Activation::Softplus.apply(1000.0), 1000.0);
+    assert_eq!(Activation::Swish.apply(0.0), 0.0);
+    assert_eq!(Activation::Gaussian.apply(0.0), 1.0);
+    assert_eq!(Activation::Sine.apply(0.0), 0.0);
+    assert_eq!(Activation::Step.apply(0.3), 1.0);
+    assert_eq!(Activation::Step.apply(-0.3), 0.0);
+    assert_eq!(Activation::Step.derivative_at(0.3), 0.0);
+    assert_eq!(Activation::Abs.apply(-1.5), 1.5);
+    assert_eq!(Activation::Abs.derivative_at(-1.5), -1.0);
+}
+
+#[test]
+fn registry_lists_every_function_once() {
+    let all = Activation::all();
+    assert_eq!(all.len(), 13);
+    for (i, a) in all.iter().enumerate() {
+        assert!(!all[i + 1..].contains(a), "{a:?} listed twice");
+    }
+    assert!(all.contains(&Activation::LeakyReLU { alpha: 0.01 }));
+    assert!(all.contains(&Activation::ELU { alpha: 1.0 }));
+    assert!(all.contains(&Activation::Sine));
+}
+
+#[test]
+fn parametric_activations_serialize_with_their_parameter() {
+    let leaky = Activation::LeakyReLU { alpha: 0.2 };
+    let json = serde_json::to_string(&leaky).unwrap();
+    assert_eq!(json, r#"{"LeakyReLU":{"alpha":0.2}}"#);
+    assert_eq!(serde_json::from_str::<Activation>(&json).unwrap(), leaky);
+    assert_eq!(
+        serde_json::to_string(&Activation::Sigmoid).unwrap(),
+        r#""Sigmoid""#
+    );
+    for activation in Activation::all() {
+        let json = serde_json::to_string(activation).unwrap();
+        assert_eq!(
+            &serde_json::from_str::<Activation>(&json).unwrap(),
+            activation
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, ActivationDistribution, ActivationDistributionError, AddRandomNeuronCommand,
+    AddRandomNeuronError, AddRandomNeuronHandler, Event, FileEventStore,
+    MutateNeuronActivationError, MutateRandomNeuronActivationCommand,
+    MutateRandomNeuronActivationHandler, NeuronRole, RandomNeuronAdded,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_distribution_{}.log", Uuid::new_v4()));
+    path
+}
+
+fn tanh_sigmoid() -> ActivationDistribution {
+    ActivationDistribution::weighted([(Activation::Tanh, 0.7), (Activation::Sigmoid, 0.3)])
+}
+
+#[test]
+fn sampling_follows_weights() {
+    let distribution = tanh_sigmoid();
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    let draws = 10_000;
+    let tanh = (0..draws)
+        .filter(|_| distribution.sample(&mut rng, None) == Some(Activation::Tanh))
+        .count();
+    let share = tanh as f64 / draws as f64;
+    assert!((share - 0.7).abs() < 0.02, "tanh share {share}");
+
+    assert_eq!(
+        distribution.sample(&mut rng, Some(Activation::Tanh)),
+        Some(Activation::Sigmoid)
+    );
+    let single = ActivationDistribution::uniform(&[Activation::Sine]);
+    assert_eq!(single.sample(&mut rng, Some(Activation::Sine)), None);
+}
+
+#[test]
+fn invalid_distributions_are_reported() {
+    let cases = [
+        (
+            ActivationDistribution::weighted([]),
+            ActivationDistributionError::Empty,
+        ),
+        (
+            ActivationDistribution::uniform(&[Activation::Tanh, Activation::Tanh]),
+            ActivationDistributionError::DuplicateActivation(Activation::Tanh),
+        ),
+        (
+            ActivationDistribution::uniform(&[Activation::LeakyReLU { alpha: f64::NAN }]),
+            ActivationDistributionError::InvalidActivation(Activation::LeakyReLU {
+                alpha: f64::NAN,
+            }),
+        ),
+        (
+            ActivationDistribution::weighted([(Activation::Tanh, -1.0)]),
+            ActivationDistributionError::InvalidWeight(Activation::Tanh),
+        ),
+        (
+            ActivationDistribution::weighted([(Activation::Tanh, f64::INFINITY)]),
+            ActivationDistributionError::InvalidWeight(Activation::Tanh),
+        ),
+        (
+            ActivationDistribution::weighted([(Activation::Tanh, 0.0), (Activation::Abs, 0.0)]),
+            ActivationDistributionError::ZeroTotalWeight,
+        ),
+        (
+            ActivationDistribution::weighted([
+                (Activation::Tanh, f64::MAX),
+                (Activation::Abs, f64::MAX),
+            ]),
+            ActivationDistributionError::InfiniteTotalWeight,
+        ),
+    ];
+    for (distribution, expected) in cases {
+        // NaN parameters never compare equal, so match on the variant.
+        let error = distribution.validate().unwrap_err();
+        match (&error, &expected) {
+            (
+                ActivationDistributionError::InvalidActivation(_),
+                ActivationDistributionError::InvalidActivation(_),
+            ) => {}
+            _ => assert_eq!(error, expected),
+        }
+    }
+    assert!(ActivationDistribution::default().validate().is_ok());
+    assert_eq!(
+        ActivationDistribution::default().weights.len(),
+        Activation::all().len()
+    );
+}
+
+#[test]
+fn add_random_neuron_uses_allowed_activations_only() {
+    let path = temp_path();
+    let rng = ChaCha8Rng::seed_from_u64(6);
+    let mut handler = AddRandomNeuronHandler::new(FileEventStore::new(path.clone()), rng).unwrap();
+    for _ in 0..20 {
+        handler
+            .handle(AddRandomNeuronCommand {
+                activations: ActivationDistribution::weighted([
+                    (Activation::Tanh, 0.7),
+                    (Activation::Sigmoid, 0.3),
+                    (Activation::Gaussian, 0.0),
+                ]),
+            })
+            .unwrap();
+    }
+    let events = FileEventStore::new(path).load().unwrap();
+    let activations: Vec<Activation> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::RandomNeuronAdded(RandomNeuronAdded { activation, .. }) => Some(*activation),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(activations.len(), 20);
+    assert!(activations
+        .iter()
+        .all(|a| matches!(a, Activation::Tanh | Activation::Sigmoid)));
+
+    assert_eq!(
+        handler.handle(AddRandomNeuronCommand {
+            activations: ActivationDistribution::weighted([]),
+        }),
+        Err(AddRandomNeuronError::InvalidActivationDistribution(
+            ActivationDistributionError::Empty
+        ))
+    );
+}
+
+#[test]
+fn mutation_respects_distribution() {
+    let mut store = FileEventStore::new(temp_path());
+    let neuron_id = Uuid::new_v4();
+    store
+        .append(&Event::RandomNeuronAdded(RandomNeuronAdded {
+            neuron_id,
+            activation: Activation::Tanh,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
+        }))
+        .unwrap();
+    let rng = ChaCha8Rng::seed_from_u64(7);
+    let mut handler = MutateRandomNeuronActivationHandler::new(store, rng).unwrap();
+
+    for _ in 0..6 {
+        handler
+            .handle(MutateRandomNeuronActivationCommand {
+                exclude_io: false,
+                activations: tanh_sigmoid(),
+            })
+            .unwrap();
+    }
+    // Each mutation must switch between the only two allowed functions.
+    assert_eq!(
+        handler.base.network.neurons[&neuron_id].activation,
+        Activation::Tanh
+    );
+
+    assert_eq!(
+        handler.handle(MutateRandomNeuronActivationCommand {
+            exclude_io: false,
+            activations: ActivationDistribution::uniform(&[Activation::Tanh]),
+        }),
+        Err(MutateNeuronActivationError::NoAlternativeActivation)
+    );
+    assert_eq!(
+        handler.handle(MutateRandomNeuronActivationCommand {
+            exclude_io: false,
+            activations: ActivationDistribution::weighted([(Activation::Tanh, -0.5)]),
+        }),
+        Err(MutateNeuronActivationError::InvalidActivationDistribution(
+            ActivationDistributionError::InvalidWeight(Activation::Tanh)
+        ))
+    );
+}
@@ -0,0 +1,351 @@
+use std::io;
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, AddMemoryEntryCommand, AddMemoryEntryHandler, AddRandomNeuronCommand,
+    AddRandomNeuronHandler, AppendError, Command, CommandHandler, DomainNetwork, Event, EventStore,
+    FileEventStore, FileMemoryEventStore, Loss, MemoryEvent, NeuronAdded, NeuronRole,
+    OptimizerConfig, RemoveRandomNeuronCommand, RemoveRandomNeuronError, RemoveRandomNeuronHandler,
+    SetSynapseWeightCommand, SetSynapseWeightHandler, TrainNetworkCommand, TrainNetworkError,
+    TrainNetworkHandler, TrainingSample,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_concurrency_{}.log", Uuid::new_v4()));
+    path
+}
+
+fn neuron() -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: Uuid::new_v4(),
+        activation: Activation::Identity,
+        role: NeuronRole::Hidden,
+        bias: 0.0,
+    })
+}
+
+/// File store to which another writer appends right after every load.
+struct BusyStore {
+    inner: FileEventStore,
+    other: FileEventStore,
+}
+
+impl EventStore for BusyStore {
+    type Error = io::Error;
+
+    fn append(&mut self, event: &Event) -> Result<(), Self::Error> {
+        self.inner.append(event)
+    }
+
+    fn load(&mut self) -> Result<Vec<Event>, Self::Error> {
+        let events = self.inner.load()?;
+        self.other.append(&neuron())?;
+        Ok(events)
+    }
+
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        self.inner.version()
+    }
+
+    fn append_expected(
+        &mut self,
+        events: &[Event],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        self.inner.append_expected(events, expected_version)
+    }
+}
+
+#[test]
+fn stale_appends_are_refused() {
+    let path = temp_path();
+    let mut first = FileEventStore::new(path.clone());
+    let mut second = FileEventStore::new(path.clone());
+    assert_eq!(first.append_expected(&[neuron(), neuron()], 0).unwrap(), 2);
+
+    let result = second.append_expected(&[neuron()], 0);
+    assert!(matches!(
+        result,
+        Err(AppendError::Conflict {
+            expected: 0,
+            actual: 2
+        })
+    ));
+    assert_eq!(second.version().unwrap(), 2);
+    assert_eq!(second.append_expected(&[neuron()], 2).unwrap(), 3);
+    assert!(matches!(
+        first.append_expected(&[neuron()], 2),
+        Err(AppendError::Conflict { actual: 3, .. })
+    ));
+    assert_eq!(FileEventStore::new(path.clone()).load().unwrap().len(), 3);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn handlers_reload_and_retry_after_a_conflict() {
+    let path = temp_path();
+    let mut adder = AddRandomNeuronHandler::new(
+        FileEventStore::new(path.clone()),
+        ChaCha8Rng::seed_from_u64(1),
+    )
+    .unwrap();
+    for _ in 0..2 {
+        adder.handle(AddRandomNeuronCommand::default()).unwrap();
+    }
+    let mut remover = RemoveRandomNeuronHandler::new(
+        FileEventStore::new(path.clone()),
+        ChaCha8Rng::seed_from_u64(2),
+    )
+    .unwrap();
+    let added = adder.handle(AddRandomNeuronCommand::default()).unwrap();
+    assert!(!remover.base.network.neurons.contains_key(&added));
+
+    // The remover works on the log extended by the adder.
+    remover.handle(RemoveRandomNeuronCommand).unwrap();
+    let events = FileEventStore::new(path.clone()).load().unwrap();
+    assert_eq!(remover.base.version(), events.len() as u64);
+    let network = DomainNetwork::hydrate(&events);
+    assert_eq!(network.neurons.len(), 2);
+    assert!(network
+        .neurons
+        .keys()
+        .eq(remover.base.network.neurons.keys()));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn retried_commands_read_the_latest_state() {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path.clone());
+    let (from, to, synapse_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    for event in [
+        neuron(),
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id: from,
+            activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
+        }),
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id: to,
+            activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
+        }),
+        Event::SynapseCreated {
+            id: synapse_id,
+            from,
+            to,
+            weight: 1.0,
+            innovation: None,
+        },
+    ] {
+        store.append(&event).unwrap();
+    }
+    let mut first = SetSynapseWeightHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let mut second = SetSynapseWeightHandler::new(FileEventStore::new(path.clone())).unwrap();
+    for (handler, new_weight) in [(&mut first, 2.0), (&mut second, 3.0)] {
+        handler
+            .handle(SetSynapseWeightCommand {
+                synapse_id,
+                new_weight,
+            })
+            .unwrap();
+    }
+
+    // The second write was based on the weight set by the first.
+    let old_weights: Vec<f64> = store
+        .load()
+        .unwrap()
+        .iter()
+        .filter_map(|e| match e {
+            Event::SynapseWeightSet(e) => Some(e.old_weight),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(old_weights, [1.0, 2.0]);
+    assert_eq!(second.network.synapses[&synapse_id].weight, 3.0);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn repeated_conflicts_are_reported() {
+    let path = temp_path();
+    FileEventStore::new(path.clone()).append(&neuron()).unwrap();
+    let store = BusyStore {
+        inner: FileEventStore::new(path.clone()),
+        other: FileEventStore::new(path.clone()),
+    };
+    let mut handler = RemoveRandomNeuronHandler::new(store, ChaCha8Rng::seed_from_u64(3)).unwrap();
+    assert_eq!(
+        handler.handle(RemoveRandomNeuronCommand),
+        Err(RemoveRandomNeuronError::Conflict)
+    );
+
+    // Only the other writer's neurons were written after the first one.
+    let events = FileEventStore::new(path.clone()).load().unwrap();
+    assert_eq!(events.len(), 3);
+    assert!(events.iter().all(|e| matches!(e, Event::NeuronAdded(_))));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn command_handlers_catch_up_before_appending() {
+    let path = temp_path();
+    let mut first = CommandHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let mut second = CommandHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+    for (handler, id) in [(&mut first, from), (&mut second, to)] {
+        handler
+            .handle(Command::CreateNeuron {
+                id,
+                activation: Activation::Identity,
+                role: NeuronRole::Hidden,
+                bias: 0.0,
+            })
+            .unwrap();
+    }
+    assert_eq!(second.network.neurons.len(), 2);
+
+    // The first handler numbers its synapse after catching up.
+    second
+        .handle(Command::CreateSynapse {
+            id: Uuid::new_v4(),
+            from,
+            to,
+            weight: 1.0,
+        })
+        .unwrap();
+    first
+        .handle(Command::CreateSynapse {
+            id: Uuid::new_v4(),
+            from: to,
+            to: from,
+            weight: 1.0,
+        })
+        .unwrap();
+    let innovations: Vec<Option<u64>> = FileEventStore::new(path.clone())
+        .load()
+        .unwrap()
+        .iter()
+        .filter_map(|e| match e {
+            Event::SynapseCreated { innovation, .. } => Some(*innovation),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(innovations, [Some(0), Some(1)]);
+    assert_eq!(first.network.synapses.len(), 2);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn memory_writers_prune_the_shared_memory() {
+    let path = temp_path();
+    let mut first = AddMemoryEntryHandler::new(FileMemoryEventStore::new(path.clone()), 1).unwrap();
+    let mut second =
+        AddMemoryEntryHandler::new(FileMemoryEventStore::new(path.clone()), 1).unwrap();
+    let add = |handler: &mut AddMemoryEntryHandler<_>, score| {
+        handler
+            .handle(AddMemoryEntryCommand {
+                event_type: "step".into(),
+                payload: json!({}),
+                score,
+            })
+            .unwrap()
+    };
+    let low = add(&mut first, 0.2);
+    let high = add(&mut second, 0.8);
+
+    // The second entry was added together with the pruning of the first.
+    let events = FileMemoryEventStore::new(path.clone()).load().unwrap();
+    assert_eq!(events.len(), 3);
+    assert!(matches!(&events[2], MemoryEvent::MemoryPruned(e) if e.removed_entries == [low]));
+    let ids: Vec<Uuid> = second.base.memory.entries.iter().map(|e| e.id).collect();
+    assert_eq!(ids, [high]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn training_reports_repeated_conflicts() {
+    let path = temp_path();
+    let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut store = FileEventStore::new(path.clone());
+    for (neuron_id, role) in [(input, NeuronRole::Input), (output, NeuronRole::Output)] {
+        store
+            .append(&Event::NeuronAdded(NeuronAdded {
+                neuron_id,
+                activation: Activation::Identity,
+                role,
+                bias: 0.0,
+            }))
+            .unwrap();
+    }
+    store
+        .append(&Event::SynapseCreated {
+            id: Uuid::new_v4(),
+            from: input,
+            to: output,
+            weight: 1.0,
+            innovation: None,
+        })
+        .unwrap();
+    let busy = BusyStore {
+        inner: FileEventStore::new(path.clone()),
+        other: FileEventStore::new(path.clone()),
+    };
+    let mut handler = TrainNetworkHandler::new(busy).unwrap();
+    let result = handler.handle(TrainNetworkCommand {
+        samples: vec![TrainingSample {
+            inputs: vec![(input, 1.0)],
+            targets: vec![(output, 0.0)],
+        }],
+        epochs: 1,
+        loss: Loss::MeanSquaredError,
+        optimizer: OptimizerConfig::sgd(0.1),
+    });
+    assert_eq!(result, Err(TrainNetworkError::Conflict));
+    assert!(!store
+        .load()
+        .unwrap()
+        .iter()
+        .any(|e| matches!(e, Event::TrainingStepCompleted(_))));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn concurrent_writers_never_lose_events() {
+    let path = temp_path();
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let mut store = FileEventStore::new(path);
+                for _ in 0..25 {
+                    loop {
+                        let version = store.version().unwrap();
+                        match store.append_expected(&[neuron()], version) {
+                            Ok(_) => break,
+                            Err(AppendError::Conflict { .. }) => continue,
+                            Err(AppendError::StorageError(error)) => panic!("{error}"),
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let envelopes = FileEventStore::new(path.clone()).load_envelopes().unwrap();
+    assert_eq!(envelopes.len(), 100);
+    for (i, envelope) in envelopes.iter().enumerate() {
+        assert_eq!(envelope.sequence, i as u64);
+    }
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,309 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    crossover, Activation, CrossoverParent, DomainNetwork, Event, EvolvePopulationCommand,
+    FileEventStore, FilePopulationEventStore, MutateRandomSynapseWeightCommand, Mutation,
+    NeuronAdded, NeuronInherited, NeuronRole, PopulationEvent, PopulationHandler, Selection,
+    SynapseDisabled, SynapseInherited,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn neuron(id: Uuid, activation: Activation, role: NeuronRole) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: id,
+        activation,
+        role,
+        bias: 0.0,
+    })
+}
+
+fn synapse(from: Uuid, to: Uuid, weight: f64, innovation: Option<u64>) -> Event {
+    Event::SynapseCreated {
+        id: Uuid::new_v4(),
+        from,
+        to,
+        weight,
+        innovation,
+    }
+}
+
+struct Parents {
+    a: DomainNetwork,
+    b: DomainNetwork,
+    ids: (Uuid, Uuid),
+    input: Uuid,
+    output: Uuid,
+    only_a: Uuid,
+    only_b: Uuid,
+}
+
+/// Two genomes sharing their input, output and `input → output` gene (with
+/// different weights), each with one hidden neuron of its own.
+fn parents() -> Parents {
+    let (input, output, only_a, only_b) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+    let shared = [
+        neuron(input, Activation::Identity, NeuronRole::Input),
+        neuron(output, Activation::Sigmoid, NeuronRole::Output),
+    ];
+    let mut a: Vec<Event> = shared.to_vec();
+    a.extend([
+        neuron(only_a, Activation::Tanh, NeuronRole::Hidden),
+        synapse(input, output, 1.0, Some(0)),
+        synapse(input, only_a, 0.5, Some(1)),
+        synapse(only_a, output, 0.5, Some(2)),
+    ]);
+    let mut b: Vec<Event> = shared.to_vec();
+    b.push(neuron(only_b, Activation::ReLU, NeuronRole::Hidden));
+    b.push(synapse(input, output, -1.0, Some(0)));
+    let disabled = match b.last() {
+        Some(Event::SynapseCreated { id, .. }) => *id,
+        _ => unreachable!(),
+    };
+    b.push(Event::SynapseDisabled(SynapseDisabled {
+        synapse_id: disabled,
+    }));
+    b.push(synapse(input, only_b, 0.5, Some(3)));
+    Parents {
+        a: DomainNetwork::hydrate(&a),
+        b: DomainNetwork::hydrate(&b),
+        ids: (Uuid::new_v4(), Uuid::new_v4()),
+        input,
+        output,
+        only_a,
+        only_b,
+    }
+}
+
+fn innovations(net: &DomainNetwork) -> Vec<u64> {
+    let mut numbers: Vec<u64> = net.synapses.values().filter_map(|s| s.innovation).collect();
+    numbers.sort_unstable();
+    numbers
+}
+
+#[test]
+fn child_takes_disjoint_genes_from_fitter_parent() {
+    let p = parents();
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let (a, b) = (
+        CrossoverParent {
+            id: p.ids.0,
+            network: &p.a,
+            fitness: 2.0,
+        },
+        CrossoverParent {
+            id: p.ids.1,
+            network: &p.b,
+            fitness: 1.0,
+        },
+    );
+
+    let child = DomainNetwork::hydrate(&crossover(a, b, &mut rng));
+    assert!(child.neurons.contains_key(&p.only_a));
+    assert!(!child.neurons.contains_key(&p.only_b));
+    assert_eq!(innovations(&child), vec![0, 1, 2]);
+
+    // The order of the arguments does not matter, only fitness does.
+    let child = DomainNetwork::hydrate(&crossover(b, a, &mut rng));
+    assert_eq!(innovations(&child), vec![0, 1, 2]);
+
+    let fitter_b = CrossoverParent { fitness: 3.0, ..b };
+    let child = DomainNetwork::hydrate(&crossover(a, fitter_b, &mut rng));
+    assert!(child.neurons.contains_key(&p.only_b));
+    assert!(!child.neurons.contains_key(&p.only_a));
+    assert_eq!(innovations(&child), vec![0, 3]);
+
+    // Ties favour the first parent.
+    let tied = CrossoverParent { fitness: 2.0, ..b };
+    let child = DomainNetwork::hydrate(&crossover(tied, a, &mut rng));
+    assert_eq!(innovations(&child), vec![0, 3]);
+}
+
+#[test]
+fn matching_genes_come_from_either_parent() {
+    let p = parents();
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+    let (mut from_a, mut from_b) = (0, 0);
+    for _ in 0..200 {
+        let events = crossover(
+            CrossoverParent {
+                id: p.ids.0,
+                network: &p.a,
+                fitness: 1.0,
+            },
+            CrossoverParent {
+                id: p.ids.1,
+                network: &p.b,
+                fitness: 0.0,
+            },
+            &mut rng,
+        );
+        let child = DomainNetwork::hydrate(&events);
+        let shared = child
+            .synapses
+            .values()
+            .find(|s| s.from == p.input && s.to == p.output)
+            .unwrap();
+        let lineage = events
+            .iter()
+            .find_map(|e| match e {
+                Event::SynapseInherited(s) if s.synapse_id == shared.id => Some(s),
+                _ => None,
+            })
+            .unwrap();
+        assert!(lineage.matching);
+        if lineage.parent_id == p.ids.0 {
+            assert_eq!((shared.weight, shared.enabled), (1.0, true));
+            from_a += 1;
+        } else {
+            assert_eq!(lineage.parent_id, p.ids.1);
+            assert_eq!((shared.weight, shared.enabled), (-1.0, false));
+            from_b += 1;
+        }
+        let output = &child.neurons[&p.output];
+        assert_eq!(output.role, NeuronRole::Output);
+    }
+    assert!(from_a > 60 && from_b > 60, "{from_a} vs {from_b}");
+}
+
+#[test]
+fn every_gene_records_its_lineage() {
+    let p = parents();
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let events = crossover(
+        CrossoverParent {
+            id: p.ids.0,
+            network: &p.a,
+            fitness: 1.0,
+        },
+        CrossoverParent {
+            id: p.ids.1,
+            network: &p.b,
+            fitness: 0.0,
+        },
+        &mut rng,
+    );
+    let neurons: Vec<&NeuronInherited> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::NeuronInherited(n) => Some(n),
+            _ => None,
+        })
+        .collect();
+    let synapses: Vec<&SynapseInherited> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::SynapseInherited(s) => Some(s),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(neurons.len(), 3);
+    assert_eq!(synapses.len(), 3);
+    for lineage in &neurons {
+        let matching = lineage.neuron_id != p.only_a;
+        assert_eq!(lineage.matching, matching);
+        if !matching {
+            assert_eq!(lineage.parent_id, p.ids.0);
+        }
+    }
+    assert_eq!(synapses.iter().filter(|s| s.matching).count(), 1);
+    assert!(synapses
+        .iter()
+        .filter(|s| !s.matching)
+        .all(|s| s.parent_id == p.ids.0));
+}
+
+#[test]
+fn unnumbered_synapses_match_by_connection() {
+    let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    let genome = |weight| {
+        DomainNetwork::hydrate(&[
+            neuron(input, Activation::Identity, NeuronRole::Input),
+            neuron(output, Activation::Identity, NeuronRole::Output),
+            synapse(input, output, weight, None),
+        ])
+    };
+    let (a, b) = (genome(1.0), genome(2.0));
+    let events = crossover(
+        CrossoverParent {
+            id: Uuid::new_v4(),
+            network: &a,
+            fitness: 1.0,
+        },
+        CrossoverParent {
+            id: Uuid::new_v4(),
+            network: &b,
+            fitness: 0.0,
+        },
+        &mut ChaCha8Rng::seed_from_u64(4),
+    );
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::SynapseInherited(s) if s.matching)));
+}
+
+#[test]
+fn population_offspring_record_both_parents() {
+    let dir: PathBuf = std::env::temp_dir().join(format!("aei_crossover_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let streams = dir.clone();
+    let mut population = PopulationHandler::new(
+        FilePopulationEventStore::new(dir.join("population.log")),
+        move |id| FileEventStore::new(streams.join(format!("{id}.log"))),
+        ChaCha8Rng::seed_from_u64(5),
+    )
+    .unwrap();
+    let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    for weight in [0.1, 0.2, 0.3, 0.4] {
+        population
+            .add_member(&[
+                neuron(input, Activation::Identity, NeuronRole::Input),
+                neuron(output, Activation::Identity, NeuronRole::Output),
+                synapse(input, output, weight, Some(0)),
+            ])
+            .unwrap();
+    }
+    let cmd = EvolvePopulationCommand {
+        selection: Selection::Roulette,
+        elitism: 1,
+        crossover_rate: 1.0,
+        mutations: vec![Mutation::MutateSynapseWeight(
+            MutateRandomSynapseWeightCommand { std_dev: 0.1 },
+        )],
+        speciation: None,
+    };
+    population
+        .evolve(&cmd, |net| net.synapses.values().map(|s| s.weight).sum())
+        .unwrap();
+
+    let events = FilePopulationEventStore::new(dir.join("population.log"))
+        .load()
+        .unwrap();
+    let children: Vec<Uuid> = events
+        .iter()
+        .filter_map(|e| match e {
+            PopulationEvent::MemberAdded(added) if added.generation == 1 => {
+                assert_eq!(added.parents.len(), 2);
+                Some(added.member_id)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(children.len(), 3);
+    for child in children {
+        let stream = FileEventStore::new(dir.join(format!("{child}.log")))
+            .load()
+            .unwrap();
+        assert!(stream
+            .iter()
+            .any(|e| matches!(e, Event::SynapseInherited(_))));
+        assert_eq!(population.network(child).unwrap().synapses.len(), 1);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,247 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use aei_framework::core::scheduler::{InMemoryScheduler, Scheduler};
+use aei_framework::{
+    Activation, CuriosityDecay, CuriosityScope, CuriosityScoreUpdated, DecayCuriosityScoresCommand,
+    DecayCuriosityScoresError, Event, FileEventStore, NeuronAdded, NeuronRole, Rarity,
+    RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
+};
+use uuid::Uuid;
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_curiosity_decay_{}.log", Uuid::new_v4()));
+    path
+}
+
+/// Writes `count` neurons, each with a curiosity score of `1.0`.
+fn scored_neurons(path: &Path, count: usize) -> Vec<Uuid> {
+    let mut store = FileEventStore::new(path.to_path_buf());
+    let mut ids: Vec<Uuid> = (0..count).map(|_| Uuid::new_v4()).collect();
+    ids.sort();
+    for id in &ids {
+        store
+            .append(&Event::NeuronAdded(NeuronAdded {
+                neuron_id: *id,
+                activation: Activation::Identity,
+                role: NeuronRole::Hidden,
+                bias: 0.0,
+            }))
+            .unwrap();
+        store
+            .append(&Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
+                target_id: *id,
+                old_score: 0.0,
+                new_score: 1.0,
+                strategy: "rarity".into(),
+            }))
+            .unwrap();
+    }
+    ids
+}
+
+fn decay(batch_size: usize) -> DecayCuriosityScoresCommand {
+    DecayCuriosityScoresCommand {
+        elapsed: HOUR,
+        decay: CuriosityDecay {
+            half_life: HOUR,
+            batch_size,
+        },
+    }
+}
+
+fn decayed(events: &[Event]) -> Vec<(Uuid, f64)> {
+    events
+        .iter()
+        .map(|e| match e {
+            Event::CuriosityScoreUpdated(e) => {
+                assert_eq!(e.strategy, "decay");
+                (e.target_id, e.new_score)
+            }
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[test]
+fn scores_halve_every_half_life() {
+    let path = temp_path();
+    let ids = scored_neurons(&path, 3);
+    let mut handler =
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+
+    let first = decayed(&handler.decay(decay(10)).unwrap());
+    assert_eq!(first, ids.iter().map(|id| (*id, 0.5)).collect::<Vec<_>>());
+    handler.decay(decay(10)).unwrap();
+    for id in &ids {
+        assert_eq!(handler.network.neurons[id].curiosity_score, 0.25);
+    }
+
+    // Replaying the log gives the decayed scores back.
+    let resumed = RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+    for id in &ids {
+        assert_eq!(resumed.network.neurons[id].curiosity_score, 0.25);
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn batches_catch_up_on_the_time_they_missed() {
+    let path = temp_path();
+    let ids = scored_neurons(&path, 5);
+    let mut handler =
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+
+    let runs: Vec<Vec<(Uuid, f64)>> = (0..4)
+        .map(|_| decayed(&handler.decay(decay(2)).unwrap()))
+        .collect();
+    // Runs wrap around the targets, and each target is lowered by every
+    // hour elapsed since it was last decayed.
+    assert_eq!(runs[0], [(ids[0], 0.5), (ids[1], 0.5)]);
+    assert_eq!(runs[1], [(ids[2], 0.25), (ids[3], 0.25)]);
+    assert_eq!(runs[2], [(ids[4], 0.125), (ids[0], 0.125)]);
+    assert_eq!(runs[3], [(ids[1], 0.0625), (ids[2], 0.0625)]);
+
+    // A recalculated score restarts its decay from the new value.
+    let recalculated = handler
+        .handle(RecalculateCuriosityScoreCommand {
+            target_ids: vec![ids[4]],
+            scope: CuriosityScope::Neuron,
+            strategy: Rarity,
+        })
+        .unwrap();
+    assert_eq!(recalculated.len(), 1);
+    let fresh = handler.network.neurons[&ids[4]].curiosity_score;
+    let run = decayed(&handler.decay(decay(3)).unwrap());
+    assert_eq!(
+        run,
+        [(ids[3], 0.03125), (ids[4], fresh * 0.5), (ids[0], 0.03125)]
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn scores_recalculated_by_another_writer_restart_their_decay() {
+    let path = temp_path();
+    let ids = scored_neurons(&path, 2);
+    let mut decayer =
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let mut scorer =
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+
+    // The runs alternate between the two targets, leaving the first one
+    // last decayed an hour before the second.
+    let runs: Vec<Vec<(Uuid, f64)>> = (0..4)
+        .map(|_| decayed(&decayer.decay(decay(1)).unwrap()))
+        .collect();
+    assert_eq!(runs[2], [(ids[0], 0.125)]);
+    assert_eq!(runs[3], [(ids[1], 0.0625)]);
+    let recalculated = scorer
+        .handle(RecalculateCuriosityScoreCommand {
+            target_ids: vec![ids[0]],
+            scope: CuriosityScope::Neuron,
+            strategy: Rarity,
+        })
+        .unwrap();
+    assert_eq!(recalculated.len(), 1);
+    let fresh = scorer.network.neurons[&ids[0]].curiosity_score;
+
+    // The new score dates from the last run, not from the last decay of the
+    // old one.
+    assert_eq!(
+        decayed(&decayer.decay(decay(1)).unwrap()),
+        [(ids[0], fresh * 0.5)]
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn scheduler_drives_the_decay() {
+    let path = temp_path();
+    let ids = scored_neurons(&path, 2);
+    let handler = Arc::new(Mutex::new(
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap(),
+    ));
+    let mut scheduler = InMemoryScheduler::new();
+    let interval = Duration::from_millis(1);
+    let decay = CuriosityDecay {
+        half_life: interval,
+        batch_size: 10,
+    };
+    RecalculateCuriosityScoreHandler::schedule_decay(&handler, &mut scheduler, interval, decay)
+        .unwrap();
+
+    std::thread::sleep(Duration::from_millis(5));
+    scheduler.tick();
+    let handler = handler.lock().unwrap();
+    for id in &ids {
+        assert_eq!(handler.network.neurons[id].curiosity_score, 0.5);
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn invalid_decays_are_rejected() {
+    let path = temp_path();
+    scored_neurons(&path, 1);
+    let handler = Arc::new(Mutex::new(
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap(),
+    ));
+    for decay in [
+        CuriosityDecay {
+            half_life: Duration::ZERO,
+            batch_size: 1,
+        },
+        CuriosityDecay {
+            half_life: HOUR,
+            batch_size: 0,
+        },
+    ] {
+        let cmd = DecayCuriosityScoresCommand {
+            elapsed: HOUR,
+            decay,
+        };
+        assert!(matches!(
+            handler.lock().unwrap().decay(cmd),
+            Err(DecayCuriosityScoresError::InvalidDecay)
+        ));
+        let mut scheduler = InMemoryScheduler::new();
+        assert_eq!(
+            RecalculateCuriosityScoreHandler::schedule_decay(&handler, &mut scheduler, HOUR, decay),
+            Err(DecayCuriosityScoresError::InvalidDecay)
+        );
+    }
+    assert_eq!(FileEventStore::new(path.clone()).load().unwrap().len(), 2);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn decay_does_not_count_as_an_occurrence() {
+    let path = temp_path();
+    let ids = scored_neurons(&path, 2);
+    let mut handler =
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let rarity = |handler: &mut RecalculateCuriosityScoreHandler<FileEventStore>| {
+        handler
+            .handle(RecalculateCuriosityScoreCommand {
+                target_ids: ids.clone(),
+                scope: CuriosityScope::Neuron,
+                strategy: Rarity,
+            })
+            .unwrap();
+        ids.iter()
+            .map(|id| handler.network.neurons[id].curiosity_score)
+            .collect::<Vec<_>>()
+    };
+    let before = rarity(&mut handler);
+    for _ in 0..3 {
+        handler.decay(decay(10)).unwrap();
+    }
+    assert!(ids.iter().all(|id| handler.index.occurrences(*id) == 1));
+    assert_eq!(rarity(&mut handler), before);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,192 @@
+use std::io;
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, AppendError, CuriosityIndex, CuriosityScope, Event, EventStore, FileEventStore,
+    NeuronAdded, NeuronRole, RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
+    RecencyWeightedRarity, SynapseSplit, SynapseWeightSet, WeightVariance,
+};
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_curiosity_index_{}.log", Uuid::new_v4()));
+    path
+}
+
+/// File store counting how often the whole log is read, tails excluded.
+struct CountingStore {
+    inner: FileEventStore,
+    loads: usize,
+}
+
+impl EventStore for CountingStore {
+    type Error = io::Error;
+
+    fn append(&mut self, event: &Event) -> Result<(), Self::Error> {
+        self.inner.append(event)
+    }
+
+    fn load(&mut self) -> Result<Vec<Event>, Self::Error> {
+        self.loads += 1;
+        self.inner.load()
+    }
+
+    fn load_from(&mut self, version: u64) -> Result<Vec<Event>, Self::Error> {
+        self.inner.load_from(version)
+    }
+
+    fn version(&mut self) -> Result<u64, Self::Error> {
+        self.inner.version()
+    }
+
+    fn append_expected(
+        &mut self,
+        events: &[Event],
+        expected_version: u64,
+    ) -> Result<u64, AppendError<Self::Error>> {
+        self.inner.append_expected(events, expected_version)
+    }
+}
+
+fn neuron(neuron_id: Uuid) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id,
+        activation: Activation::Identity,
+        role: NeuronRole::Hidden,
+        bias: 0.0,
+    })
+}
+
+#[test]
+fn index_tracks_positions_and_parameters() {
+    let (a, b, loop_id, split) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+    let (hidden, incoming, outgoing) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let events = vec![
+        neuron(a),
+        neuron(b),
+        Event::SynapseCreated {
+            id: loop_id,
+            from: a,
+            to: a,
+            weight: 1.0,
+            innovation: None,
+        },
+        Event::SynapseCreated {
+            id: split,
+            from: a,
+            to: b,
+            weight: 3.0,
+            innovation: None,
+        },
+        Event::SynapseSplit(SynapseSplit {
+            synapse_id: split,
+            from: a,
+            to: b,
+            weight: 3.0,
+            neuron_id: hidden,
+            activation: Activation::Identity,
+            incoming_synapse_id: incoming,
+            outgoing_synapse_id: outgoing,
+            incoming_innovation: None,
+            outgoing_innovation: None,
+        }),
+        Event::SynapseWeightSet(SynapseWeightSet {
+            synapse_id: outgoing,
+            old_weight: 3.0,
+            new_weight: 1.0,
+        }),
+    ];
+
+    let mut index = CuriosityIndex::default();
+    for event in &events {
+        index.apply(event);
+    }
+    assert_eq!(index, CuriosityIndex::from_events(&events));
+    assert_eq!(index.len(), 6);
+
+    // A self-loop counts once for its neuron.
+    assert_eq!(index.positions(a), [0, 2, 3]);
+    assert_eq!(index.positions(b), [1, 3]);
+    assert_eq!(index.positions(split), [3, 4]);
+    assert_eq!(index.positions(hidden), [4]);
+    assert_eq!(index.occurrences(outgoing), 2);
+    assert_eq!(index.occurrences(Uuid::new_v4()), 0);
+
+    assert_eq!(index.parameter_variance(incoming), Some(0.0));
+    assert_eq!(index.parameter_variance(outgoing), Some(1.0));
+    assert_eq!(index.parameter_variance(hidden), Some(0.0));
+    assert_eq!(index.parameter_variance(Uuid::new_v4()), None);
+}
+
+#[test]
+fn recalculation_reads_the_log_once() {
+    let path = temp_path();
+    let mut inner = FileEventStore::new(path.clone());
+    let ids: Vec<Uuid> = (0..20).map(|_| Uuid::new_v4()).collect();
+    for id in &ids {
+        inner.append(&neuron(*id)).unwrap();
+    }
+    let store = CountingStore { inner, loads: 0 };
+    let mut handler = RecalculateCuriosityScoreHandler::new(store).unwrap();
+
+    // Every pass rescores each neuron, as the log grows between passes.
+    for _ in 0..5 {
+        handler
+            .handle(RecalculateCuriosityScoreCommand {
+                target_ids: Vec::new(),
+                scope: CuriosityScope::All,
+                strategy: RecencyWeightedRarity { half_life: 10.0 },
+            })
+            .unwrap();
+    }
+    handler
+        .handle(RecalculateCuriosityScoreCommand {
+            target_ids: ids[..3].to_vec(),
+            scope: CuriosityScope::Neuron,
+            strategy: WeightVariance,
+        })
+        .unwrap();
+    assert_eq!(handler.store.loads, 1);
+
+    // The incremental index agrees with one rebuilt from the log.
+    let fresh = RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+    assert_eq!(fresh.index, handler.index);
+    assert_eq!(fresh.index.len(), 20 + 5 * 20 + 3);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn recalculation_sees_events_from_other_writers() {
+    let path = temp_path();
+    let mut other = FileEventStore::new(path.clone());
+    let first = Uuid::new_v4();
+    other.append(&neuron(first)).unwrap();
+    let mut handler =
+        RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+
+    // A neuron added by another writer is scored by the next recalculation.
+    let second = Uuid::new_v4();
+    other.append(&neuron(second)).unwrap();
+    let emitted = handler
+        .handle(RecalculateCuriosityScoreCommand {
+            target_ids: Vec::new(),
+            scope: CuriosityScope::All,
+            strategy: RecencyWeightedRarity { half_life: 10.0 },
+        })
+        .unwrap();
+    assert!(handler.network.neurons.contains_key(&second));
+    assert!(emitted.iter().any(|e| matches!(
+        e,
+        Event::CuriosityScoreUpdated(e) if e.target_id == second
+    )));
+
+    let fresh = RecalculateCuriosityScoreHandler::new(FileEventStore::new(path.clone())).unwrap();
+    assert_eq!(fresh.index, handler.index);
+    std::fs::remove_file(path).unwrap();
+}
@@ -10,6 +10,7 @@ fn curiosity_projection_seeds_and_updates_scores() {
         target_id: id,
         old_score: 0.0,
         new_score: 0.5,
+        strategy: "rarity".into(),
     })];
 
     let mut projection = CuriosityScoreProjection::from_events(&events);
@@ -19,6 +20,7 @@ fn curiosity_projection_seeds_and_updates_scores() {
         target_id: id,
         old_score: 0.5,
         new_score: 0.8,
+        strategy: "rarity".into(),
     });
     projection.apply(&update_event);
     assert_eq!(projection.get(id), Some(0.8));
@@ -2,8 +2,8 @@ use std::path::PathBuf;
 
 use aei_framework::{
     infrastructure::projection::CuriosityScoreProjection, Activation, CuriosityScope,
-    CuriosityScoreUpdated, DomainNetwork, Event, FileEventStore, RandomNeuronAdded,
-    RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
+    CuriosityScoreUpdated, DomainNetwork, Event, FileEventStore, NeuronRole, RandomNeuronAdded,
+    Rarity, RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
 };
 use uuid::Uuid;
 
@@ -21,6 +21,8 @@ fn curiosity_score_event_and_projection() {
     let event = Event::RandomNeuronAdded(RandomNeuronAdded {
         neuron_id,
         activation: Activation::Identity,
+        role: NeuronRole::Hidden,
+        bias: 0.0,
     });
     store.append(&event).unwrap();
 
@@ -28,6 +30,7 @@ fn curiosity_score_event_and_projection() {
     let cmd = RecalculateCuriosityScoreCommand {
         target_ids: vec![neuron_id],
         scope: CuriosityScope::Neuron,
+        strategy: Rarity,
     };
     let events = handler.handle(cmd).unwrap();
     assert!(matches!(
@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, CuriosityIndex, CuriosityScope, CuriosityScoreUpdated, CuriosityStrategy,
+    DomainNetwork, Event, FileEventStore, NeuronAdded, NeuronBiasSet, NeuronRole, PredictionError,
+    Rarity, RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
+    RecencyWeightedRarity, SynapseWeightSet, WeightVariance,
+};
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_curiosity_strategies_{}.log", Uuid::new_v4()));
+    path
+}
+
+fn neuron(neuron_id: Uuid) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id,
+        activation: Activation::Identity,
+        role: NeuronRole::Hidden,
+        bias: 0.0,
+    })
+}
+
+fn synapse(id: Uuid, from: Uuid, to: Uuid, weight: f64) -> Event {
+    Event::SynapseCreated {
+        id,
+        from,
+        to,
+        weight,
+        innovation: None,
+    }
+}
+
+fn set_weight(synapse_id: Uuid, old_weight: f64, new_weight: f64) -> Event {
+    Event::SynapseWeightSet(SynapseWeightSet {
+        synapse_id,
+        old_weight,
+        new_weight,
+    })
+}
+
+fn score(strategy: &dyn CuriosityStrategy, target: Uuid, history: &[Event]) -> f64 {
+    let index = CuriosityIndex::from_events(history);
+    strategy.score(target, &index, &DomainNetwork::hydrate(history))
+}
+
+#[test]
+fn recency_weighting_forgets_old_occurrences() {
+    let (old, busy, recent) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let mut history = vec![neuron(old), neuron(busy)];
+    history.extend((0..9).map(|i| {
+        Event::NeuronBiasSet(NeuronBiasSet {
+            neuron_id: busy,
+            old_bias: i as f64,
+            new_bias: i as f64 + 1.0,
+        })
+    }));
+    history.push(neuron(recent));
+
+    // Both neurons occur once, so plain rarity cannot tell them apart.
+    assert_eq!(score(&Rarity, old, &history), 0.5);
+    assert_eq!(score(&Rarity, recent, &history), 0.5);
+
+    // Eleven events later, the first occurrence only counts a quarter.
+    let strategy = RecencyWeightedRarity { half_life: 5.5 };
+    assert!((score(&strategy, old, &history) - 0.8).abs() < 1e-12);
+    assert!((score(&strategy, recent, &history) - 0.5).abs() < 1e-12);
+    assert!(score(&strategy, busy, &history) < score(&strategy, old, &history));
+}
+
+#[test]
+fn weight_variance_follows_parameter_trajectories() {
+    let (from, to) = (Uuid::new_v4(), Uuid::new_v4());
+    let (moving, settled) = (Uuid::new_v4(), Uuid::new_v4());
+    let history = vec![
+        neuron(from),
+        neuron(to),
+        synapse(moving, from, to, 0.0),
+        synapse(settled, from, to, 1.0),
+        set_weight(moving, 0.0, 2.0),
+        set_weight(settled, 1.0, 1.0),
+        Event::NeuronBiasSet(NeuronBiasSet {
+            neuron_id: to,
+            old_bias: 0.0,
+            new_bias: 6.0,
+        }),
+    ];
+
+    // Weights 0 and 2 have variance 1; biases 0 and 6 have variance 9.
+    assert!((score(&WeightVariance, moving, &history) - 0.5).abs() < 1e-12);
+    assert_eq!(score(&WeightVariance, settled, &history), 0.0);
+    assert!((score(&WeightVariance, to, &history) - 0.9).abs() < 1e-12);
+    assert_eq!(score(&WeightVariance, from, &history), 0.0);
+    assert_eq!(score(&WeightVariance, Uuid::new_v4(), &history), 0.0);
+}
+
+#[test]
+fn prediction_error_reads_the_supplied_signal() {
+    let (surprising, expected) = (Uuid::new_v4(), Uuid::new_v4());
+    let strategy = PredictionError::new(|id| if id == surprising { -3.0 } else { 0.0 });
+    assert_eq!(score(&strategy, surprising, &[]), 0.75);
+    assert_eq!(score(&strategy, expected, &[]), 0.0);
+}
+
+#[test]
+fn scores_record_the_strategy_that_produced_them() {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path.clone());
+    let (a, b, s) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    for event in [
+        neuron(a),
+        neuron(b),
+        synapse(s, a, b, 0.0),
+        set_weight(s, 0.0, 2.0),
+    ] {
+        store.append(&event).unwrap();
+    }
+    let mut handler = RecalculateCuriosityScoreHandler::new(store).unwrap();
+
+    let strategies: Vec<String> = handler
+        .handle(RecalculateCuriosityScoreCommand {
+            target_ids: vec![s],
+            scope: CuriosityScope::Synapse,
+            strategy: WeightVariance,
+        })
+        .unwrap()
+        .iter()
+        .chain(
+            &handler
+                .handle(RecalculateCuriosityScoreCommand {
+                    target_ids: Vec::new(),
+                    scope: CuriosityScope::All,
+                    strategy: PredictionError::new(|id| if id == a { 1.0 } else { 0.0 }),
+                })
+                .unwrap(),
+        )
+        .map(|e| match e {
+            Event::CuriosityScoreUpdated(e) => e.strategy.clone(),
+            _ => unreachable!(),
+        })
+        .collect();
+    // The prediction error only changes the score of `a` and of the synapse.
+    assert_eq!(
+        strategies,
+        ["weight_variance", "prediction_error", "prediction_error"]
+    );
+    assert_eq!(handler.network.neurons[&a].curiosity_score, 0.5);
+    assert_eq!(handler.network.synapses[&s].curiosity_score, 0.0);
+
+    let events = handler.store.load().unwrap();
+    match events.last() {
+        Some(Event::CuriosityScoreUpdated(e)) => assert_eq!(e.strategy, "prediction_error"),
+        other => panic!("unexpected {other:?}"),
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn legacy_scores_were_produced_by_rarity() {
+    let id = Uuid::new_v4();
+    let json = format!(
+        r#"{{"CuriosityScoreUpdated":{{"target_id":"{id}","old_score":0.0,"new_score":0.5}}}}"#
+    );
+    let event: Event = serde_json::from_str(&json).unwrap();
+    match event {
+        Event::CuriosityScoreUpdated(CuriosityScoreUpdated { strategy, .. }) => {
+            assert_eq!(strategy, Rarity.name())
+        }
+        _ => unreachable!(),
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use aei_framework::{
+    Activation, CuriosityScoreUpdated, Event, FileEventStore, MutateNeuronActivationError,
+    MutateRandomNeuronActivationCommand, MutateRandomNeuronActivationHandler,
+    MutateRandomSynapseWeightCommand, MutateRandomSynapseWeightHandler, NeuronAdded, NeuronRole,
+    RemoveRandomSynapseCommand, RemoveRandomSynapseHandler, TargetSelection, TargetSelectionError,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_curiosity_targets_{}.log", Uuid::new_v4()));
+    path
+}
+
+fn curiosity(target_id: Uuid, new_score: f64) -> Event {
+    Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
+        target_id,
+        old_score: 0.0,
+        new_score,
+        strategy: "rarity".into(),
+    })
+}
+
+/// Writes three hidden neurons chained by two synapses, scoring the element
+/// at `curious` (0..3 for neurons, 3..5 for synapses) with `score`.
+fn network(path: &Path, curious: usize, score: f64) -> Vec<Uuid> {
+    let mut store = FileEventStore::new(path.to_path_buf());
+    let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+    for id in &ids[..3] {
+        store
+            .append(&Event::NeuronAdded(NeuronAdded {
+                neuron_id: *id,
+                activation: Activation::Identity,
+                role: NeuronRole::Hidden,
+                bias: 0.0,
+            }))
+            .unwrap();
+    }
+    for (i, id) in ids[3..].iter().enumerate() {
+        store
+            .append(&Event::SynapseCreated {
+                id: *id,
+                from: ids[i],
+                to: ids[i + 1],
+                weight: 0.5,
+                innovation: None,
+            })
+            .unwrap();
+    }
+    store.append(&curiosity(ids[curious], score)).unwrap();
+    ids
+}
+
+#[test]
+fn softmax_follows_the_temperature() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let (high, low) = (Uuid::new_v4(), Uuid::new_v4());
+    let candidates = [(high, 1.0), (low, 0.0)];
+    let share = |selection: TargetSelection, rng: &mut ChaCha8Rng| {
+        let hits = (0..4000)
+            .filter(|_| selection.choose(&candidates, rng) == Some(high))
+            .count();
+        hits as f64 / 4000.0
+    };
+
+    // exp(1) / (exp(1) + 1) ≈ 0.731
+    let warm = share(TargetSelection::Curiosity { temperature: 1.0 }, &mut rng);
+    assert!((warm - 0.731).abs() < 0.03, "{warm}");
+    let cold = share(TargetSelection::Curiosity { temperature: 0.1 }, &mut rng);
+    assert!(cold > 0.99, "{cold}");
+    let inverted = share(TargetSelection::Curiosity { temperature: -0.1 }, &mut rng);
+    assert!(inverted < 0.01, "{inverted}");
+    let uniform = share(TargetSelection::Uniform, &mut rng);
+    assert!((uniform - 0.5).abs() < 0.03, "{uniform}");
+    assert_eq!(TargetSelection::default().choose(&[], &mut rng), None);
+}
+
+#[test]
+fn weight_mutations_concentrate_on_curious_synapses() {
+    let path = temp_path();
+    let ids = network(&path, 4, 2.0);
+    let store = FileEventStore::new(path.clone());
+    let mut handler =
+        MutateRandomSynapseWeightHandler::new(store, ChaCha8Rng::seed_from_u64(2)).unwrap();
+    handler
+        .base
+        .select_targets(TargetSelection::Curiosity { temperature: 0.25 })
+        .unwrap();
+    let cmd = MutateRandomSynapseWeightCommand { std_dev: 0.1 };
+    let hits = (0..200)
+        .filter(|_| handler.handle(cmd).unwrap() == ids[4])
+        .count();
+    assert!(hits > 190, "{hits}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn negative_temperature_spares_curious_synapses() {
+    for seed in 0..20 {
+        let path = temp_path();
+        let ids = network(&path, 3, 3.0);
+        let store = FileEventStore::new(path.clone());
+        let mut handler =
+            RemoveRandomSynapseHandler::new(store, ChaCha8Rng::seed_from_u64(seed)).unwrap();
+        handler
+            .base
+            .select_targets(TargetSelection::Curiosity { temperature: -0.2 })
+            .unwrap();
+        assert_eq!(handler.handle(RemoveRandomSynapseCommand).unwrap(), ids[4]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn activation_mutations_follow_neuron_scores() {
+    let path = temp_path();
+    let ids = network(&path, 1, 2.0);
+    let store = FileEventStore::new(path.clone());
+    let mut handler =
+        MutateRandomNeuronActivationHandler::new(store, ChaCha8Rng::seed_from_u64(3)).unwrap();
+    handler
+        .base
+        .select_targets(TargetSelection::Curiosity { temperature: 0.25 })
+        .unwrap();
+    let mut hits = 0;
+    for _ in 0..100 {
+        match handler.handle(MutateRandomNeuronActivationCommand::default()) {
+            Ok(id) if id == ids[1] => hits += 1,
+            Ok(_) => {}
+            Err(e) => assert_eq!(e, MutateNeuronActivationError::NoAlternativeActivation),
+        }
+    }
+    assert!(hits > 90, "{hits}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn invalid_temperatures_are_rejected() {
+    let path = temp_path();
+    let store = FileEventStore::new(path.clone());
+    let mut handler =
+        MutateRandomSynapseWeightHandler::new(store, ChaCha8Rng::seed_from_u64(4)).unwrap();
+    let valid = TargetSelection::Curiosity { temperature: 1.0 };
+    handler.base.select_targets(valid).unwrap();
+    for temperature in [0.0, f64::NAN, f64::INFINITY] {
+        assert_eq!(
+            handler
+                .base
+                .select_targets(TargetSelection::Curiosity { temperature }),
+            Err(TargetSelectionError::InvalidTemperature)
+        );
+    }
+    assert_eq!(handler.base.targets(), valid);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn non_finite_scores_count_as_the_lowest() {
+    let mut rng = ChaCha8Rng::seed_from_u64(4);
+    let (curious, dull, diverged) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let selection = TargetSelection::Curiosity { temperature: 0.1 };
+    let candidates = [(diverged, f64::NAN), (curious, 1.0), (dull, 0.0)];
+    let hits = (0..200)
+        .filter(|_| selection.choose(&candidates, &mut rng) == Some(curious))
+        .count();
+    assert!(hits > 190, "{hits}");
+
+    // Without any finite score the choice is uniform.
+    let candidates = [(diverged, f64::NAN), (dull, f64::INFINITY)];
+    assert!(selection.choose(&candidates, &mut rng).is_some());
+}
@@ -1,4 +1,6 @@
-use aei_framework::{Activation, DomainNetwork, Event, RandomNeuronAdded, RandomNeuronRemoved};
+use aei_framework::{
+    Activation, DomainNetwork, Event, NeuronRole, RandomNeuronAdded, RandomNeuronRemoved,
+};
 use uuid::Uuid;
 
 // Ensure domain rules such as synapse cleanup are respected.
@@ -12,16 +14,21 @@ fn removing_neuron_cleans_up_synapses() {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::SynapseCreated {
             id: s,
             from: n1,
             to: n2,
             weight: 1.0,
+            innovation: None,
         },
         Event::RandomNeuronRemoved(RandomNeuronRemoved { neuron_id: n1 }),
     ];
@@ -40,12 +47,15 @@ fn synapse_with_unknown_neuron_is_ignored() {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::SynapseCreated {
             id: Uuid::new_v4(),
             from: n1,
             to: Uuid::new_v4(),
             weight: 1.0,
+            innovation: None,
         },
     ];
 
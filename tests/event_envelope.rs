@@ -0,0 +1,306 @@
+use std::path::PathBuf;
+
+use std::time::Duration;
+
+use aei_framework::infrastructure::EVENT_SCHEMA_VERSION;
+use aei_framework::{
+    Activation, AddMemoryEntryCommand, AddMemoryEntryHandler, AddRandomNeuronCommand,
+    AddRandomNeuronHandler, AddRandomSynapseCommand, AddRandomSynapseHandler, Command,
+    CommandHandler, CuriosityDecay, CuriosityScope, DecayCuriosityScoresCommand, DomainNetwork,
+    Event, EvolvePopulationCommand, FileEventStore, FileMemoryEventStore, FilePopulationEventStore,
+    Loss, MutateRandomSynapseWeightCommand, Mutation, NeuronAdded, NeuronRole, OptimizerConfig,
+    PopulationHandler, Rarity, RecalculateCuriosityScoreCommand, RecalculateCuriosityScoreHandler,
+    Selection, SetNeuronBiasCommand, SetNeuronBiasHandler, SetSynapseWeightCommand,
+    SetSynapseWeightHandler, TrainNetworkCommand, TrainNetworkHandler, TrainingSample,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_event_envelope_{}.log", Uuid::new_v4()));
+    path
+}
+
+#[test]
+fn envelopes_group_events_by_command() {
+    let path = temp_path();
+    let store = FileEventStore::new(path.clone());
+    let mut neurons = AddRandomNeuronHandler::new(store, ChaCha8Rng::seed_from_u64(1)).unwrap();
+    assert_eq!(neurons.base.correlation_id(), None);
+    // The second neuron is connected to the first: three events in all.
+    let mut commands = Vec::new();
+    for _ in 0..2 {
+        neurons.handle(AddRandomNeuronCommand::default()).unwrap();
+        commands.push(neurons.base.correlation_id().unwrap());
+    }
+
+    let mut synapses =
+        AddRandomSynapseHandler::new(neurons.base.store, ChaCha8Rng::seed_from_u64(2)).unwrap();
+    synapses.base.set_causation(Some(commands[1]));
+    synapses.handle(AddRandomSynapseCommand).unwrap();
+    commands.push(synapses.base.correlation_id().unwrap());
+
+    let envelopes = synapses.base.store.load_envelopes().unwrap();
+    assert_eq!(envelopes.len(), 4);
+    for (i, envelope) in envelopes.iter().enumerate() {
+        assert_eq!(envelope.sequence, i as u64);
+        assert_eq!(envelope.schema_version, EVENT_SCHEMA_VERSION);
+        assert!(!envelope.is_bare());
+    }
+    assert!(envelopes
+        .windows(2)
+        .all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    let produced_by = |id| {
+        envelopes
+            .iter()
+            .filter(|e| e.correlation_id == Some(id))
+            .count()
+    };
+    let counts: Vec<usize> = commands.iter().map(|id| produced_by(*id)).collect();
+    assert_eq!(counts, [1, 2, 1]);
+    assert!(envelopes[..3].iter().all(|e| e.causation_id.is_none()));
+    assert_eq!(envelopes[3].causation_id, Some(commands[1]));
+    assert!(matches!(envelopes[3].event, Event::RandomSynapseAdded(_)));
+
+    // Handlers replay enveloped logs like bare ones.
+    let network = DomainNetwork::hydrate(&synapses.base.store.load().unwrap());
+    assert!(network
+        .neurons
+        .keys()
+        .eq(synapses.base.network.neurons.keys()));
+    assert_eq!(network.synapses.len(), 2);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn every_command_gets_its_own_correlation() {
+    let path = temp_path();
+    let mut handler = CommandHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let mut commands = Vec::new();
+    for id in [Uuid::new_v4(), Uuid::new_v4()] {
+        handler.handle(Command::RemoveNeuron { id }).unwrap();
+        commands.push(handler.correlation_id().unwrap());
+    }
+    let correlations: Vec<_> = handler
+        .store
+        .load_envelopes()
+        .unwrap()
+        .iter()
+        .map(|e| e.correlation_id.unwrap())
+        .collect();
+    assert_eq!(correlations, commands);
+    assert_ne!(commands[0], commands[1]);
+
+    let memory_path = temp_path();
+    let store = FileMemoryEventStore::new(memory_path.clone());
+    let mut memory = AddMemoryEntryHandler::new(store, 1).unwrap();
+    memory.base.set_causation(Some(commands[1]));
+    for score in [0.2, 0.8] {
+        memory
+            .handle(AddMemoryEntryCommand {
+                event_type: "step".into(),
+                payload: json!({}),
+                score,
+            })
+            .unwrap();
+    }
+    // The second entry and the pruning it causes share a correlation.
+    let envelopes = memory.base.store.load_envelopes().unwrap();
+    assert_eq!(envelopes.len(), 3);
+    assert_eq!(envelopes[1].correlation_id, memory.base.correlation_id());
+    assert_eq!(envelopes[2].correlation_id, memory.base.correlation_id());
+    assert_ne!(envelopes[0].correlation_id, envelopes[1].correlation_id);
+    assert!(envelopes
+        .iter()
+        .all(|e| e.causation_id == Some(commands[1])));
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(memory_path).unwrap();
+}
+
+/// Input neuron `.0` connected to output neuron `.1` by synapse `.2`.
+fn genome() -> (Uuid, Uuid, Uuid, Vec<Event>) {
+    let (input, output, synapse) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let neuron = |neuron_id, role| {
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id,
+            activation: Activation::Identity,
+            role,
+            bias: 0.0,
+        })
+    };
+    let events = vec![
+        neuron(input, NeuronRole::Input),
+        neuron(output, NeuronRole::Output),
+        Event::SynapseCreated {
+            id: synapse,
+            from: input,
+            to: output,
+            weight: 0.5,
+            innovation: None,
+        },
+    ];
+    (input, output, synapse, events)
+}
+
+/// Checks that the events of `store` past the first `from` were all appended
+/// by the command of `correlation` and returns the length of the log.
+fn appended_by(store: &mut FileEventStore, from: usize, correlation: Option<Uuid>) -> usize {
+    let envelopes = store.load_envelopes().unwrap();
+    assert!(correlation.is_some());
+    assert!(envelopes.len() > from);
+    assert!(envelopes[from..]
+        .iter()
+        .all(|e| e.correlation_id == correlation));
+    envelopes.len()
+}
+
+#[test]
+fn handlers_without_a_base_correlate_their_commands() {
+    let path = temp_path();
+    let (input, output, synapse, genome) = genome();
+    let mut store = FileEventStore::new(path.clone());
+    for event in &genome {
+        store.append(event).unwrap();
+    }
+
+    let mut weights = SetSynapseWeightHandler::new(store).unwrap();
+    weights
+        .handle(SetSynapseWeightCommand {
+            synapse_id: synapse,
+            new_weight: 1.0,
+        })
+        .unwrap();
+    let weighted = weights.correlation_id();
+    let len = appended_by(&mut weights.store, 3, weighted);
+
+    let mut biases = SetNeuronBiasHandler::new(weights.store).unwrap();
+    biases
+        .handle(SetNeuronBiasCommand {
+            neuron_id: output,
+            new_bias: 0.1,
+        })
+        .unwrap();
+    let biased = biases.correlation_id();
+    assert_ne!(biased, weighted);
+    let len = appended_by(&mut biases.store, len, biased);
+
+    // Every epoch of a training run belongs to the same command.
+    let mut training = TrainNetworkHandler::new(biases.store).unwrap();
+    training
+        .handle(TrainNetworkCommand {
+            samples: vec![TrainingSample {
+                inputs: vec![(input, 1.0)],
+                targets: vec![(output, 0.0)],
+            }],
+            epochs: 2,
+            loss: Loss::MeanSquaredError,
+            optimizer: OptimizerConfig::sgd(0.1),
+        })
+        .unwrap();
+    let trained = training.correlation_id();
+    let len = appended_by(&mut training.store, len, trained);
+
+    let mut curiosity = RecalculateCuriosityScoreHandler::new(training.store).unwrap();
+    curiosity
+        .handle(RecalculateCuriosityScoreCommand {
+            target_ids: vec![input, output],
+            scope: CuriosityScope::Neuron,
+            strategy: Rarity,
+        })
+        .unwrap();
+    let recalculated = curiosity.correlation_id();
+    let len = appended_by(&mut curiosity.store, len, recalculated);
+    curiosity
+        .decay(DecayCuriosityScoresCommand {
+            elapsed: Duration::from_secs(3600),
+            decay: CuriosityDecay {
+                half_life: Duration::from_secs(3600),
+                batch_size: 10,
+            },
+        })
+        .unwrap();
+    let decayed = curiosity.correlation_id();
+    assert_ne!(decayed, recalculated);
+    appended_by(&mut curiosity.store, len, decayed);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn population_commands_correlate_every_stream_they_write() {
+    let dir = std::env::temp_dir().join(format!("aei_event_envelope_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let store = FilePopulationEventStore::new(dir.join("population.log"));
+    let streams = dir.clone();
+    let open = move |id| FileEventStore::new(streams.join(format!("{id}.log")));
+    let mut handler = PopulationHandler::new(store, open, ChaCha8Rng::seed_from_u64(5)).unwrap();
+
+    let founder = handler.add_member(&genome().3).unwrap();
+    let added = handler.correlation_id();
+    let stream = |id: Uuid| FileEventStore::new(dir.join(format!("{id}.log")));
+    appended_by(&mut stream(founder), 0, added);
+
+    handler
+        .evolve(
+            &EvolvePopulationCommand {
+                selection: Selection::default(),
+                elitism: 0,
+                crossover_rate: 0.0,
+                mutations: vec![Mutation::MutateSynapseWeight(
+                    MutateRandomSynapseWeightCommand { std_dev: 0.1 },
+                )],
+                speciation: None,
+            },
+            |_| 1.0,
+        )
+        .unwrap();
+    let evolved = handler.correlation_id();
+    assert_ne!(evolved, added);
+    let envelopes = handler.store.load_envelopes().unwrap();
+    assert_eq!(envelopes[0].correlation_id, added);
+    assert!(envelopes[1..].iter().all(|e| e.correlation_id == evolved));
+
+    // The offspring copies the genome within the command, and its mutation
+    // is recorded as caused by it.
+    let child = *handler.population.members.keys().next().unwrap();
+    let envelopes = stream(child).load_envelopes().unwrap();
+    assert_eq!(envelopes.len(), 4);
+    assert!(envelopes[..3].iter().all(|e| e.correlation_id == evolved));
+    assert_ne!(envelopes[3].correlation_id, evolved);
+    assert_eq!(envelopes[3].causation_id, evolved);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bare_logs_stay_readable_and_can_be_extended() {
+    let path = temp_path();
+    let store = FileEventStore::bare(path.clone());
+    let mut handler = AddRandomNeuronHandler::new(store, ChaCha8Rng::seed_from_u64(3)).unwrap();
+    for _ in 0..2 {
+        handler.handle(AddRandomNeuronCommand::default()).unwrap();
+    }
+    let bare = std::fs::read_to_string(&path).unwrap();
+    assert!(bare.lines().all(|line| !line.contains("sequence")));
+
+    let store = FileEventStore::new(path.clone());
+    let mut handler = AddRandomNeuronHandler::new(store, ChaCha8Rng::seed_from_u64(4)).unwrap();
+    handler.handle(AddRandomNeuronCommand::default()).unwrap();
+
+    // The bare lines are left untouched and numbered by their position,
+    // and the envelopes carry on from there.
+    assert!(std::fs::read_to_string(&path).unwrap().starts_with(&bare));
+    let envelopes = FileEventStore::bare(path.clone()).load_envelopes().unwrap();
+    let bare_count = bare.lines().count();
+    assert_eq!(bare_count, 3);
+    assert!(envelopes.len() > bare_count);
+    for (i, envelope) in envelopes.iter().enumerate() {
+        assert_eq!(envelope.sequence, i as u64);
+        assert_eq!(envelope.is_bare(), i < bare_count);
+        assert_eq!(envelope.timestamp.is_some(), i >= bare_count);
+    }
+    let events = FileEventStore::new(path.clone()).load().unwrap();
+    assert_eq!(DomainNetwork::hydrate(&events).neurons.len(), 3);
+    std::fs::remove_file(path).unwrap();
+}
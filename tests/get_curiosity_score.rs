@@ -1,6 +1,6 @@
 use aei_framework::{
     application::{Query, QueryHandler, QueryResult},
-    domain::{Activation, CuriosityScoreUpdated, Event, RandomNeuronAdded},
+    domain::{Activation, CuriosityScoreUpdated, Event, NeuronRole, RandomNeuronAdded},
     infrastructure::projection::{CuriosityScoreProjection, NetworkProjection},
 };
 use uuid::Uuid;
@@ -12,11 +12,14 @@ fn query_handler_returns_curiosity_score() {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: id,
             activation: Activation::ReLU,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
             target_id: id,
             old_score: 0.0,
             new_score: 0.7,
+            strategy: "rarity".into(),
         }),
     ];
     let network = NetworkProjection::from_events(&events);
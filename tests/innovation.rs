@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, AddRandomNeuronCommand, AddRandomNeuronHandler, AddRandomSynapseCommand,
+    AddRandomSynapseHandler, Command, CommandHandler, DomainNetwork, Event, FileEventStore,
+    InnovationTracker, NeuronRole, SplitRandomSynapseCommand, SplitRandomSynapseHandler,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_innovation_{}.log", Uuid::new_v4()));
+    path
+}
+
+/// Creates a command handler on a fresh log containing the given neurons.
+fn handler_with_neurons(neurons: &[Uuid]) -> (PathBuf, CommandHandler<FileEventStore>) {
+    let path = temp_path();
+    let mut handler = CommandHandler::new(FileEventStore::new(path.clone())).unwrap();
+    for id in neurons {
+        handler
+            .handle(Command::CreateNeuron {
+                id: *id,
+                activation: Activation::Identity,
+                role: NeuronRole::Hidden,
+                bias: 0.0,
+            })
+            .unwrap();
+    }
+    (path, handler)
+}
+
+fn create_synapse(handler: &mut CommandHandler<FileEventStore>, from: Uuid, to: Uuid) -> Uuid {
+    let id = Uuid::new_v4();
+    handler
+        .handle(Command::CreateSynapse {
+            id,
+            from,
+            to,
+            weight: 0.5,
+        })
+        .unwrap();
+    id
+}
+
+#[test]
+fn shared_tracker_reuses_numbers_within_a_generation() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let (_, mut first) = handler_with_neurons(&[a, b, c]);
+    let (_, mut second) = handler_with_neurons(&[a, b, c]);
+    let tracker = InnovationTracker::new();
+    first.share_innovations(&tracker);
+    second.share_innovations(&tracker);
+
+    let ab = create_synapse(&mut first, a, b);
+    let bc = create_synapse(&mut first, b, c);
+    let same_ab = create_synapse(&mut second, a, b);
+    let innovation = |h: &CommandHandler<FileEventStore>, id| h.network.synapses[&id].innovation;
+    assert_eq!(innovation(&first, ab), Some(0));
+    assert_eq!(innovation(&first, bc), Some(1));
+    assert_eq!(innovation(&second, same_ab), Some(0));
+
+    tracker.next_generation();
+    let later_bc = create_synapse(&mut second, b, c);
+    assert_eq!(innovation(&second, later_bc), Some(2));
+}
+
+#[test]
+fn innovations_are_recorded_and_resume_after_reload() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let (path, mut handler) = handler_with_neurons(&[a, b, c]);
+    let ab = create_synapse(&mut handler, a, b);
+    drop(handler);
+
+    let mut store = FileEventStore::new(path.clone());
+    let events = store.load().unwrap();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::SynapseCreated { id, innovation: Some(0), .. } if *id == ab
+    )));
+    assert_eq!(
+        DomainNetwork::hydrate(&events).synapses[&ab].innovation,
+        Some(0)
+    );
+
+    let mut resumed = CommandHandler::new(FileEventStore::new(path)).unwrap();
+    assert_eq!(resumed.innovations.peek(), 1);
+    let ac = create_synapse(&mut resumed, a, c);
+    assert_eq!(resumed.network.synapses[&ac].innovation, Some(1));
+
+    // Sharing a fresh tracker keeps the numbers already used by the log.
+    let tracker = InnovationTracker::new();
+    resumed.share_innovations(&tracker);
+    assert_eq!(tracker.peek(), 2);
+}
+
+#[test]
+fn random_handlers_number_their_connections() {
+    let path = temp_path();
+    let tracker = InnovationTracker::new();
+    let rng = ChaCha8Rng::seed_from_u64(5);
+    let mut neurons = AddRandomNeuronHandler::new(FileEventStore::new(path.clone()), rng).unwrap();
+    neurons.base.share_innovations(&tracker);
+    for _ in 0..3 {
+        neurons.handle(AddRandomNeuronCommand::default()).unwrap();
+    }
+    let rng = ChaCha8Rng::seed_from_u64(6);
+    let mut synapses =
+        AddRandomSynapseHandler::new(FileEventStore::new(path.clone()), rng).unwrap();
+    synapses.base.share_innovations(&tracker);
+    let added = synapses.handle(AddRandomSynapseCommand).unwrap();
+    let rng = ChaCha8Rng::seed_from_u64(7);
+    let mut splits =
+        SplitRandomSynapseHandler::new(FileEventStore::new(path.clone()), rng).unwrap();
+    splits.base.share_innovations(&tracker);
+    splits.handle(SplitRandomSynapseCommand::default()).unwrap();
+
+    let events = FileEventStore::new(path).load().unwrap();
+    let network = DomainNetwork::hydrate(&events);
+    let mut numbers: Vec<u64> = network
+        .synapses
+        .values()
+        .map(|s| s.innovation.expect("every synapse is numbered"))
+        .collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    assert_eq!(numbers.len(), network.synapses.len());
+    assert_eq!(tracker.peek(), numbers.len() as u64);
+    assert!(network.synapses[&added].innovation.is_some());
+}
+
+#[test]
+fn legacy_events_have_no_innovation() {
+    let (from, to, id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let json =
+        format!(r#"{{"SynapseCreated":{{"id":"{id}","from":"{from}","to":"{to}","weight":1.0}}}}"#);
+    let event: Event = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        event,
+        Event::SynapseCreated {
+            innovation: None,
+            ..
+        }
+    ));
+    assert_eq!(InnovationTracker::from_events(&[event]).peek(), 0);
+}
@@ -9,6 +9,13 @@ struct TestEvent {
     id: u32,
 }
 
+/// Event whose first field happens to be named like the envelope's.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct Tick {
+    sequence: u64,
+    value: u32,
+}
+
 fn temp_path() -> PathBuf {
     let mut path = std::env::temp_dir();
     path.push(format!("aei_jsonl_store_test_{}.log", Uuid::new_v4()));
@@ -29,3 +36,31 @@ fn generic_store_persists_custom_events() {
     assert_eq!(events, vec![first, second]);
     std::fs::remove_file(path).unwrap();
 }
+
+#[test]
+fn bare_events_shaped_like_envelopes_are_read_back() {
+    let path = temp_path();
+    let tick = |sequence, value| Tick { sequence, value };
+    JsonlEventStore::bare(path.clone())
+        .append(&tick(7, 1))
+        .unwrap();
+    JsonlEventStore::new(path.clone())
+        .append(&tick(8, 2))
+        .unwrap();
+
+    let mut store = JsonlEventStore::<Tick>::new(path.clone());
+    let envelopes = store.load_envelopes().unwrap();
+    assert!(envelopes[0].is_bare());
+    assert_eq!(envelopes[0].sequence, 0);
+    assert!(!envelopes[1].is_bare());
+    assert_eq!(envelopes[1].sequence, 1);
+    assert_eq!(store.load().unwrap(), [tick(7, 1), tick(8, 2)]);
+
+    // Lines holding neither report why the event does not deserialize.
+    JsonlEventStore::<TestEvent>::bare(path.clone())
+        .append(&TestEvent { id: 3 })
+        .unwrap();
+    let error = store.load().unwrap_err();
+    assert!(error.to_string().contains("sequence"), "{error}");
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,85 @@
+use aei_framework::Loss;
+
+const LOSSES: [Loss; 5] = [
+    Loss::MeanSquaredError,
+    Loss::MeanAbsoluteError,
+    Loss::BinaryCrossEntropy,
+    Loss::SoftmaxCrossEntropy,
+    Loss::Huber { delta: 0.5 },
+];
+
+#[test]
+fn losses_match_reference_values() {
+    let outputs = [0.8, 0.1];
+    let targets = [1.0, 0.0];
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+
+    assert!(close(
+        Loss::MeanSquaredError.compute(&outputs, &targets),
+        0.025
+    ));
+    assert!(close(
+        Loss::MeanAbsoluteError.compute(&outputs, &targets),
+        0.15
+    ));
+    assert!(close(
+        Loss::BinaryCrossEntropy.compute(&outputs, &targets),
+        -(0.8f64.ln() + 0.9f64.ln()) / 2.0
+    ));
+    let softmax_first = 0.8f64.exp() / (0.8f64.exp() + 0.1f64.exp());
+    assert!(close(
+        Loss::SoftmaxCrossEntropy.compute(&outputs, &targets),
+        -softmax_first.ln()
+    ));
+    assert!(close(
+        Loss::Huber { delta: 0.1 }.compute(&[2.0, 0.05], &[0.0, 0.0]),
+        (0.1 * (2.0 - 0.05) + 0.5 * 0.05 * 0.05) / 2.0
+    ));
+}
+
+#[test]
+fn gradients_match_finite_differences() {
+    let outputs = [0.3, 0.7, 0.45];
+    let targets = [0.0, 1.0, 1.0];
+    let eps = 1e-6;
+    for loss in LOSSES {
+        let gradient = loss.gradient(&outputs, &targets);
+        for i in 0..outputs.len() {
+            let (mut plus, mut minus) = (outputs, outputs);
+            plus[i] += eps;
+            minus[i] -= eps;
+            let numeric =
+                (loss.compute(&plus, &targets) - loss.compute(&minus, &targets)) / (2.0 * eps);
+            assert!(
+                (numeric - gradient[i]).abs() < 1e-5,
+                "{loss:?}[{i}]: numeric {numeric} vs analytic {}",
+                gradient[i]
+            );
+        }
+    }
+}
+
+#[test]
+fn perfect_predictions_have_minimal_loss() {
+    for loss in [
+        Loss::MeanSquaredError,
+        Loss::MeanAbsoluteError,
+        Loss::Huber { delta: 1.0 },
+    ] {
+        assert_eq!(loss.compute(&[0.5, -2.0], &[0.5, -2.0]), 0.0);
+        assert!(loss
+            .gradient(&[0.5, -2.0], &[0.5, -2.0])
+            .iter()
+            .all(|g| *g == 0.0));
+    }
+    assert!(Loss::BinaryCrossEntropy.compute(&[1.0, 0.0], &[1.0, 0.0]) < 1e-9);
+    assert!(Loss::SoftmaxCrossEntropy.compute(&[50.0, 0.0], &[1.0, 0.0]) < 1e-9);
+}
+
+#[test]
+fn huber_requires_positive_delta() {
+    assert!(Loss::Huber { delta: 1.0 }.is_valid());
+    assert!(!Loss::Huber { delta: 0.0 }.is_valid());
+    assert!(!Loss::Huber { delta: f64::NAN }.is_valid());
+    assert!(Loss::default().is_valid());
+}
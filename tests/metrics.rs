@@ -0,0 +1,76 @@
+use aei_framework::{
+    domain::metrics::{accuracy, class_of, r_squared},
+    Activation, ConfusionMatrix, DomainNetwork, Event, Loss, NeuronAdded, NeuronRole,
+    TrainingSample,
+};
+use uuid::Uuid;
+
+#[test]
+fn class_of_thresholds_single_values_and_picks_argmax() {
+    assert_eq!(class_of(&[0.2]), 0);
+    assert_eq!(class_of(&[0.5]), 1);
+    assert_eq!(class_of(&[0.1, 0.7, 0.2]), 1);
+    assert_eq!(class_of(&[0.4, 0.4]), 0);
+}
+
+#[test]
+fn confusion_matrix_reports_per_class_scores() {
+    let matrix = ConfusionMatrix::from_pairs(2, [(0, 0), (0, 1), (1, 1), (1, 1), (1, 0)]);
+    assert_eq!(matrix.total(), 5);
+    assert_eq!(matrix.count(1, 1), 2);
+    assert_eq!(matrix.accuracy(), 0.6);
+    assert_eq!(matrix.precision(1), 2.0 / 3.0);
+    assert_eq!(matrix.recall(1), 2.0 / 3.0);
+    assert_eq!(matrix.precision(0), 0.5);
+    assert_eq!(ConfusionMatrix::new(3).accuracy(), 0.0);
+}
+
+#[test]
+fn accuracy_and_r_squared_follow_definitions() {
+    assert_eq!(accuracy(&[0, 1, 1, 2], &[0, 1, 2, 2]), 0.75);
+    assert_eq!(r_squared(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]), 1.0);
+    assert_eq!(r_squared(&[2.0, 2.0, 2.0], &[1.0, 2.0, 3.0]), 0.0);
+    assert!((r_squared(&[1.5, 2.0, 2.5], &[1.0, 2.0, 3.0]) - 0.75).abs() < 1e-12);
+}
+
+#[test]
+fn evaluate_samples_scores_network() {
+    let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut net = DomainNetwork::hydrate(&[
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id: input,
+            activation: Activation::Identity,
+            role: NeuronRole::Input,
+            bias: 0.0,
+        }),
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id: output,
+            activation: Activation::Identity,
+            role: NeuronRole::Output,
+            bias: 0.0,
+        }),
+        Event::SynapseCreated {
+            id: Uuid::new_v4(),
+            from: input,
+            to: output,
+            weight: 1.0,
+            innovation: None,
+        },
+    ]);
+    let samples: Vec<TrainingSample> = [(0.0, 0.0), (1.0, 1.0), (0.7, 0.0)]
+        .into_iter()
+        .map(|(x, t)| TrainingSample {
+            inputs: vec![(input, x)],
+            targets: vec![(output, t)],
+        })
+        .collect();
+
+    let evaluation = net
+        .evaluate_samples(&samples, Loss::MeanSquaredError)
+        .unwrap();
+    assert!((evaluation.loss - 0.49 / 3.0).abs() < 1e-12);
+    assert_eq!(evaluation.confusion.classes(), 2);
+    assert_eq!(evaluation.confusion.count(0, 1), 1);
+    assert!((evaluation.accuracy() - 2.0 / 3.0).abs() < 1e-12);
+    assert!((evaluation.r_squared - (1.0 - 0.49 / (2.0 / 3.0))).abs() < 1e-12);
+}
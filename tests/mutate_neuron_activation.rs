@@ -5,7 +5,7 @@ use aei_framework::{
         MutateNeuronActivationError, MutateRandomNeuronActivationCommand,
         MutateRandomNeuronActivationHandler, Query, QueryHandler, QueryResult,
     },
-    domain::{Activation, Event, NeuronActivationMutated, RandomNeuronAdded},
+    domain::{Activation, Event, NeuronActivationMutated, NeuronRole, RandomNeuronAdded},
     infrastructure::{projection::NetworkProjection, FileEventStore},
 };
 use rand::SeedableRng;
@@ -18,10 +18,12 @@ fn temp_path() -> PathBuf {
     path
 }
 
-fn seed_neuron(store: &mut FileEventStore, id: Uuid, activation: Activation) {
+fn seed_neuron(store: &mut FileEventStore, id: Uuid, activation: Activation, role: NeuronRole) {
     let event = Event::RandomNeuronAdded(RandomNeuronAdded {
         neuron_id: id,
         activation,
+        role,
+        bias: 0.0,
     });
     store.append(&event).unwrap();
 }
@@ -31,12 +33,20 @@ fn mutate_neuron_activation_appends_event() {
     let path = temp_path();
     let mut store = FileEventStore::new(path.clone());
     let neuron_id = Uuid::new_v4();
-    seed_neuron(&mut store, neuron_id, Activation::Identity);
+    seed_neuron(
+        &mut store,
+        neuron_id,
+        Activation::Identity,
+        NeuronRole::Hidden,
+    );
 
     let rng = ChaCha8Rng::seed_from_u64(1);
     let mut handler = MutateRandomNeuronActivationHandler::new(store, rng).unwrap();
     let mutated_id = handler
-        .handle(MutateRandomNeuronActivationCommand { exclude_io: false })
+        .handle(MutateRandomNeuronActivationCommand {
+            exclude_io: false,
+            ..Default::default()
+        })
         .unwrap();
     assert_eq!(mutated_id, neuron_id);
 
@@ -79,11 +89,19 @@ fn mutate_neuron_activation_errors_when_no_eligible() {
     let path = temp_path();
     let mut store = FileEventStore::new(path);
     let neuron_id = Uuid::new_v4();
-    seed_neuron(&mut store, neuron_id, Activation::Identity);
+    seed_neuron(
+        &mut store,
+        neuron_id,
+        Activation::Identity,
+        NeuronRole::Input,
+    );
 
     let rng = ChaCha8Rng::seed_from_u64(2);
     let mut handler = MutateRandomNeuronActivationHandler::new(store, rng).unwrap();
-    let res = handler.handle(MutateRandomNeuronActivationCommand { exclude_io: true });
+    let res = handler.handle(MutateRandomNeuronActivationCommand {
+        exclude_io: true,
+        ..Default::default()
+    });
     assert!(matches!(
         res,
         Err(MutateNeuronActivationError::NoEligibleNeuron)
@@ -95,12 +113,20 @@ fn mutate_neuron_activation_event_replay() {
     let path = temp_path();
     let mut store = FileEventStore::new(path.clone());
     let neuron_id = Uuid::new_v4();
-    seed_neuron(&mut store, neuron_id, Activation::Identity);
+    seed_neuron(
+        &mut store,
+        neuron_id,
+        Activation::Identity,
+        NeuronRole::Hidden,
+    );
 
     let rng = ChaCha8Rng::seed_from_u64(3);
     let mut handler = MutateRandomNeuronActivationHandler::new(store, rng).unwrap();
     handler
-        .handle(MutateRandomNeuronActivationCommand { exclude_io: false })
+        .handle(MutateRandomNeuronActivationCommand {
+            exclude_io: false,
+            ..Default::default()
+        })
         .unwrap();
     let store = handler.base.store;
     let mut replay_store = store;
@@ -124,3 +150,34 @@ fn mutate_neuron_activation_event_replay() {
         _ => panic!("activation not found"),
     }
 }
+
+#[test]
+fn mutations_draw_from_the_activation_registry() {
+    let mut store = FileEventStore::new(temp_path());
+    let neuron_id = Uuid::new_v4();
+    seed_neuron(
+        &mut store,
+        neuron_id,
+        Activation::Identity,
+        NeuronRole::Hidden,
+    );
+
+    let rng = ChaCha8Rng::seed_from_u64(4);
+    let mut handler = MutateRandomNeuronActivationHandler::new(store, rng).unwrap();
+    let mut seen = Vec::new();
+    for _ in 0..200 {
+        let before = handler.base.network.neurons[&neuron_id].activation;
+        handler
+            .handle(MutateRandomNeuronActivationCommand {
+                exclude_io: false,
+                ..Default::default()
+            })
+            .unwrap();
+        let after = handler.base.network.neurons[&neuron_id].activation;
+        assert_ne!(before, after);
+        if !seen.contains(&after) {
+            seen.push(after);
+        }
+    }
+    assert_eq!(seen.len(), Activation::all().len());
+}
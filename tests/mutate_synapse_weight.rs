@@ -5,7 +5,7 @@ use aei_framework::{
         MutateRandomSynapseWeightCommand, MutateRandomSynapseWeightError,
         MutateRandomSynapseWeightHandler, Query, QueryHandler, QueryResult,
     },
-    domain::{Event, RandomNeuronAdded, RandomSynapseAdded, SynapseWeightMutated},
+    domain::{Event, NeuronRole, RandomNeuronAdded, RandomSynapseAdded, SynapseWeightMutated},
     infrastructure::{projection::NetworkProjection, FileEventStore},
     Activation,
 };
@@ -24,10 +24,14 @@ fn seed_two_neurons(store: &mut FileEventStore, n1: Uuid, n2: Uuid) {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
     ];
     for e in &events {
@@ -41,6 +45,7 @@ fn seed_synapse(store: &mut FileEventStore, id: Uuid, from: Uuid, to: Uuid) {
         from,
         to,
         weight: 1.0,
+        innovation: None,
     });
     store.append(&event).unwrap();
 }
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use aei_framework::{
+    Activation, AddRandomSynapseCommand, AppliedMutation, Event, FileEventStore,
+    MutateRandomNeuronBiasCommand, MutateRandomSynapseWeightCommand, Mutation, MutationKind,
+    MutationPolicy, MutationPolicyError, NetworkHandlerBase, NeuronAdded, NeuronRole,
+    RemoveRandomSynapseCommand, SplitRandomSynapseCommand,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_mutation_policy_{}.log", Uuid::new_v4()));
+    path
+}
+
+/// Opens a handler base on a log holding two unconnected neurons.
+fn base(path: &Path, seed: u64) -> NetworkHandlerBase<FileEventStore, ChaCha8Rng> {
+    let mut store = FileEventStore::new(path.to_path_buf());
+    for role in [NeuronRole::Input, NeuronRole::Output] {
+        store
+            .append(&Event::NeuronAdded(NeuronAdded {
+                neuron_id: Uuid::new_v4(),
+                activation: Activation::Identity,
+                role,
+                bias: 0.0,
+            }))
+            .unwrap();
+    }
+    NetworkHandlerBase::new(store, ChaCha8Rng::seed_from_u64(seed)).unwrap()
+}
+
+fn weight(std_dev: f64) -> Mutation {
+    Mutation::MutateSynapseWeight(MutateRandomSynapseWeightCommand { std_dev })
+}
+
+#[test]
+fn certain_operators_apply_in_order_on_the_shared_base() {
+    let path = temp_path();
+    let mut base = base(&path, 1);
+    let policy = MutationPolicy::new()
+        .with_operator(1.0, Mutation::RemoveSynapse(RemoveRandomSynapseCommand))
+        .with_operator(1.0, Mutation::AddSynapse(AddRandomSynapseCommand))
+        .with_operator(1.0, weight(0.1))
+        .with_operator(
+            0.0,
+            Mutation::SplitSynapse(SplitRandomSynapseCommand::default()),
+        );
+
+    let report = policy.step(&mut base).unwrap();
+    // Nothing to remove yet; the weight mutation sees the new synapse.
+    assert_eq!(report.skipped, vec![0]);
+    let synapse = *base.network.synapses.keys().next().unwrap();
+    assert_eq!(
+        report.applied,
+        vec![
+            AppliedMutation {
+                operator: 1,
+                kind: MutationKind::AddSynapse,
+                target: synapse,
+            },
+            AppliedMutation {
+                operator: 2,
+                kind: MutationKind::MutateSynapseWeight,
+                target: synapse,
+            },
+        ]
+    );
+    assert!(base.network.synapses[&synapse].innovation.is_some());
+
+    let events = FileEventStore::new(path.clone()).load().unwrap();
+    assert!(matches!(events[2], Event::RandomSynapseAdded { .. }));
+    assert!(matches!(events[3], Event::SynapseWeightMutated(_)));
+    assert_eq!(events.len(), 4);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn operators_fire_with_their_probability() {
+    let path = temp_path();
+    let mut base = base(&path, 2);
+    Mutation::AddSynapse(AddRandomSynapseCommand)
+        .apply(&mut base)
+        .unwrap()
+        .unwrap();
+    let policy = MutationPolicy::new()
+        .with_operator(0.3, weight(0.1))
+        .with_operator(
+            0.05,
+            Mutation::MutateNeuronBias(MutateRandomNeuronBiasCommand { std_dev: 0.1 }),
+        );
+
+    let mut counts = [0usize; 2];
+    for _ in 0..2000 {
+        for applied in policy.step(&mut base).unwrap().applied {
+            counts[applied.operator] += 1;
+        }
+    }
+    assert!((500..700).contains(&counts[0]), "{counts:?}");
+    assert!((60..140).contains(&counts[1]), "{counts:?}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn invalid_policies_apply_nothing() {
+    let path = temp_path();
+    let mut base = base(&path, 3);
+    let policy = MutationPolicy::new()
+        .with_operator(1.0, Mutation::AddSynapse(AddRandomSynapseCommand))
+        .with_operator(1.5, weight(0.1));
+    assert_eq!(
+        policy.step(&mut base),
+        Err(MutationPolicyError::InvalidProbability(1))
+    );
+    let policy = MutationPolicy::new().with_operator(0.5, weight(0.0));
+    assert_eq!(
+        policy.validate(),
+        Err(MutationPolicyError::InvalidMutation(0))
+    );
+    assert!(base.network.synapses.is_empty());
+    assert_eq!(FileEventStore::new(path.clone()).load().unwrap().len(), 2);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn same_seed_produces_same_steps() {
+    let policy = MutationPolicy::new()
+        .with_operator(0.5, Mutation::AddSynapse(AddRandomSynapseCommand))
+        .with_operator(0.5, weight(0.2))
+        .with_operator(
+            0.2,
+            Mutation::SplitSynapse(SplitRandomSynapseCommand::default()),
+        );
+    let run = |seed| {
+        let path = temp_path();
+        let mut base = base(&path, seed);
+        let kinds: Vec<Vec<MutationKind>> = (0..20)
+            .map(|_| {
+                let report = policy.step(&mut base).unwrap();
+                report.applied.iter().map(|a| a.kind).collect()
+            })
+            .collect();
+        std::fs::remove_file(path).unwrap();
+        kinds
+    };
+    assert_eq!(run(4), run(4));
+}
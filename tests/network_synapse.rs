@@ -1,4 +1,6 @@
-use aei_framework::{Activation, DomainNetwork, Event, RandomNeuronAdded, RandomSynapseAdded};
+use aei_framework::{
+    Activation, DomainNetwork, Event, NeuronRole, RandomNeuronAdded, RandomSynapseAdded,
+};
 use uuid::Uuid;
 
 /// Ensure a synapse pointing to the same neuron is ignored.
@@ -12,16 +14,21 @@ fn self_loop_synapse_is_ignored() {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomSynapseAdded(RandomSynapseAdded {
             synapse_id: s,
             from: n1,
             to: n1,
             weight: 1.0,
+            innovation: None,
         }),
     ];
 
@@ -41,22 +48,28 @@ fn duplicate_synapse_is_ignored() {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomSynapseAdded(RandomSynapseAdded {
             synapse_id: s1,
             from: n1,
             to: n2,
             weight: 1.0,
+            innovation: None,
         }),
         Event::RandomSynapseAdded(RandomSynapseAdded {
             synapse_id: s2,
             from: n1,
             to: n2,
             weight: 2.0,
+            innovation: None,
         }),
     ];
 
@@ -0,0 +1,205 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    application::{Query, QueryHandler, QueryResult},
+    infrastructure::projection::NetworkProjection,
+    Activation, DomainNetwork, Event, FileEventStore, Loss, MutateRandomNeuronBiasCommand,
+    MutateRandomNeuronBiasError, MutateRandomNeuronBiasHandler, NeuronAdded, NeuronBiasMutated,
+    NeuronRole, OptimizerConfig, SetNeuronBiasCommand, SetNeuronBiasError, SetNeuronBiasHandler,
+    TrainNetworkCommand, TrainNetworkHandler, TrainingSample,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_bias_{}.log", Uuid::new_v4()));
+    path
+}
+
+fn neuron(id: Uuid, activation: Activation, role: NeuronRole, bias: f64) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: id,
+        activation,
+        role,
+        bias,
+    })
+}
+
+fn synapse(from: Uuid, to: Uuid, weight: f64) -> Event {
+    Event::SynapseCreated {
+        id: Uuid::new_v4(),
+        from,
+        to,
+        weight,
+        innovation: None,
+    }
+}
+
+#[test]
+fn bias_shifts_weighted_sum_before_activation() {
+    let (input, out) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut net = DomainNetwork::hydrate(&[
+        neuron(input, Activation::Identity, NeuronRole::Input, 5.0),
+        neuron(out, Activation::ReLU, NeuronRole::Output, -1.0),
+        synapse(input, out, 2.0),
+    ]);
+    assert_eq!(
+        net.propagate(&[(input, 1.5)], &[input, out]).unwrap(),
+        vec![1.5, 2.0]
+    );
+    assert_eq!(net.propagate(&[(input, 0.25)], &[out]).unwrap(), vec![0.0]);
+}
+
+#[test]
+fn set_neuron_bias_updates_network_and_projection() {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path.clone());
+    let id = Uuid::new_v4();
+    store
+        .append(&neuron(id, Activation::Identity, NeuronRole::Hidden, 0.0))
+        .unwrap();
+
+    let mut handler = SetNeuronBiasHandler::new(store).unwrap();
+    handler
+        .handle(SetNeuronBiasCommand {
+            neuron_id: id,
+            new_bias: 0.75,
+        })
+        .unwrap();
+    assert_eq!(handler.network.neurons[&id].bias, 0.75);
+    assert_eq!(
+        handler.handle(SetNeuronBiasCommand {
+            neuron_id: Uuid::new_v4(),
+            new_bias: 1.0,
+        }),
+        Err(SetNeuronBiasError::NeuronNotFound)
+    );
+
+    let events = FileEventStore::new(path).load().unwrap();
+    let projection = NetworkProjection::from_events(&events);
+    let queries = QueryHandler::new(&projection);
+    match queries.handle(Query::GetNeuronBias { id }) {
+        QueryResult::Bias(Some(bias)) => assert_eq!(bias, 0.75),
+        _ => panic!("bias not found"),
+    }
+    assert_eq!(queries.bias(Uuid::new_v4()), None);
+}
+
+#[test]
+fn mutate_random_neuron_bias_targets_hidden_and_output_neurons() {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path.clone());
+    let hidden = Uuid::new_v4();
+    for event in [
+        neuron(Uuid::new_v4(), Activation::Identity, NeuronRole::Input, 0.0),
+        neuron(Uuid::new_v4(), Activation::Identity, NeuronRole::Bias, 0.0),
+        neuron(hidden, Activation::Tanh, NeuronRole::Hidden, 0.5),
+    ] {
+        store.append(&event).unwrap();
+    }
+
+    let rng = ChaCha8Rng::seed_from_u64(11);
+    let mut handler = MutateRandomNeuronBiasHandler::new(store, rng).unwrap();
+    for _ in 0..5 {
+        let id = handler
+            .handle(MutateRandomNeuronBiasCommand { std_dev: 0.1 })
+            .unwrap();
+        assert_eq!(id, hidden);
+    }
+    assert_eq!(
+        handler.handle(MutateRandomNeuronBiasCommand { std_dev: 0.0 }),
+        Err(MutateRandomNeuronBiasError::InvalidStdDev)
+    );
+
+    let events = FileEventStore::new(path).load().unwrap();
+    match events.last().unwrap() {
+        Event::NeuronBiasMutated(NeuronBiasMutated {
+            neuron_id,
+            old_bias,
+            new_bias,
+        }) => {
+            assert_eq!(*neuron_id, hidden);
+            assert_ne!(old_bias, new_bias);
+            let replayed = DomainNetwork::hydrate(&events);
+            assert_eq!(replayed.neurons[&hidden].bias, *new_bias);
+            assert_eq!(handler.base.network.neurons[&hidden].bias, *new_bias);
+        }
+        e => panic!("unexpected event {e:?}"),
+    }
+}
+
+#[test]
+fn mutate_random_neuron_bias_errors_without_candidates() {
+    let mut store = FileEventStore::new(temp_path());
+    store
+        .append(&neuron(
+            Uuid::new_v4(),
+            Activation::Identity,
+            NeuronRole::Input,
+            0.0,
+        ))
+        .unwrap();
+    let rng = ChaCha8Rng::seed_from_u64(12);
+    let mut handler = MutateRandomNeuronBiasHandler::new(store, rng).unwrap();
+    assert_eq!(
+        handler.handle(MutateRandomNeuronBiasCommand { std_dev: 0.1 }),
+        Err(MutateRandomNeuronBiasError::NoNeuronAvailable)
+    );
+}
+
+#[test]
+fn training_with_biases_solves_xor() {
+    let (a, b, h1, h2, out) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+    let mut store = FileEventStore::new(temp_path());
+    for event in [
+        neuron(a, Activation::Identity, NeuronRole::Input, 0.0),
+        neuron(b, Activation::Identity, NeuronRole::Input, 0.0),
+        neuron(h1, Activation::Tanh, NeuronRole::Hidden, 0.1),
+        neuron(h2, Activation::Tanh, NeuronRole::Hidden, -0.1),
+        neuron(out, Activation::Sigmoid, NeuronRole::Output, 0.0),
+        synapse(a, h1, 0.5),
+        synapse(b, h1, -0.4),
+        synapse(a, h2, -0.3),
+        synapse(b, h2, 0.6),
+        synapse(h1, out, 0.7),
+        synapse(h2, out, 0.2),
+    ] {
+        store.append(&event).unwrap();
+    }
+    let samples: Vec<TrainingSample> = [
+        (0.0, 0.0, 0.0),
+        (0.0, 1.0, 1.0),
+        (1.0, 0.0, 1.0),
+        (1.0, 1.0, 0.0),
+    ]
+    .into_iter()
+    .map(|(x, y, t)| TrainingSample {
+        inputs: vec![(a, x), (b, y)],
+        targets: vec![(out, t)],
+    })
+    .collect();
+
+    let mut handler = TrainNetworkHandler::new(store).unwrap();
+    handler
+        .handle(TrainNetworkCommand {
+            samples: samples.clone(),
+            epochs: 500,
+            loss: Loss::BinaryCrossEntropy,
+            optimizer: OptimizerConfig::adam(0.05),
+        })
+        .unwrap();
+    let evaluation = handler
+        .network
+        .evaluate_samples(&samples, Loss::BinaryCrossEntropy)
+        .unwrap();
+    assert_eq!(evaluation.accuracy(), 1.0);
+    assert!(handler.network.neurons[&h1].bias != 0.1);
+}
@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 
 use aei_framework::{
-    AddRandomNeuronCommand, AddRandomNeuronHandler, Event, FileEventStore, RandomNeuronAdded,
-    RemoveRandomNeuronCommand, RemoveRandomNeuronError, RemoveRandomNeuronHandler,
+    Activation, AddRandomNeuronCommand, AddRandomNeuronHandler, Event, FileEventStore, NeuronAdded,
+    NeuronRole, RandomNeuronAdded, RemoveRandomNeuronCommand, RemoveRandomNeuronError,
+    RemoveRandomNeuronHandler,
 };
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
@@ -22,7 +23,7 @@ fn add_random_neuron_appends_event() {
     let mut handler = AddRandomNeuronHandler::new(store, rng).unwrap();
 
     let id = handler
-        .handle(AddRandomNeuronCommand)
+        .handle(AddRandomNeuronCommand::default())
         .expect("neuron added");
     assert!(handler.base.network.neurons.contains_key(&id));
 
@@ -42,8 +43,8 @@ fn remove_random_neuron_removes_synapses() {
     let store = FileEventStore::new(path.clone());
     let rng = ChaCha8Rng::seed_from_u64(1);
     let mut add = AddRandomNeuronHandler::new(store, rng).unwrap();
-    let id1 = add.handle(AddRandomNeuronCommand).unwrap();
-    let id2 = add.handle(AddRandomNeuronCommand).unwrap();
+    let id1 = add.handle(AddRandomNeuronCommand::default()).unwrap();
+    let id2 = add.handle(AddRandomNeuronCommand::default()).unwrap();
 
     // Manually connect the two neurons.
     let syn_id = Uuid::new_v4();
@@ -52,6 +53,7 @@ fn remove_random_neuron_removes_synapses() {
         from: id1,
         to: id2,
         weight: 1.0,
+        innovation: None,
     };
     add.base.store.append(&event).unwrap();
     add.base.network.apply(&event);
@@ -80,13 +82,44 @@ fn remove_random_neuron_errors_when_empty() {
     ));
 }
 
+#[test]
+fn remove_random_neuron_skips_protected_roles() {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path);
+    for role in [NeuronRole::Input, NeuronRole::Output, NeuronRole::Bias] {
+        let event = Event::NeuronAdded(NeuronAdded {
+            neuron_id: Uuid::new_v4(),
+            activation: Activation::Identity,
+            role,
+            bias: 0.0,
+        });
+        store.append(&event).unwrap();
+    }
+    let rng = ChaCha8Rng::seed_from_u64(8);
+    let mut handler = RemoveRandomNeuronHandler::new(store, rng).unwrap();
+    assert!(matches!(
+        handler.handle(RemoveRandomNeuronCommand),
+        Err(RemoveRandomNeuronError::NoNeuronAvailable)
+    ));
+
+    let rng = ChaCha8Rng::seed_from_u64(9);
+    let mut add = AddRandomNeuronHandler::new(handler.base.store, rng).unwrap();
+    let hidden = add.handle(AddRandomNeuronCommand::default()).unwrap();
+    assert_eq!(add.base.network.neurons[&hidden].role, NeuronRole::Hidden);
+
+    let rng = ChaCha8Rng::seed_from_u64(10);
+    let mut remove = RemoveRandomNeuronHandler::new(add.base.store, rng).unwrap();
+    assert_eq!(remove.handle(RemoveRandomNeuronCommand).unwrap(), hidden);
+    assert_eq!(remove.base.network.neurons.len(), 3);
+}
+
 #[test]
 fn event_replay_reconstructs_state() {
     let path = temp_path();
     let store = FileEventStore::new(path.clone());
     let rng = ChaCha8Rng::seed_from_u64(5);
     let mut add = AddRandomNeuronHandler::new(store, rng).unwrap();
-    let id = add.handle(AddRandomNeuronCommand).unwrap();
+    let id = add.handle(AddRandomNeuronCommand::default()).unwrap();
 
     let store = add.base.store; // move
     let rng = ChaCha8Rng::seed_from_u64(6);
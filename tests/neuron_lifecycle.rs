@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use aei_framework::{
     Activation, Command, CommandHandler, DomainNetwork, Event, FileEventStore, NeuronAdded,
-    NeuronRemoved,
+    NeuronRemoved, NeuronRole,
 };
 use uuid::Uuid;
 
@@ -23,6 +23,8 @@ fn create_neuron_appends_event() {
         .handle(Command::CreateNeuron {
             id,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         })
         .unwrap();
     assert!(handler.network.neurons.contains_key(&id));
@@ -47,12 +49,16 @@ fn remove_neuron_removes_synapses() {
         .handle(Command::CreateNeuron {
             id: id1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         })
         .unwrap();
     handler
         .handle(Command::CreateNeuron {
             id: id2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         })
         .unwrap();
 
@@ -90,6 +96,8 @@ fn event_replay_reconstructs_state() {
         .handle(Command::CreateNeuron {
             id,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         })
         .unwrap();
     handler.handle(Command::RemoveNeuron { id }).unwrap();
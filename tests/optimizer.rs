@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use aei_framework::{domain::Adam, Optimizer, OptimizerConfig};
+use uuid::Uuid;
+
+fn gradients(id: Uuid, gradient: f64) -> BTreeMap<Uuid, f64> {
+    BTreeMap::from([(id, gradient)])
+}
+
+#[test]
+fn sgd_momentum_accumulates_velocity() {
+    let id = Uuid::new_v4();
+    let mut sgd = OptimizerConfig::Sgd {
+        learning_rate: 0.1,
+        momentum: 0.5,
+    }
+    .build();
+    assert!((sgd.step(&gradients(id, 1.0))[&id] + 0.1).abs() < 1e-12);
+    assert!((sgd.step(&gradients(id, 1.0))[&id] + 0.15).abs() < 1e-12);
+}
+
+#[test]
+fn rms_prop_normalises_gradient_scale() {
+    let id = Uuid::new_v4();
+    let mut small = OptimizerConfig::rms_prop(0.01).build();
+    let mut large = OptimizerConfig::rms_prop(0.01).build();
+    let a = small.step(&gradients(id, 1e-3))[&id];
+    let b = large.step(&gradients(id, 1e3))[&id];
+    assert!((a - b).abs() < 1e-6);
+    assert!(a < 0.0);
+}
+
+#[test]
+fn adam_first_step_moves_by_learning_rate() {
+    let id = Uuid::new_v4();
+    let mut adam = OptimizerConfig::adam(0.05).build();
+    let delta = adam.step(&gradients(id, -4.0))[&id];
+    assert!((delta - 0.05).abs() < 1e-9);
+}
+
+#[test]
+fn config_builds_matching_optimizer() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut direct = Adam::new(0.01, 0.9, 0.999, 1e-8);
+    let mut built = OptimizerConfig::adam(0.01).build();
+    for i in 0..5 {
+        let g = BTreeMap::from([(a, f64::from(i) - 2.0), (b, 0.5 * f64::from(i))]);
+        assert_eq!(direct.step(&g), built.step(&g));
+    }
+}
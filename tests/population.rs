@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, AddRandomSynapseCommand, DomainNetwork, Event, EvolvePopulationCommand,
+    FileEventStore, FilePopulationEventStore, MutateRandomSynapseWeightCommand, Mutation,
+    NeuronAdded, NeuronRole, PopulationError, PopulationEvent, PopulationHandler, Selection,
+};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aei_population_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+type Handler = PopulationHandler<
+    FilePopulationEventStore,
+    FileEventStore,
+    Box<dyn FnMut(Uuid) -> FileEventStore>,
+    ChaCha8Rng,
+>;
+
+fn handler(dir: &std::path::Path, seed: u64) -> Handler {
+    let store = FilePopulationEventStore::new(dir.join("population.log"));
+    let streams = dir.to_path_buf();
+    let open: Box<dyn FnMut(Uuid) -> FileEventStore> =
+        Box::new(move |id| FileEventStore::new(streams.join(format!("{id}.log"))));
+    PopulationHandler::new(store, open, ChaCha8Rng::seed_from_u64(seed)).unwrap()
+}
+
+fn neuron(id: Uuid, role: NeuronRole) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: id,
+        activation: Activation::Identity,
+        role,
+        bias: 0.0,
+    })
+}
+
+/// Input and output neurons joined by one synapse of the given weight.
+fn genome(input: Uuid, output: Uuid, weight: f64) -> Vec<Event> {
+    vec![
+        neuron(input, NeuronRole::Input),
+        neuron(output, NeuronRole::Output),
+        Event::SynapseCreated {
+            id: Uuid::new_v4(),
+            from: input,
+            to: output,
+            weight,
+            innovation: Some(0),
+        },
+    ]
+}
+
+fn weight_evolution() -> EvolvePopulationCommand {
+    EvolvePopulationCommand {
+        selection: Selection::Tournament { size: 3 },
+        elitism: 1,
+        crossover_rate: 0.0,
+        mutations: vec![Mutation::MutateSynapseWeight(
+            MutateRandomSynapseWeightCommand { std_dev: 0.3 },
+        )],
+        speciation: None,
+    }
+}
+
+#[test]
+fn evolution_improves_fitness_and_records_generations() {
+    let dir = temp_dir();
+    let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    let fitness = |net: &mut DomainNetwork| {
+        let value = net.propagate(&[(input, 1.0)], &[output]).unwrap()[0];
+        -(value - 2.0).powi(2)
+    };
+    let mut population = handler(&dir, 3);
+    for weight in [-1.0, -0.5, 0.0, 0.5] {
+        population
+            .add_member(&genome(input, output, weight))
+            .unwrap();
+    }
+    let founders: Vec<Uuid> = population.population.members.keys().copied().collect();
+    let initial = founders
+        .iter()
+        .map(|id| fitness(&mut population.network(*id).unwrap()))
+        .fold(f64::MIN, f64::max);
+
+    let mut best = Vec::new();
+    for generation in 0..30 {
+        let completed = population.evolve(&weight_evolution(), fitness).unwrap();
+        assert_eq!(completed.generation, generation);
+        assert_eq!(population.population.members.len(), 4);
+        assert!(population
+            .population
+            .members
+            .contains_key(&completed.best_member_id));
+        best.push(completed.best_fitness);
+    }
+    assert!(best.windows(2).all(|w| w[1] >= w[0]), "{best:?}");
+    assert!(best[29] > initial);
+    assert!(best[29] > -0.05, "{best:?}");
+
+    let events = FilePopulationEventStore::new(dir.join("population.log"))
+        .load()
+        .unwrap();
+    let boundaries = events
+        .iter()
+        .filter(|e| matches!(e, PopulationEvent::GenerationCompleted(_)))
+        .count();
+    assert_eq!(boundaries, 30);
+    let children: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            PopulationEvent::MemberAdded(added) if added.generation > 0 => Some(added),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(children.len(), 30 * 3);
+    assert!(children.iter().all(|c| c.parents.len() == 1));
+
+    let resumed = handler(&dir, 4);
+    assert_eq!(resumed.population.generation, 30);
+    assert_eq!(
+        resumed.population.members.keys().collect::<Vec<_>>(),
+        population.population.members.keys().collect::<Vec<_>>()
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn offspring_share_innovations_within_a_generation() {
+    let dir = temp_dir();
+    let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+    let founder: Vec<Event> = ids
+        .iter()
+        .map(|id| neuron(*id, NeuronRole::Hidden))
+        .collect();
+    let mut population = handler(&dir, 9);
+    for _ in 0..6 {
+        population.add_member(&founder).unwrap();
+    }
+    let cmd = EvolvePopulationCommand {
+        selection: Selection::Roulette,
+        elitism: 0,
+        crossover_rate: 0.0,
+        mutations: vec![Mutation::AddSynapse(AddRandomSynapseCommand)],
+        speciation: None,
+    };
+    population.evolve(&cmd, |_| 1.0).unwrap();
+
+    let mut numbers: HashMap<(Uuid, Uuid), u64> = HashMap::new();
+    let members: Vec<Uuid> = population.population.members.keys().copied().collect();
+    for member in members {
+        let network = population.network(member).unwrap();
+        assert_eq!(network.synapses.len(), 1);
+        for synapse in network.synapses.values() {
+            let innovation = synapse.innovation.unwrap();
+            let known = *numbers
+                .entry((synapse.from, synapse.to))
+                .or_insert(innovation);
+            assert_eq!(known, innovation);
+        }
+    }
+    let mut distinct: Vec<u64> = numbers.values().copied().collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct.len(), numbers.len());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn evolve_rejects_invalid_commands() {
+    let dir = temp_dir();
+    let mut population = handler(&dir, 1);
+    assert_eq!(
+        population.evolve(&weight_evolution(), |_| 0.0),
+        Err(PopulationError::EmptyPopulation)
+    );
+    let (input, output) = (Uuid::new_v4(), Uuid::new_v4());
+    let member = population.add_member(&genome(input, output, 0.1)).unwrap();
+
+    let mut cmd = weight_evolution();
+    cmd.elitism = 2;
+    assert_eq!(
+        population.evolve(&cmd, |_| 0.0),
+        Err(PopulationError::InvalidElitism)
+    );
+    let mut cmd = weight_evolution();
+    cmd.selection = Selection::Truncation { fraction: 0.0 };
+    assert_eq!(
+        population.evolve(&cmd, |_| 0.0),
+        Err(PopulationError::InvalidSelection)
+    );
+    let mut cmd = weight_evolution();
+    cmd.mutations.push(Mutation::MutateSynapseWeight(
+        MutateRandomSynapseWeightCommand { std_dev: -1.0 },
+    ));
+    assert_eq!(
+        population.evolve(&cmd, |_| 0.0),
+        Err(PopulationError::InvalidMutation(1))
+    );
+    assert_eq!(
+        population.evolve(&weight_evolution(), |_| f64::NAN),
+        Err(PopulationError::NonFiniteFitness(member))
+    );
+    assert_eq!(population.population.generation, 0);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn selection_schemes_favour_fitter_members() {
+    let mut rng = ChaCha8Rng::seed_from_u64(17);
+    let ranked: Vec<(Uuid, f64)> = [4.0, 3.0, 2.0, 1.0]
+        .into_iter()
+        .map(|f| (Uuid::new_v4(), f))
+        .collect();
+    let counts = |selection: Selection, rng: &mut ChaCha8Rng| {
+        let mut counts = [0usize; 4];
+        for _ in 0..4000 {
+            let id = selection.select(&ranked, rng).unwrap();
+            counts[ranked.iter().position(|(r, _)| *r == id).unwrap()] += 1;
+        }
+        counts
+    };
+
+    let truncation = counts(Selection::Truncation { fraction: 0.5 }, &mut rng);
+    assert_eq!(truncation[2] + truncation[3], 0);
+    assert!(truncation[0] > 1800 && truncation[1] > 1800);
+
+    let roulette = counts(Selection::Roulette, &mut rng);
+    assert_eq!(roulette[3], 0);
+    assert!(roulette[0] > roulette[1] && roulette[1] > roulette[2]);
+
+    let tournament = counts(Selection::Tournament { size: 2 }, &mut rng);
+    assert!(tournament[0] > tournament[1] && tournament[2] > tournament[3]);
+
+    let flat = [(Uuid::new_v4(), 1.0), (Uuid::new_v4(), 1.0)];
+    assert!(Selection::Roulette.select(&flat, &mut rng).is_some());
+    assert_eq!(Selection::default().select(&[], &mut rng), None);
+    assert!(!Selection::Tournament { size: 0 }.is_valid());
+    assert!(!Selection::Truncation { fraction: 1.5 }.is_valid());
+}
+
+/// Always draws the largest value, landing at the very end of the wheel.
+struct MaxRng;
+
+impl RngCore for MaxRng {
+    fn next_u32(&mut self) -> u32 {
+        u32::MAX
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        u64::MAX
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(u8::MAX);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[test]
+fn roulette_never_lands_on_the_least_fit_member() {
+    // These weights sum to slightly more than the draw can subtract, so the
+    // wheel runs out before the threshold drops below zero.
+    let ranked: Vec<(Uuid, f64)> = [0.9, 0.7, 0.6, 0.2, 0.0]
+        .into_iter()
+        .map(|f| (Uuid::new_v4(), f))
+        .collect();
+    assert_eq!(
+        Selection::Roulette.select(&ranked, &mut MaxRng),
+        Some(ranked[3].0)
+    );
+}
@@ -1,6 +1,6 @@
 use aei_framework::{
     application::{Query, QueryHandler, QueryResult},
-    domain::{Event, RandomNeuronAdded},
+    domain::{Event, NeuronRole, RandomNeuronAdded},
     infrastructure::projection::NetworkProjection,
     Activation,
 };
@@ -12,6 +12,8 @@ fn projection_replays_events_for_queries() {
     let events = vec![Event::RandomNeuronAdded(RandomNeuronAdded {
         neuron_id,
         activation: Activation::ReLU,
+        role: NeuronRole::Hidden,
+        bias: 0.0,
     })];
     let projection = NetworkProjection::from_events(&events);
     let handler = QueryHandler::new(&projection);
@@ -0,0 +1,110 @@
+use aei_framework::{Activation, DomainNetwork, Event, NeuronAdded, NeuronRole, PropagationError};
+use uuid::Uuid;
+
+fn neuron(id: Uuid, activation: Activation) -> Event {
+    neuron_with_role(id, activation, NeuronRole::Hidden)
+}
+
+fn neuron_with_role(id: Uuid, activation: Activation, role: NeuronRole) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: id,
+        activation,
+        role,
+        bias: 0.0,
+    })
+}
+
+fn synapse(from: Uuid, to: Uuid, weight: f64) -> Event {
+    Event::SynapseCreated {
+        id: Uuid::new_v4(),
+        from,
+        to,
+        weight,
+        innovation: None,
+    }
+}
+
+#[test]
+fn propagate_evaluates_chained_neurons() {
+    let (input, hidden, output) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let mut net = DomainNetwork::hydrate(&[
+        neuron(input, Activation::Identity),
+        neuron(hidden, Activation::ReLU),
+        neuron(output, Activation::Sigmoid),
+        synapse(input, hidden, 2.0),
+        synapse(hidden, output, -1.0),
+    ]);
+
+    let values = net.propagate(&[(input, 0.5)], &[hidden, output]).unwrap();
+    assert_eq!(values[0], 1.0);
+    assert!((values[1] - Activation::Sigmoid.apply(-1.0)).abs() < 1e-12);
+    assert_eq!(net.neurons[&output].value, values[1]);
+}
+
+#[test]
+fn propagate_sums_all_incoming_synapses() {
+    let (a, b, out) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let mut net = DomainNetwork::hydrate(&[
+        neuron(a, Activation::Identity),
+        neuron(b, Activation::Identity),
+        neuron(out, Activation::Identity),
+        synapse(a, out, 0.5),
+        synapse(b, out, 2.0),
+    ]);
+
+    let values = net.propagate(&[(a, 4.0), (b, -1.0)], &[out]).unwrap();
+    assert_eq!(values, vec![0.0]);
+    let values = net.propagate(&[(a, 2.0), (b, 1.0)], &[out]).unwrap();
+    assert_eq!(values, vec![3.0]);
+}
+
+#[test]
+fn propagate_rejects_cycles() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut net = DomainNetwork::hydrate(&[
+        neuron(a, Activation::Identity),
+        neuron(b, Activation::Identity),
+        synapse(a, b, 1.0),
+        synapse(b, a, 1.0),
+    ]);
+
+    assert_eq!(
+        net.topological_order(),
+        Err(PropagationError::CycleDetected)
+    );
+    assert_eq!(
+        net.propagate(&[], &[a]),
+        Err(PropagationError::CycleDetected)
+    );
+}
+
+#[test]
+fn propagate_rejects_unknown_neurons() {
+    let a = Uuid::new_v4();
+    let mut net = DomainNetwork::hydrate(&[neuron(a, Activation::Identity)]);
+    let unknown = Uuid::new_v4();
+
+    assert_eq!(
+        net.propagate(&[(unknown, 1.0)], &[a]),
+        Err(PropagationError::UnknownNeuron(unknown))
+    );
+    assert_eq!(
+        net.propagate(&[(a, 1.0)], &[unknown]),
+        Err(PropagationError::UnknownNeuron(unknown))
+    );
+}
+
+#[test]
+fn propagate_bias_neurons_emit_one() {
+    let (input, bias, out) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let mut net = DomainNetwork::hydrate(&[
+        neuron_with_role(input, Activation::Identity, NeuronRole::Input),
+        neuron_with_role(bias, Activation::Identity, NeuronRole::Bias),
+        neuron_with_role(out, Activation::Identity, NeuronRole::Output),
+        synapse(input, out, 1.0),
+        synapse(bias, out, 0.5),
+    ]);
+
+    let values = net.propagate(&[(input, 2.0)], &[bias, out]).unwrap();
+    assert_eq!(values, vec![1.0, 2.5]);
+}
@@ -1,6 +1,6 @@
 use aei_framework::{
-    application::QueryHandler,
-    domain::{Event, RandomNeuronAdded},
+    application::{Query, QueryHandler, QueryResult},
+    domain::{Event, NeuronAdded, NeuronRole, RandomNeuronAdded},
     infrastructure::projection::NetworkProjection,
     Activation,
 };
@@ -15,16 +15,21 @@ fn convenience_methods_return_expected_values() {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: neuron_a,
             activation: Activation::ReLU,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: neuron_b,
             activation: Activation::Sigmoid,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::SynapseCreated {
             id: synapse_id,
             from: neuron_a,
             to: neuron_b,
             weight: 0.5,
+            innovation: None,
         },
     ];
     let projection = NetworkProjection::from_events(&events);
@@ -42,3 +47,36 @@ fn convenience_methods_return_expected_values() {
     assert_eq!(handler.activation(neuron_a), Some(Activation::ReLU));
     assert_eq!(handler.activation(Uuid::new_v4()), None);
 }
+
+#[test]
+fn list_neurons_by_role_filters_neurons() {
+    let input = Uuid::new_v4();
+    let hidden = Uuid::new_v4();
+    let events = vec![
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id: input,
+            activation: Activation::Identity,
+            role: NeuronRole::Input,
+            bias: 0.0,
+        }),
+        Event::RandomNeuronAdded(RandomNeuronAdded {
+            neuron_id: hidden,
+            activation: Activation::ReLU,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
+        }),
+    ];
+    let projection = NetworkProjection::from_events(&events);
+    let handler = QueryHandler::new(&projection);
+
+    match handler.handle(Query::ListNeuronsByRole {
+        role: NeuronRole::Input,
+    }) {
+        QueryResult::Neurons(neurons) => {
+            assert_eq!(neurons.len(), 1);
+            assert_eq!(neurons[0].id, input);
+        }
+        _ => panic!("unexpected result"),
+    }
+    assert!(handler.neurons_by_role(NeuronRole::Output).is_empty());
+}
@@ -0,0 +1,115 @@
+use aei_framework::{Activation, DomainNetwork, Event, NeuronAdded, NeuronRole, PropagationError};
+use uuid::Uuid;
+
+fn neuron(id: Uuid, role: NeuronRole) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: id,
+        activation: Activation::Identity,
+        role,
+        bias: 0.0,
+    })
+}
+
+fn synapse(id: Uuid, from: Uuid, to: Uuid, weight: f64) -> Event {
+    Event::SynapseCreated {
+        id,
+        from,
+        to,
+        weight,
+        innovation: None,
+    }
+}
+
+#[test]
+fn step_keeps_state_between_ticks_until_reset() {
+    let (input, acc) = (Uuid::new_v4(), Uuid::new_v4());
+    let self_loop = Uuid::new_v4();
+    let mut net = DomainNetwork::hydrate(&[
+        neuron(input, NeuronRole::Input),
+        neuron(acc, NeuronRole::Output),
+        synapse(Uuid::new_v4(), input, acc, 1.0),
+        synapse(self_loop, acc, acc, 0.5),
+    ]);
+    assert_eq!(
+        net.recurrent_synapses().into_iter().collect::<Vec<_>>(),
+        vec![self_loop]
+    );
+
+    let ticks: Vec<f64> = (0..3)
+        .map(|_| net.step(&[(input, 2.0)], &[acc]).unwrap()[0])
+        .collect();
+    assert_eq!(ticks, vec![2.0, 3.0, 3.5]);
+
+    net.reset_state();
+    assert!(net.neurons.values().all(|n| n.value == 0.0));
+    assert_eq!(net.step(&[(input, 2.0)], &[acc]).unwrap(), vec![2.0]);
+}
+
+#[test]
+fn step_reads_previous_tick_through_back_edges() {
+    let (input, a, b) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let back = Uuid::new_v4();
+    let mut net = DomainNetwork::hydrate(&[
+        neuron(input, NeuronRole::Input),
+        neuron(a, NeuronRole::Hidden),
+        neuron(b, NeuronRole::Output),
+        synapse(Uuid::new_v4(), input, a, 1.0),
+        synapse(Uuid::new_v4(), a, b, 1.0),
+        synapse(back, b, a, 10.0),
+    ]);
+    assert!(net.recurrent_synapses().contains(&back));
+    assert_eq!(
+        net.propagate(&[(input, 1.0)], &[b]),
+        Err(PropagationError::CycleDetected)
+    );
+
+    assert_eq!(net.step(&[(input, 1.0)], &[a, b]).unwrap(), vec![1.0, 1.0]);
+    assert_eq!(
+        net.step(&[(input, 0.0)], &[a, b]).unwrap(),
+        vec![10.0, 10.0]
+    );
+    assert_eq!(
+        net.step(&[(input, 0.0)], &[a, b]).unwrap(),
+        vec![100.0, 100.0]
+    );
+}
+
+#[test]
+fn step_matches_propagate_on_acyclic_networks() {
+    let (input, bias, hidden, out) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+    let events = [
+        neuron(input, NeuronRole::Input),
+        neuron(bias, NeuronRole::Bias),
+        neuron(hidden, NeuronRole::Hidden),
+        neuron(out, NeuronRole::Output),
+        synapse(Uuid::new_v4(), input, hidden, 0.5),
+        synapse(Uuid::new_v4(), bias, hidden, -1.0),
+        synapse(Uuid::new_v4(), hidden, out, 3.0),
+    ];
+    let mut forward = DomainNetwork::hydrate(&events);
+    let mut recurrent = DomainNetwork::hydrate(&events);
+    assert!(recurrent.recurrent_synapses().is_empty());
+
+    for x in [4.0, -2.0, 0.5] {
+        assert_eq!(
+            forward.propagate(&[(input, x)], &[out]).unwrap(),
+            recurrent.step(&[(input, x)], &[out]).unwrap()
+        );
+    }
+}
+
+#[test]
+fn step_rejects_unknown_neurons() {
+    let a = Uuid::new_v4();
+    let mut net = DomainNetwork::hydrate(&[neuron(a, NeuronRole::Input)]);
+    let unknown = Uuid::new_v4();
+    assert_eq!(
+        net.step(&[(a, 1.0)], &[unknown]),
+        Err(PropagationError::UnknownNeuron(unknown))
+    );
+}
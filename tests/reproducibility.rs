@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use aei_framework::{
+    AddRandomNeuronCommand, AddRandomNeuronHandler, AddRandomSynapseCommand,
+    AddRandomSynapseHandler, Event, FileEventStore, MutateRandomNeuronActivationCommand,
+    MutateRandomNeuronActivationHandler, MutateRandomNeuronBiasCommand,
+    MutateRandomNeuronBiasHandler, MutateRandomSynapseWeightCommand,
+    MutateRandomSynapseWeightHandler, RemoveRandomNeuronCommand, RemoveRandomNeuronHandler,
+    RunSeeded,
+};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_seeded_{}.log", Uuid::new_v4()));
+    path
+}
+
+/// Runs a fixed sequence of random commands, creating a fresh handler for
+/// each stage as an experiment driver would.
+fn run(path: &Path, seed: u64) {
+    let store = FileEventStore::bare(path.to_path_buf());
+    let mut add = AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(store, seed).unwrap();
+    for _ in 0..4 {
+        add.handle(AddRandomNeuronCommand::default()).unwrap();
+    }
+
+    let mut synapses =
+        AddRandomSynapseHandler::<_, ChaCha8Rng>::with_seed(add.base.store, seed).unwrap();
+    for _ in 0..3 {
+        synapses.handle(AddRandomSynapseCommand).unwrap();
+    }
+
+    let mut weights =
+        MutateRandomSynapseWeightHandler::<_, ChaCha8Rng>::with_seed(synapses.base.store, seed)
+            .unwrap();
+    weights
+        .handle(MutateRandomSynapseWeightCommand { std_dev: 0.5 })
+        .unwrap();
+
+    let mut activations =
+        MutateRandomNeuronActivationHandler::<_, ChaCha8Rng>::with_seed(weights.base.store, seed)
+            .unwrap();
+    activations
+        .handle(MutateRandomNeuronActivationCommand::default())
+        .unwrap();
+
+    let mut biases =
+        MutateRandomNeuronBiasHandler::<_, ChaCha8Rng>::with_seed(activations.base.store, seed)
+            .unwrap();
+    biases
+        .handle(MutateRandomNeuronBiasCommand { std_dev: 0.2 })
+        .unwrap();
+
+    let mut remove =
+        RemoveRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(biases.base.store, seed).unwrap();
+    remove.handle(RemoveRandomNeuronCommand).unwrap();
+}
+
+#[test]
+fn same_seed_writes_identical_logs() {
+    let (first, second, other) = (temp_path(), temp_path(), temp_path());
+    run(&first, 7);
+    run(&second, 7);
+    run(&other, 8);
+
+    let bytes = std::fs::read(&first).unwrap();
+    assert!(!bytes.is_empty());
+    assert_eq!(bytes, std::fs::read(&second).unwrap());
+    assert_ne!(bytes, std::fs::read(&other).unwrap());
+
+    let events = FileEventStore::new(first).load().unwrap();
+    assert!(matches!(
+        events.first(),
+        Some(Event::RunSeeded(RunSeeded { seed: 7 }))
+    ));
+    let seeds = events
+        .iter()
+        .filter(|e| matches!(e, Event::RunSeeded(_)))
+        .count();
+    assert_eq!(seeds, 1);
+}
+
+#[test]
+fn handlers_on_the_same_run_draw_distinct_ids() {
+    let path = temp_path();
+    let store = FileEventStore::bare(path.clone());
+    let mut first = AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(store, 3).unwrap();
+    let a = first.handle(AddRandomNeuronCommand::default()).unwrap();
+    let mut second =
+        AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(first.base.store, 3).unwrap();
+    let b = second.handle(AddRandomNeuronCommand::default()).unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a.get_version_num(), 4);
+}
+
+#[test]
+fn resumed_run_keeps_recorded_seed() {
+    let (path, expected) = (temp_path(), temp_path());
+    let store = FileEventStore::bare(expected.clone());
+    let mut handler = AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(store, 11).unwrap();
+    handler.handle(AddRandomNeuronCommand::default()).unwrap();
+    let mut handler =
+        AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(handler.base.store, 11).unwrap();
+    handler.handle(AddRandomNeuronCommand::default()).unwrap();
+
+    let store = FileEventStore::bare(path.clone());
+    let mut handler = AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(store, 11).unwrap();
+    handler.handle(AddRandomNeuronCommand::default()).unwrap();
+    drop(handler);
+    // A different seed passed on resume is ignored in favour of the log.
+    let store = FileEventStore::bare(path.clone());
+    let mut handler = AddRandomNeuronHandler::<_, ChaCha8Rng>::with_seed(store, 99).unwrap();
+    handler.handle(AddRandomNeuronCommand::default()).unwrap();
+
+    assert_eq!(
+        std::fs::read(path).unwrap(),
+        std::fs::read(expected).unwrap()
+    );
+}
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use aei_framework::{
     application::{Query, QueryHandler, QueryResult},
-    domain::{Event, RandomNeuronAdded, RandomSynapseAdded, SynapseWeightSet},
+    domain::{Event, NeuronRole, RandomNeuronAdded, RandomSynapseAdded, SynapseWeightSet},
     infrastructure::{projection::NetworkProjection, FileEventStore},
     Activation, SetSynapseWeightCommand, SetSynapseWeightHandler,
 };
@@ -19,16 +19,21 @@ fn seed_network(store: &mut FileEventStore, n1: Uuid, n2: Uuid, syn: Uuid) {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomSynapseAdded(RandomSynapseAdded {
             synapse_id: syn,
             from: n1,
             to: n2,
             weight: 1.0,
+            innovation: None,
         }),
     ];
     for e in &events {
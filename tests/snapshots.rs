@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use aei_framework::infrastructure::projection::{
+    CuriosityScoreProjection, MemoryProjection, NetworkProjection, PopulationProjection,
+};
+use aei_framework::{
+    Activation, AddMemoryEntryCommand, AddMemoryEntryHandler, AddRandomNeuronCommand,
+    AddRandomSynapseCommand, Command, CommandHandler, CuriosityScoreUpdated, Event, FileEventStore,
+    FileMemoryEventStore, FileSnapshotStore, FitnessEvaluated, MemberAdded, MemberRetired,
+    MemoryHandlerBase, MutateRandomSynapseWeightCommand, Mutation, MutationPolicy,
+    NetworkHandlerBase, NeuronAdded, NeuronRemoved, NeuronRole, Population, PopulationEvent,
+    RemoveRandomSynapseCommand, Snapshot, SnapshotPolicy, SnapshotStore, SplitRandomSynapseCommand,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+fn temp_path(extension: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_snapshots_{}.{extension}", Uuid::new_v4()));
+    path
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).unwrap() == serde_json::to_value(b).unwrap()
+}
+
+#[test]
+fn snapshot_and_tail_match_a_full_replay() {
+    let (log, snapshot) = (temp_path("log"), temp_path("json"));
+    let mut snapshots = FileSnapshotStore::new(snapshot.clone());
+    let mut base = NetworkHandlerBase::new(
+        FileEventStore::new(log.clone()),
+        ChaCha8Rng::seed_from_u64(7),
+    )
+    .unwrap();
+    let policy = MutationPolicy::new()
+        .with_operator(0.6, Mutation::AddNeuron(AddRandomNeuronCommand::default()))
+        .with_operator(0.8, Mutation::AddSynapse(AddRandomSynapseCommand))
+        .with_operator(
+            0.8,
+            Mutation::MutateSynapseWeight(MutateRandomSynapseWeightCommand { std_dev: 0.5 }),
+        )
+        .with_operator(0.3, Mutation::RemoveSynapse(RemoveRandomSynapseCommand))
+        .with_operator(
+            0.3,
+            Mutation::SplitSynapse(SplitRandomSynapseCommand::default()),
+        );
+    let mut taken = Vec::new();
+    for _ in 0..40 {
+        policy.step(&mut base).unwrap();
+        if base
+            .snapshot_if_due(&mut snapshots, SnapshotPolicy::EveryEvents(30))
+            .unwrap()
+        {
+            taken.push(base.version());
+        }
+    }
+    // Leave a tail after the latest snapshot.
+    while base.version() == *taken.last().unwrap() {
+        policy.step(&mut base).unwrap();
+    }
+
+    let latest = snapshots.load_latest().unwrap().unwrap();
+    assert!(taken.len() > 1);
+    assert!(taken.windows(2).all(|pair| pair[1] - pair[0] >= 30));
+    assert_eq!(Some(&latest.version), taken.last());
+    assert!(latest.version < base.version());
+
+    // Restoring the snapshot and replaying the tail gives the state of a
+    // full replay, innovation counter included.
+    let restored = NetworkHandlerBase::from_snapshot(
+        FileEventStore::new(log.clone()),
+        ChaCha8Rng::seed_from_u64(8),
+        Some(latest.clone()),
+    )
+    .unwrap();
+    let full = NetworkHandlerBase::new(
+        FileEventStore::new(log.clone()),
+        ChaCha8Rng::seed_from_u64(8),
+    )
+    .unwrap();
+    assert!(same(&restored.network, &full.network));
+    assert!(same(&restored.network, &base.network));
+    assert_eq!(restored.version(), full.version());
+    assert_eq!(restored.innovations.peek(), full.innovations.peek());
+
+    let mut store = FileEventStore::new(log.clone());
+    let tail = store.load_from(latest.version).unwrap();
+    let projection = NetworkProjection::from_snapshot(latest.state.network, &tail);
+    let replayed = NetworkProjection::from_events(&store.load().unwrap());
+    assert!(same(&projection.neurons(), &replayed.neurons()));
+    assert!(same(&projection.synapses(), &replayed.synapses()));
+    std::fs::remove_file(log).unwrap();
+    std::fs::remove_file(snapshot).unwrap();
+}
+
+#[test]
+fn command_handlers_snapshot_on_demand_or_periodically() {
+    let (log, snapshot) = (temp_path("log"), temp_path("json"));
+    let mut snapshots = FileSnapshotStore::new(snapshot.clone());
+    let mut handler = CommandHandler::new(FileEventStore::new(log.clone())).unwrap();
+    let ids: Vec<Uuid> = (0..10).map(|_| Uuid::new_v4()).collect();
+    for (i, id) in ids.iter().enumerate() {
+        handler
+            .handle(Command::CreateNeuron {
+                id: *id,
+                activation: Activation::Identity,
+                role: NeuronRole::Hidden,
+                bias: i as f64,
+            })
+            .unwrap();
+        handler
+            .snapshot_if_due(&mut snapshots, SnapshotPolicy::EveryEvents(4))
+            .unwrap();
+    }
+    assert_eq!(snapshots.load_latest().unwrap().unwrap().version, 8);
+
+    handler
+        .handle(Command::CreateSynapse {
+            id: Uuid::new_v4(),
+            from: ids[0],
+            to: ids[1],
+            weight: 0.5,
+        })
+        .unwrap();
+    handler.save_snapshot(&mut snapshots).unwrap();
+    let latest = snapshots.load_latest().unwrap().unwrap();
+    assert_eq!(latest.version, 11);
+    assert!(!SnapshotPolicy::OnDemand.is_due(0, latest.version));
+
+    handler
+        .handle(Command::RemoveNeuron { id: ids[2] })
+        .unwrap();
+    let restored =
+        CommandHandler::from_snapshot(FileEventStore::new(log.clone()), Some(latest)).unwrap();
+    assert!(same(&restored.network, &handler.network));
+    assert_eq!(restored.innovations.peek(), 1);
+    std::fs::remove_file(log).unwrap();
+    std::fs::remove_file(snapshot).unwrap();
+}
+
+#[test]
+fn memory_snapshots_match_a_full_replay() {
+    let (log, snapshot) = (temp_path("log"), temp_path("json"));
+    let mut snapshots = FileSnapshotStore::new(snapshot.clone());
+    let mut handler =
+        AddMemoryEntryHandler::new(FileMemoryEventStore::new(log.clone()), 3).unwrap();
+    for i in 0..8 {
+        handler
+            .handle(AddMemoryEntryCommand {
+                event_type: "step".into(),
+                payload: json!({ "step": i }),
+                score: f64::from(i % 4) / 4.0,
+            })
+            .unwrap();
+        if i == 4 {
+            handler.base.save_snapshot(&mut snapshots).unwrap();
+        }
+    }
+
+    let latest = snapshots.load_latest().unwrap().unwrap();
+    assert!(latest.version > 0);
+    let restored = MemoryHandlerBase::from_snapshot(
+        FileMemoryEventStore::new(log.clone()),
+        3,
+        Some(latest.clone()),
+    )
+    .unwrap();
+    let full = MemoryHandlerBase::new(FileMemoryEventStore::new(log.clone()), 3).unwrap();
+    assert!(same(&restored.memory, &full.memory));
+    assert!(same(&restored.memory, &handler.base.memory));
+
+    let mut store = FileMemoryEventStore::new(log.clone());
+    let tail = store.load_from(latest.version).unwrap();
+    let projection = MemoryProjection::from_snapshot(latest.state, &tail);
+    let replayed = MemoryProjection::from_events(3, &store.load().unwrap());
+    assert!(same(&projection.entries(), &replayed.entries()));
+    std::fs::remove_file(log).unwrap();
+    std::fs::remove_file(snapshot).unwrap();
+}
+
+#[test]
+fn read_models_resume_from_a_snapshot() {
+    let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let scored = |target_id, new_score| {
+        Event::CuriosityScoreUpdated(CuriosityScoreUpdated {
+            target_id,
+            old_score: 0.0,
+            new_score,
+            strategy: "rarity".into(),
+        })
+    };
+    let mut events: Vec<Event> = [a, b, c]
+        .map(|neuron_id| {
+            Event::NeuronAdded(NeuronAdded {
+                neuron_id,
+                activation: Activation::Identity,
+                role: NeuronRole::Hidden,
+                bias: 0.0,
+            })
+        })
+        .into();
+    events.push(scored(c, 0.75));
+    events.push(Event::NeuronRemoved(NeuronRemoved { neuron_id: c }));
+    events.push(scored(a, 0.5));
+    events.push(scored(a, 0.25));
+
+    // The snapshot holds the scores of the projection itself, so targets
+    // never scored stay absent and removed ones keep their last score.
+    let path = temp_path("json");
+    let mut snapshots = FileSnapshotStore::new(path.clone());
+    let (head, tail) = events.split_at(6);
+    let state = CuriosityScoreProjection::from_events(head).scores().clone();
+    snapshots
+        .save(&Snapshot {
+            version: head.len() as u64,
+            state,
+        })
+        .unwrap();
+    let latest: Snapshot<HashMap<Uuid, f64>> = snapshots.load_latest().unwrap().unwrap();
+    let scores = CuriosityScoreProjection::from_snapshot(latest.state, tail);
+    let replayed = CuriosityScoreProjection::from_events(&events);
+    assert_eq!(scores.scores(), replayed.scores());
+    assert_eq!(scores.get(a), Some(0.25));
+    assert_eq!(scores.get(b), None);
+    assert_eq!(scores.get(c), Some(0.75));
+    std::fs::remove_file(path).unwrap();
+
+    let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+    let events = vec![
+        PopulationEvent::MemberAdded(MemberAdded {
+            member_id: first,
+            parents: Vec::new(),
+            generation: 0,
+        }),
+        PopulationEvent::FitnessEvaluated(FitnessEvaluated {
+            member_id: first,
+            generation: 0,
+            fitness: 1.0,
+        }),
+        PopulationEvent::MemberAdded(MemberAdded {
+            member_id: second,
+            parents: vec![first],
+            generation: 0,
+        }),
+        PopulationEvent::MemberRetired(MemberRetired {
+            member_id: first,
+            generation: 0,
+        }),
+    ];
+    let (head, tail) = events.split_at(2);
+    let population = PopulationProjection::from_snapshot(Population::hydrate(head), tail);
+    let replayed = PopulationProjection::from_events(&events);
+    assert_eq!(population.members(), replayed.members());
+    assert_eq!(population.member(second).unwrap().parents, [first]);
+    assert!(population.member(first).is_none());
+}
@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+
+use aei_framework::infrastructure::projection::PopulationProjection;
+use aei_framework::{
+    Activation, CompatibilityCoefficients, DomainNetwork, Event, EvolvePopulationCommand,
+    FileEventStore, FilePopulationEventStore, NeuronAdded, NeuronRole, PopulationError,
+    PopulationEvent, PopulationHandler, PopulationQuery, PopulationQueryHandler,
+    PopulationQueryResult, Selection, SpeciationConfig,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aei_speciation_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+type Handler = PopulationHandler<
+    FilePopulationEventStore,
+    FileEventStore,
+    Box<dyn FnMut(Uuid) -> FileEventStore>,
+    ChaCha8Rng,
+>;
+
+fn handler(dir: &Path) -> Handler {
+    let store = FilePopulationEventStore::new(dir.join("population.log"));
+    let streams = dir.to_path_buf();
+    let open: Box<dyn FnMut(Uuid) -> FileEventStore> =
+        Box::new(move |id| FileEventStore::new(streams.join(format!("{id}.log"))));
+    PopulationHandler::new(store, open, ChaCha8Rng::seed_from_u64(7)).unwrap()
+}
+
+fn load(dir: &Path) -> Vec<PopulationEvent> {
+    FilePopulationEventStore::new(dir.join("population.log"))
+        .load()
+        .unwrap()
+}
+
+/// Four neurons shared by every genome, joined by the given synapses.
+struct Genomes {
+    neurons: [Uuid; 4],
+}
+
+impl Genomes {
+    fn new() -> Self {
+        Self {
+            neurons: [
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+                Uuid::new_v4(),
+            ],
+        }
+    }
+
+    /// Builds a genome with one synapse per `(from, to, weight, innovation)`
+    /// entry, the endpoints being indices into the shared neurons.
+    fn events(&self, synapses: &[(usize, usize, f64, Option<u64>)]) -> Vec<Event> {
+        let mut events: Vec<Event> = self
+            .neurons
+            .iter()
+            .map(|id| {
+                Event::NeuronAdded(NeuronAdded {
+                    neuron_id: *id,
+                    activation: Activation::Identity,
+                    role: NeuronRole::Hidden,
+                    bias: 0.0,
+                })
+            })
+            .collect();
+        events.extend(synapses.iter().map(|(from, to, weight, innovation)| {
+            Event::SynapseCreated {
+                id: Uuid::new_v4(),
+                from: self.neurons[*from],
+                to: self.neurons[*to],
+                weight: *weight,
+                innovation: *innovation,
+            }
+        }));
+        events
+    }
+
+    fn network(&self, synapses: &[(usize, usize, f64, Option<u64>)]) -> DomainNetwork {
+        DomainNetwork::hydrate(&self.events(synapses))
+    }
+
+    /// One-synapse genome of the first cluster.
+    fn small(&self) -> Vec<Event> {
+        self.events(&[(0, 1, 0.5, Some(0))])
+    }
+
+    /// Three-synapse genome of the second cluster, sharing no gene with
+    /// the first.
+    fn large(&self) -> Vec<Event> {
+        self.events(&[
+            (1, 2, 0.5, Some(5)),
+            (2, 3, 0.5, Some(6)),
+            (3, 0, 0.5, Some(7)),
+        ])
+    }
+}
+
+#[test]
+fn distance_counts_excess_disjoint_and_weight_differences() {
+    let genomes = Genomes::new();
+    let a = genomes.network(&[
+        (0, 1, 1.0, Some(0)),
+        (0, 2, 0.5, Some(1)),
+        (0, 3, 0.0, Some(4)),
+    ]);
+    let b = genomes.network(&[(0, 1, 0.0, Some(0)), (1, 2, 0.5, Some(2))]);
+    // Innovation 4 lies beyond the highest number of `b` (excess), while
+    // 1 and 2 are disjoint; the matching gene differs by 1.0 in weight.
+    let only = |excess, disjoint, weight| CompatibilityCoefficients {
+        excess,
+        disjoint,
+        weight,
+    };
+    assert!((only(3.0, 0.0, 0.0).distance(&a, &b) - 1.0).abs() < 1e-12);
+    assert!((only(0.0, 3.0, 0.0).distance(&a, &b) - 2.0).abs() < 1e-12);
+    assert!((only(0.0, 0.0, 1.0).distance(&a, &b) - 1.0).abs() < 1e-12);
+
+    let coefficients = CompatibilityCoefficients::default();
+    let distance = coefficients.distance(&a, &b);
+    assert!((distance - 1.4).abs() < 1e-12, "{distance}");
+    assert_eq!(distance, coefficients.distance(&b, &a));
+    assert_eq!(coefficients.distance(&a, &a), 0.0);
+
+    // Synapses without innovation numbers match by connection and are
+    // otherwise disjoint.
+    let c = genomes.network(&[(0, 1, 1.0, None), (1, 2, 1.0, None)]);
+    let d = genomes.network(&[(0, 1, 1.0, None)]);
+    assert!((only(1.0, 0.0, 0.0).distance(&c, &d)).abs() < 1e-12);
+    assert!((only(0.0, 1.0, 0.0).distance(&c, &d) - 0.5).abs() < 1e-12);
+    assert!(!only(-1.0, 1.0, 1.0).is_valid());
+}
+
+#[test]
+fn speciation_groups_members_and_records_events() {
+    let dir = temp_dir();
+    let genomes = Genomes::new();
+    let mut population = handler(&dir);
+    let small: Vec<Uuid> = (0..3)
+        .map(|_| population.add_member(&genomes.small()).unwrap())
+        .collect();
+    let large = population.add_member(&genomes.large()).unwrap();
+
+    let config = SpeciationConfig {
+        threshold: 1.0,
+        ..SpeciationConfig::default()
+    };
+    assert_eq!(population.speciate(&config).unwrap(), 2);
+
+    let events = load(&dir);
+    let created = events
+        .iter()
+        .filter(|e| matches!(e, PopulationEvent::SpeciesCreated(_)))
+        .count();
+    assert_eq!(created, 2);
+    let assigned: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            PopulationEvent::SpeciesAssigned(a) => Some(a),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(assigned.len(), 4);
+    assert!(assigned.iter().all(|a| a.generation == 0));
+
+    let projection = PopulationProjection::from_events(&events);
+    let queries = PopulationQueryHandler::new(&projection);
+    let species_of = |id| match queries.handle(PopulationQuery::GetMemberSpecies { id }) {
+        PopulationQueryResult::MemberSpecies(species) => species.unwrap().id,
+        _ => unreachable!(),
+    };
+    let shared = species_of(small[0]);
+    assert!(small.iter().all(|id| species_of(*id) == shared));
+    assert_ne!(species_of(large), shared);
+    match queries.handle(PopulationQuery::ListSpeciesMembers { species_id: shared }) {
+        PopulationQueryResult::SpeciesMembers(members) => assert_eq!(members.len(), 3),
+        _ => unreachable!(),
+    }
+    match queries.handle(PopulationQuery::ListSpecies) {
+        PopulationQueryResult::Species(species) => {
+            let representatives: Vec<Uuid> = species.iter().map(|s| s.representative_id).collect();
+            assert!(representatives.contains(&large));
+        }
+        _ => unreachable!(),
+    }
+
+    // Speciating again keeps the species and their representatives.
+    assert_eq!(population.speciate(&config).unwrap(), 2);
+    let resumed = handler(&dir);
+    assert_eq!(resumed.population.species, population.population.species);
+    assert_eq!(resumed.population.members, population.population.members);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn threshold_moves_towards_target_species() {
+    let dir = temp_dir();
+    let genomes = Genomes::new();
+    let mut population = handler(&dir);
+    population.add_member(&genomes.small()).unwrap();
+    population.add_member(&genomes.large()).unwrap();
+
+    let config = SpeciationConfig {
+        threshold: 1.0,
+        target_species: Some(1),
+        threshold_step: 2.0,
+        ..SpeciationConfig::default()
+    };
+    assert_eq!(population.speciate(&config).unwrap(), 2);
+    assert_eq!(population.population.compatibility_threshold, Some(3.0));
+
+    // With the raised threshold every member joins the oldest species and
+    // the other one dies out.
+    assert_eq!(population.speciate(&config).unwrap(), 1);
+    let events = load(&dir);
+    let extinct: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            PopulationEvent::SpeciesExtinct(e) => Some(e.species_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(extinct, vec![1]);
+    let adjustments: Vec<(f64, f64)> = events
+        .iter()
+        .filter_map(|e| match e {
+            PopulationEvent::CompatibilityThresholdAdjusted(e) => {
+                Some((e.old_threshold, e.new_threshold))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(adjustments, vec![(1.0, 3.0)]);
+
+    // Too few species lower the threshold, never below the step.
+    let config = SpeciationConfig {
+        target_species: Some(3),
+        ..config
+    };
+    population.speciate(&config).unwrap();
+    assert_eq!(population.population.compatibility_threshold, Some(2.0));
+    assert_eq!(config.adjust(2.5, 1), 2.0);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn fitness_sharing_protects_small_species() {
+    let genomes = Genomes::new();
+    let fitness = |net: &mut DomainNetwork| {
+        if net.synapses.len() == 1 {
+            1.0
+        } else {
+            0.9
+        }
+    };
+    let evolve = |speciation: Option<SpeciationConfig>| {
+        let dir = temp_dir();
+        let mut population = handler(&dir);
+        for _ in 0..3 {
+            population.add_member(&genomes.small()).unwrap();
+        }
+        let large = population.add_member(&genomes.large()).unwrap();
+        let cmd = EvolvePopulationCommand {
+            selection: Selection::Truncation { fraction: 0.25 },
+            elitism: 0,
+            crossover_rate: 0.0,
+            mutations: Vec::new(),
+            speciation,
+        };
+        population.evolve(&cmd, fitness).unwrap();
+        let from_large = load(&dir)
+            .iter()
+            .filter(|e| matches!(e, PopulationEvent::MemberAdded(a) if a.parents == [large]))
+            .count();
+        std::fs::remove_dir_all(dir).unwrap();
+        from_large
+    };
+
+    // On raw fitness the crowded species wins every selection; shared
+    // between three members, its fitness drops below the lone genome's.
+    assert_eq!(evolve(None), 0);
+    let config = SpeciationConfig {
+        threshold: 1.0,
+        ..SpeciationConfig::default()
+    };
+    assert_eq!(evolve(Some(config)), 4);
+}
+
+#[test]
+fn invalid_speciation_is_rejected() {
+    let dir = temp_dir();
+    let genomes = Genomes::new();
+    let mut population = handler(&dir);
+    population.add_member(&genomes.small()).unwrap();
+    let config = SpeciationConfig {
+        threshold: 0.0,
+        ..SpeciationConfig::default()
+    };
+    assert_eq!(
+        population.speciate(&config),
+        Err(PopulationError::InvalidSpeciation)
+    );
+    let cmd = EvolvePopulationCommand {
+        selection: Selection::default(),
+        elitism: 0,
+        crossover_rate: 0.0,
+        mutations: Vec::new(),
+        speciation: Some(SpeciationConfig {
+            target_species: Some(0),
+            ..SpeciationConfig::default()
+        }),
+    };
+    assert_eq!(
+        population.evolve(&cmd, |_| 1.0),
+        Err(PopulationError::InvalidSpeciation)
+    );
+    assert!(load(&dir)
+        .iter()
+        .all(|e| matches!(e, PopulationEvent::MemberAdded(_))));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, DomainNetwork, Event, FileEventStore, NeuronAdded, NeuronRole,
+    SplitRandomSynapseCommand, SplitRandomSynapseError, SplitRandomSynapseHandler, SynapseSplit,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_split_{}.log", Uuid::new_v4()));
+    path
+}
+
+fn neuron(id: Uuid, activation: Activation, role: NeuronRole) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: id,
+        activation,
+        role,
+        bias: 0.0,
+    })
+}
+
+#[test]
+fn split_preserves_outputs_and_disables_synapse() {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path.clone());
+    let (input, output, synapse) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    for event in [
+        neuron(input, Activation::Identity, NeuronRole::Input),
+        neuron(output, Activation::Sigmoid, NeuronRole::Output),
+        Event::SynapseCreated {
+            id: synapse,
+            from: input,
+            to: output,
+            weight: -0.8,
+            innovation: None,
+        },
+    ] {
+        store.append(&event).unwrap();
+    }
+
+    let rng = ChaCha8Rng::seed_from_u64(21);
+    let mut handler = SplitRandomSynapseHandler::new(store, rng).unwrap();
+    let before: Vec<f64> = [-1.5, 0.0, 2.0]
+        .iter()
+        .map(|x| {
+            handler
+                .base
+                .network
+                .propagate(&[(input, *x)], &[output])
+                .unwrap()[0]
+        })
+        .collect();
+
+    let inserted = handler
+        .handle(SplitRandomSynapseCommand::default())
+        .unwrap();
+
+    let network = &mut handler.base.network;
+    assert!(!network.synapses[&synapse].enabled);
+    assert_eq!(network.synapses[&synapse].weight, -0.8);
+    assert_eq!(network.neurons[&inserted].role, NeuronRole::Hidden);
+    assert_eq!(network.neurons[&inserted].activation, Activation::Identity);
+    let incoming = network
+        .synapses
+        .values()
+        .find(|s| s.from == input && s.to == inserted)
+        .unwrap();
+    assert_eq!(incoming.weight, 1.0);
+    let outgoing = network
+        .synapses
+        .values()
+        .find(|s| s.from == inserted && s.to == output)
+        .unwrap();
+    assert_eq!(outgoing.weight, -0.8);
+    let after: Vec<f64> = [-1.5, 0.0, 2.0]
+        .iter()
+        .map(|x| network.propagate(&[(input, *x)], &[output]).unwrap()[0])
+        .collect();
+    assert_eq!(before, after);
+
+    let events = FileEventStore::new(path).load().unwrap();
+    assert_eq!(events.len(), 4);
+    match events.last().unwrap() {
+        Event::SynapseSplit(SynapseSplit {
+            synapse_id,
+            neuron_id,
+            weight,
+            ..
+        }) => {
+            assert_eq!(*synapse_id, synapse);
+            assert_eq!(*neuron_id, inserted);
+            assert_eq!(*weight, -0.8);
+        }
+        e => panic!("unexpected event {e:?}"),
+    }
+    let replayed = DomainNetwork::hydrate(&events);
+    assert_eq!(replayed.neurons.len(), 3);
+    assert_eq!(
+        replayed.synapses.keys().collect::<Vec<_>>(),
+        handler.base.network.synapses.keys().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn split_uses_requested_activation() {
+    let mut store = FileEventStore::new(temp_path());
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    for event in [
+        neuron(a, Activation::Identity, NeuronRole::Input),
+        neuron(b, Activation::Identity, NeuronRole::Output),
+        Event::SynapseCreated {
+            id: Uuid::new_v4(),
+            from: a,
+            to: b,
+            weight: 0.5,
+            innovation: None,
+        },
+    ] {
+        store.append(&event).unwrap();
+    }
+    let rng = ChaCha8Rng::seed_from_u64(22);
+    let mut handler = SplitRandomSynapseHandler::new(store, rng).unwrap();
+    let inserted = handler
+        .handle(SplitRandomSynapseCommand {
+            activation: Activation::Tanh,
+        })
+        .unwrap();
+    assert_eq!(
+        handler.base.network.neurons[&inserted].activation,
+        Activation::Tanh
+    );
+    assert_eq!(handler.base.network.synapses.len(), 3);
+    assert_eq!(handler.base.network.enabled_synapses().count(), 2);
+
+    // The disabled synapse is never split again.
+    let second = handler
+        .handle(SplitRandomSynapseCommand::default())
+        .unwrap();
+    assert_ne!(second, inserted);
+    assert_eq!(handler.base.network.enabled_synapses().count(), 3);
+}
+
+#[test]
+fn split_fails_without_synapses() {
+    let mut store = FileEventStore::new(temp_path());
+    store
+        .append(&neuron(
+            Uuid::new_v4(),
+            Activation::Identity,
+            NeuronRole::Input,
+        ))
+        .unwrap();
+    let rng = ChaCha8Rng::seed_from_u64(23);
+    let mut handler = SplitRandomSynapseHandler::new(store, rng).unwrap();
+    assert_eq!(
+        handler.handle(SplitRandomSynapseCommand::default()),
+        Err(SplitRandomSynapseError::NoSynapseAvailable)
+    );
+}
@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, AddMemoryEntryCommand, AddMemoryEntryHandler, AddRandomNeuronCommand,
+    AddRandomNeuronHandler, AppendError, DirectoryEventStore, DirectoryMemoryEventStore,
+    DomainNetwork, Event, EventStore, NeuronAdded, NeuronRole, RemoveRandomNeuronCommand,
+    RemoveRandomNeuronHandler, StreamEventStore,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::json;
+use uuid::Uuid;
+
+fn temp_dir() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_streams_{}", Uuid::new_v4()));
+    path
+}
+
+fn neuron(bias: f64) -> Event {
+    Event::NeuronAdded(NeuronAdded {
+        neuron_id: Uuid::new_v4(),
+        activation: Activation::Identity,
+        role: NeuronRole::Hidden,
+        bias,
+    })
+}
+
+fn bias(event: &Event) -> f64 {
+    match event {
+        Event::NeuronAdded(e) => e.bias,
+        _ => panic!("unexpected event"),
+    }
+}
+
+/// Appends through the stream-aware API only.
+fn append<S: StreamEventStore<Event>>(store: &mut S, stream_id: Uuid, biases: &[f64]) {
+    let events: Vec<Event> = biases.iter().map(|b| neuron(*b)).collect();
+    assert!(store.append(stream_id, &events).is_ok());
+}
+
+#[test]
+fn streams_are_kept_apart_and_merged_in_append_order() {
+    let dir = temp_dir();
+    let mut store = DirectoryEventStore::new(dir.clone());
+    assert!(store.list_streams().unwrap().is_empty());
+    assert!(store.load_all().unwrap().is_empty());
+
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    append(&mut store, a, &[0.0, 1.0]);
+    append(&mut store, b, &[2.0]);
+    append(&mut store, a, &[3.0]);
+    append(&mut store, b, &[4.0, 5.0]);
+
+    let biases = |events: Vec<Event>| events.iter().map(bias).collect::<Vec<_>>();
+    assert_eq!(biases(store.load(a).unwrap()), [0.0, 1.0, 3.0]);
+    assert_eq!(biases(store.load(b).unwrap()), [2.0, 4.0, 5.0]);
+    assert!(store.load(Uuid::new_v4()).unwrap().is_empty());
+
+    let mut expected = vec![a, b];
+    expected.sort();
+    assert_eq!(store.list_streams().unwrap(), expected);
+
+    let all = store.load_all().unwrap();
+    let streams: Vec<Uuid> = all.iter().map(|(id, _)| *id).collect();
+    assert_eq!(streams, [a, a, b, a, b, b]);
+    for (i, (_, envelope)) in all.iter().enumerate() {
+        assert_eq!(envelope.sequence, i as u64);
+        assert_eq!(bias(&envelope.event), i as f64);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn stream_appends_check_the_stream_version() {
+    let dir = temp_dir();
+    let streams = DirectoryEventStore::new(dir.clone());
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut first = streams.stream(a);
+    let mut second = streams.stream(a);
+    let mut other = streams.stream(b);
+    assert_eq!(first.append_expected(&[neuron(0.0)], 0).unwrap(), 1);
+    // Other streams do not move the version of `a`.
+    assert_eq!(other.append_expected(&[neuron(1.0)], 0).unwrap(), 1);
+
+    assert!(matches!(
+        second.append_expected(&[neuron(2.0)], 0),
+        Err(AppendError::Conflict {
+            expected: 0,
+            actual: 1
+        })
+    ));
+    assert_eq!(second.version().unwrap(), 1);
+    assert_eq!(second.append_expected(&[neuron(2.0)], 1).unwrap(), 2);
+    assert_eq!(first.load().unwrap().len(), 2);
+    assert_eq!(first.load_from(1).unwrap().len(), 1);
+    assert_eq!(
+        DirectoryEventStore::new(dir.clone())
+            .load_all()
+            .unwrap()
+            .len(),
+        3
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn handlers_are_built_against_a_stream() {
+    let dir = temp_dir();
+    let streams = DirectoryEventStore::new(dir.clone());
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut first =
+        AddRandomNeuronHandler::new(streams.stream(a), ChaCha8Rng::seed_from_u64(1)).unwrap();
+    let mut second =
+        AddRandomNeuronHandler::new(streams.stream(b), ChaCha8Rng::seed_from_u64(2)).unwrap();
+    for _ in 0..3 {
+        first.handle(AddRandomNeuronCommand::default()).unwrap();
+    }
+    second.handle(AddRandomNeuronCommand::default()).unwrap();
+    assert_eq!(first.base.store.stream_id(), a);
+
+    // A handler on the same stream replays only that stream.
+    let mut remover =
+        RemoveRandomNeuronHandler::new(streams.stream(a), ChaCha8Rng::seed_from_u64(3)).unwrap();
+    assert!(remover
+        .base
+        .network
+        .neurons
+        .keys()
+        .eq(first.base.network.neurons.keys()));
+    remover.handle(RemoveRandomNeuronCommand).unwrap();
+
+    let mut store = DirectoryEventStore::new(dir.clone());
+    let network = DomainNetwork::hydrate(&store.load(a).unwrap());
+    assert_eq!(network.neurons.len(), 2);
+    assert_eq!(
+        DomainNetwork::hydrate(&store.load(b).unwrap())
+            .neurons
+            .len(),
+        1
+    );
+    let mut stream = streams.stream(b);
+    assert_eq!(
+        EventStore::version(&mut stream).unwrap(),
+        second.base.version()
+    );
+
+    let mut memories = DirectoryMemoryEventStore::new(dir.join("memories"));
+    let mut memory = AddMemoryEntryHandler::new(memories.stream(a), 4).unwrap();
+    memory
+        .handle(AddMemoryEntryCommand {
+            event_type: "step".into(),
+            payload: json!({ "step": 0 }),
+            score: 0.5,
+        })
+        .unwrap();
+    assert_eq!(memories.list_streams().unwrap(), [a]);
+    assert_eq!(memories.load(a).unwrap().len(), 1);
+    assert_eq!(memory.base.memory.entries.len(), 1);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use aei_framework::{
     Activation, AddRandomSynapseCommand, AddRandomSynapseError, AddRandomSynapseHandler, Event,
-    FileEventStore, RandomNeuronAdded, RandomSynapseAdded, RandomSynapseRemoved,
+    FileEventStore, NeuronRole, RandomNeuronAdded, RandomSynapseAdded, RandomSynapseRemoved,
     RemoveRandomSynapseCommand, RemoveRandomSynapseError, RemoveRandomSynapseHandler,
 };
 use rand::SeedableRng;
@@ -20,10 +20,14 @@ fn seed_two_neurons(store: &mut FileEventStore, n1: Uuid, n2: Uuid) {
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n1,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
         Event::RandomNeuronAdded(RandomNeuronAdded {
             neuron_id: n2,
             activation: Activation::Identity,
+            role: NeuronRole::Hidden,
+            bias: 0.0,
         }),
     ];
     for e in &events {
@@ -37,6 +41,7 @@ fn seed_synapse(store: &mut FileEventStore, id: Uuid, from: Uuid, to: Uuid) {
         from,
         to,
         weight: 1.0,
+        innovation: None,
     };
     store.append(&event).unwrap();
 }
@@ -72,6 +77,8 @@ fn add_random_synapse_requires_two_neurons() {
     let event = Event::RandomNeuronAdded(RandomNeuronAdded {
         neuron_id: n1,
         activation: Activation::Identity,
+        role: NeuronRole::Hidden,
+        bias: 0.0,
     });
     store.append(&event).unwrap();
 
@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    application::{Query, QueryHandler, QueryResult},
+    infrastructure::projection::NetworkProjection,
+    Activation, Command, CommandHandler, DomainNetwork, Event, FileEventStore, Loss, NeuronRole,
+    Synapse, SynapseDisabled, SynapseEnabled, ToggleRandomSynapseCommand, ToggleRandomSynapseError,
+    ToggleRandomSynapseHandler, TrainingSample,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_toggle_{}.log", Uuid::new_v4()));
+    path
+}
+
+struct Chain {
+    handler: CommandHandler<FileEventStore>,
+    path: PathBuf,
+    input: Uuid,
+    output: Uuid,
+    direct: Uuid,
+    back: Uuid,
+}
+
+/// Input → output with a second synapse output → input closing a cycle.
+fn chain() -> Chain {
+    let path = temp_path();
+    let mut handler = CommandHandler::new(FileEventStore::new(path.clone())).unwrap();
+    let (input, output, direct, back) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+    for command in [
+        Command::CreateNeuron {
+            id: input,
+            activation: Activation::Identity,
+            role: NeuronRole::Input,
+            bias: 0.0,
+        },
+        Command::CreateNeuron {
+            id: output,
+            activation: Activation::Identity,
+            role: NeuronRole::Output,
+            bias: 0.0,
+        },
+        Command::CreateSynapse {
+            id: direct,
+            from: input,
+            to: output,
+            weight: 3.0,
+        },
+        Command::CreateSynapse {
+            id: back,
+            from: output,
+            to: input,
+            weight: 0.5,
+        },
+    ] {
+        handler.handle(command).unwrap();
+    }
+    Chain {
+        handler,
+        path,
+        input,
+        output,
+        direct,
+        back,
+    }
+}
+
+#[test]
+fn disabled_synapses_are_skipped_but_kept() {
+    let mut c = chain();
+    assert!(c
+        .handler
+        .network
+        .propagate(&[(c.input, 1.0)], &[c.output])
+        .is_err());
+
+    c.handler
+        .handle(Command::DisableSynapse { id: c.back })
+        .unwrap();
+    let net = &mut c.handler.network;
+    assert_eq!(
+        net.propagate(&[(c.input, 2.0)], &[c.output]).unwrap(),
+        vec![6.0]
+    );
+    assert!(!net.synapses[&c.back].enabled);
+    assert_eq!(net.synapses[&c.back].weight, 0.5);
+
+    c.handler
+        .handle(Command::DisableSynapse { id: c.direct })
+        .unwrap();
+    let net = &mut c.handler.network;
+    assert_eq!(
+        net.propagate(&[(c.input, 2.0)], &[c.output]).unwrap(),
+        vec![0.0]
+    );
+
+    c.handler
+        .handle(Command::EnableSynapse { id: c.direct })
+        .unwrap();
+    let net = &mut c.handler.network;
+    assert_eq!(
+        net.propagate(&[(c.input, 2.0)], &[c.output]).unwrap(),
+        vec![6.0]
+    );
+
+    let events = FileEventStore::new(c.path).load().unwrap();
+    assert!(matches!(
+        &events[4],
+        Event::SynapseDisabled(SynapseDisabled { synapse_id }) if *synapse_id == c.back
+    ));
+    assert!(matches!(
+        events.last().unwrap(),
+        Event::SynapseEnabled(SynapseEnabled { synapse_id }) if *synapse_id == c.direct
+    ));
+
+    let projection = NetworkProjection::from_events(&events);
+    let queries = QueryHandler::new(&projection);
+    match queries.handle(Query::GetSynapse { id: c.back }) {
+        QueryResult::Synapse(Some(synapse)) => assert!(!synapse.enabled),
+        _ => panic!("disabled synapse missing from projection"),
+    }
+    assert_eq!(projection.synapses().len(), 2);
+}
+
+#[test]
+fn training_ignores_disabled_synapses() {
+    let mut c = chain();
+    c.handler
+        .handle(Command::DisableSynapse { id: c.back })
+        .unwrap();
+    let sample = TrainingSample {
+        inputs: vec![(c.input, 1.0)],
+        targets: vec![(c.output, 0.0)],
+    };
+    let gradients = c
+        .handler
+        .network
+        .batch_gradients(&[sample], Loss::MeanSquaredError)
+        .unwrap();
+    assert_eq!(
+        gradients.weights.keys().collect::<Vec<_>>(),
+        vec![&c.direct]
+    );
+}
+
+#[test]
+fn toggle_random_synapse_flips_flag() {
+    let c = chain();
+    let rng = ChaCha8Rng::seed_from_u64(31);
+    let mut handler = ToggleRandomSynapseHandler::new(c.handler.store, rng).unwrap();
+    let first = handler.handle(ToggleRandomSynapseCommand).unwrap();
+    assert!(!handler.base.network.synapses[&first].enabled);
+
+    let mut toggled_back = false;
+    for _ in 0..20 {
+        let id = handler.handle(ToggleRandomSynapseCommand).unwrap();
+        let replayed = DomainNetwork::hydrate(&handler.base.store.load().unwrap());
+        assert_eq!(
+            replayed.synapses[&id].enabled,
+            handler.base.network.synapses[&id].enabled
+        );
+        toggled_back |= id == first && handler.base.network.synapses[&id].enabled;
+    }
+    assert!(toggled_back);
+}
+
+#[test]
+fn toggle_random_synapse_requires_a_synapse() {
+    let rng = ChaCha8Rng::seed_from_u64(32);
+    let mut handler =
+        ToggleRandomSynapseHandler::new(FileEventStore::new(temp_path()), rng).unwrap();
+    assert_eq!(
+        handler.handle(ToggleRandomSynapseCommand),
+        Err(ToggleRandomSynapseError::NoSynapseAvailable)
+    );
+}
+
+#[test]
+fn synapses_serialized_without_flag_are_enabled() {
+    let id = Uuid::new_v4();
+    let json =
+        format!(r#"{{"id":"{id}","from":"{id}","to":"{id}","weight":1.0,"curiosity_score":0.0}}"#);
+    let synapse: Synapse = serde_json::from_str(&json).unwrap();
+    assert!(synapse.enabled);
+}
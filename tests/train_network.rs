@@ -0,0 +1,280 @@
+use std::path::PathBuf;
+
+use aei_framework::{
+    Activation, DomainNetwork, Event, FileEventStore, Loss, NeuronAdded, NeuronRole,
+    OptimizerConfig, PropagationError, TrainNetworkCommand, TrainNetworkError, TrainNetworkHandler,
+    TrainingSample, TrainingStepCompleted,
+};
+use uuid::Uuid;
+
+fn temp_path() -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("aei_train_{}.log", Uuid::new_v4()));
+    path
+}
+
+struct Fixture {
+    events: Vec<Event>,
+    inputs: [Uuid; 2],
+    hidden: Uuid,
+    output: Uuid,
+}
+
+/// Two inputs and a bias feeding a tanh hidden neuron and a sigmoid output.
+fn fixture() -> Fixture {
+    let (a, b, bias, hidden, output) = (
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+    );
+    let neuron = |id, activation, role| {
+        Event::NeuronAdded(NeuronAdded {
+            neuron_id: id,
+            activation,
+            role,
+            bias: 0.0,
+        })
+    };
+    let synapse = |from, to, weight| Event::SynapseCreated {
+        id: Uuid::new_v4(),
+        from,
+        to,
+        weight,
+        innovation: None,
+    };
+    let events = vec![
+        neuron(a, Activation::Identity, NeuronRole::Input),
+        neuron(b, Activation::Identity, NeuronRole::Input),
+        neuron(bias, Activation::Identity, NeuronRole::Bias),
+        neuron(hidden, Activation::Tanh, NeuronRole::Hidden),
+        neuron(output, Activation::Sigmoid, NeuronRole::Output),
+        synapse(a, hidden, 0.4),
+        synapse(b, hidden, -0.6),
+        synapse(bias, hidden, 0.1),
+        synapse(a, output, 0.3),
+        synapse(hidden, output, 0.8),
+        synapse(bias, output, -0.2),
+    ];
+    Fixture {
+        events,
+        inputs: [a, b],
+        hidden,
+        output,
+    }
+}
+
+fn samples(f: &Fixture) -> Vec<TrainingSample> {
+    [
+        (0.0, 1.0, 1.0),
+        (1.0, 0.0, 1.0),
+        (1.0, 1.0, 0.0),
+        (0.0, 0.0, 0.0),
+    ]
+    .into_iter()
+    .map(|(x, y, t)| TrainingSample {
+        inputs: vec![(f.inputs[0], x), (f.inputs[1], y)],
+        targets: vec![(f.output, t)],
+    })
+    .collect()
+}
+
+/// Central finite difference of the batch loss after nudging one parameter.
+fn numeric_gradient(
+    net: &DomainNetwork,
+    samples: &[TrainingSample],
+    nudge: impl Fn(&mut DomainNetwork, f64),
+) -> f64 {
+    let eps = 1e-6;
+    let loss = |delta| {
+        let mut net = net.clone();
+        nudge(&mut net, delta);
+        net.batch_gradients(samples, Loss::MeanSquaredError)
+            .unwrap()
+            .loss
+    };
+    (loss(eps) - loss(-eps)) / (2.0 * eps)
+}
+
+#[test]
+fn gradients_match_finite_differences() {
+    let f = fixture();
+    let samples = samples(&f);
+    let mut net = DomainNetwork::hydrate(&f.events);
+    net.neurons.get_mut(&f.hidden).unwrap().bias = 0.05;
+    net.neurons.get_mut(&f.output).unwrap().bias = -0.1;
+    let gradients = net
+        .batch_gradients(&samples, Loss::MeanSquaredError)
+        .unwrap();
+    assert_eq!(gradients.weights.len(), net.synapses.len());
+    assert_eq!(gradients.biases.len(), net.neurons.len());
+
+    for (id, gradient) in &gradients.weights {
+        let numeric = numeric_gradient(&net, &samples, |n, d| {
+            n.synapses.get_mut(id).unwrap().weight += d
+        });
+        assert!(
+            (numeric - gradient).abs() < 1e-6,
+            "synapse {id}: numeric {numeric} vs analytic {gradient}"
+        );
+    }
+    for (id, gradient) in &gradients.biases {
+        let numeric = numeric_gradient(&net, &samples, |n, d| {
+            n.neurons.get_mut(id).unwrap().bias += d
+        });
+        assert!(
+            (numeric - gradient).abs() < 1e-6,
+            "neuron {id}: numeric {numeric} vs analytic {gradient}"
+        );
+    }
+    for input in f.inputs {
+        assert_eq!(gradients.biases[&input], 0.0);
+    }
+}
+
+#[test]
+fn training_reduces_loss_and_replays_from_log() {
+    let f = fixture();
+    let (path, store) = seeded_store(&f);
+    let mut handler = TrainNetworkHandler::new(store).unwrap();
+    let losses = handler
+        .handle(TrainNetworkCommand {
+            samples: samples(&f),
+            epochs: 200,
+            loss: Loss::MeanSquaredError,
+            optimizer: OptimizerConfig::sgd(0.5),
+        })
+        .unwrap();
+    assert_eq!(losses.len(), 200);
+    assert!(losses[199] < losses[0]);
+    assert_eq!(handler.steps, 200);
+
+    let mut store = FileEventStore::new(path.clone());
+    let events = store.load().unwrap();
+    let steps: Vec<&TrainingStepCompleted> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::TrainingStepCompleted(step) => Some(step),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(steps.len(), 200);
+    assert_eq!(steps[0].step, 1);
+    assert_eq!(steps[199].step, 200);
+    assert_eq!(steps[0].loss, losses[0]);
+
+    let replayed = DomainNetwork::hydrate(&events);
+    for (id, synapse) in &handler.network.synapses {
+        assert_eq!(replayed.synapses[id].weight, synapse.weight);
+    }
+
+    let resumed = TrainNetworkHandler::new(FileEventStore::new(path)).unwrap();
+    assert_eq!(resumed.steps, 200);
+}
+
+fn seeded_store(f: &Fixture) -> (PathBuf, FileEventStore) {
+    let path = temp_path();
+    let mut store = FileEventStore::new(path.clone());
+    for event in &f.events {
+        store.append(event).unwrap();
+    }
+    (path, store)
+}
+
+#[test]
+fn interrupted_training_resumes_optimizer_state() {
+    let f = fixture();
+    for optimizer in [
+        OptimizerConfig::Sgd {
+            learning_rate: 0.1,
+            momentum: 0.9,
+        },
+        OptimizerConfig::rms_prop(0.01),
+        OptimizerConfig::adam(0.05),
+    ] {
+        let command = |epochs| TrainNetworkCommand {
+            samples: samples(&f),
+            epochs,
+            loss: Loss::MeanSquaredError,
+            optimizer,
+        };
+
+        let (_, store) = seeded_store(&f);
+        let mut uninterrupted = TrainNetworkHandler::new(store).unwrap();
+        let expected = uninterrupted.handle(command(10)).unwrap();
+
+        let (path, store) = seeded_store(&f);
+        let mut first = TrainNetworkHandler::new(store).unwrap();
+        let mut losses = first.handle(command(5)).unwrap();
+        drop(first);
+        let mut resumed = TrainNetworkHandler::new(FileEventStore::new(path)).unwrap();
+        losses.extend(resumed.handle(command(5)).unwrap());
+
+        assert_eq!(losses, expected, "{optimizer:?}");
+        for (id, synapse) in &uninterrupted.network.synapses {
+            assert_eq!(resumed.network.synapses[id].weight, synapse.weight);
+        }
+    }
+}
+
+#[test]
+fn training_rejects_invalid_commands() {
+    let f = fixture();
+    let (_, store) = seeded_store(&f);
+    let mut handler = TrainNetworkHandler::new(store).unwrap();
+
+    assert_eq!(
+        handler.handle(TrainNetworkCommand {
+            samples: vec![],
+            epochs: 1,
+            loss: Loss::MeanSquaredError,
+            optimizer: OptimizerConfig::sgd(0.1),
+        }),
+        Err(TrainNetworkError::NoSamples)
+    );
+    for optimizer in [
+        OptimizerConfig::sgd(0.0),
+        OptimizerConfig::Sgd {
+            learning_rate: 0.1,
+            momentum: 1.0,
+        },
+        OptimizerConfig::Adam {
+            learning_rate: 0.1,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 0.0,
+        },
+    ] {
+        assert_eq!(
+            handler.handle(TrainNetworkCommand {
+                samples: samples(&f),
+                epochs: 1,
+                loss: Loss::MeanSquaredError,
+                optimizer,
+            }),
+            Err(TrainNetworkError::InvalidOptimizerConfig)
+        );
+    }
+
+    let cycle = Event::SynapseCreated {
+        id: Uuid::new_v4(),
+        from: f.output,
+        to: f.hidden,
+        weight: 1.0,
+        innovation: None,
+    };
+    handler.store.append(&cycle).unwrap();
+    handler.network.apply(&cycle);
+    assert_eq!(
+        handler.handle(TrainNetworkCommand {
+            samples: samples(&f),
+            epochs: 1,
+            loss: Loss::MeanSquaredError,
+            optimizer: OptimizerConfig::sgd(0.1),
+        }),
+        Err(TrainNetworkError::Propagation(
+            PropagationError::CycleDetected
+        ))
+    );
+}